@@ -0,0 +1,44 @@
+//! ABC 2.1 notation.
+//!
+//! The reader covers the header fields `X:`, `T:`, `M:`, `L:` and `K:`; notes
+//! with accidentals (including the `^/` and `_/` quarter-tone forms), octave
+//! marks and lengths relative to the unit note length; broken rhythms; rests
+//! (`z`, and `x` as spacers); chords in brackets; tuplets such as `(3`; ties;
+//! bar lines; and voices introduced with `V:`. Each voice becomes a `Staff`
+//! holding one `Voice`. Bars that fill the current meter become `Measure`s;
+//! incomplete bars, such as pickups, are kept unmeasured. Decorations, chord
+//! symbols, grace notes and lyrics lines are skipped.
+//!
+//! The writer produces one tune with `L:1/8` and `K:C`, spelling every
+//! accidental explicitly.
+
+mod reader;
+mod writer;
+
+pub use reader::{read_abc, read_abc_tunes};
+pub use writer::write_abc;
+
+use crate::{pitch::Accidental, score_object::BarLine};
+
+const ACCIDENTALS: [(Accidental, &str); 9] = [
+    (Accidental::DoubleSharp, "^^"),
+    (Accidental::ThreeQuarterSharp, "^3/2"),
+    (Accidental::QuarterSharp, "^/"),
+    (Accidental::Sharp, "^"),
+    (Accidental::DoubleFlat, "__"),
+    (Accidental::ThreeQuarterFlat, "_3/2"),
+    (Accidental::QuarterFlat, "_/"),
+    (Accidental::Flat, "_"),
+    (Accidental::Natural, "="),
+];
+
+const BAR_LINES: [(BarLine, &str); 8] = [
+    (BarLine::DoubleRepeat, ":|:"),
+    (BarLine::DoubleRepeat, "::"),
+    (BarLine::EndRepeat, ":|"),
+    (BarLine::StartRepeat, "|:"),
+    (BarLine::Final, "|]"),
+    (BarLine::Double, "||"),
+    (BarLine::Double, "[|"),
+    (BarLine::Single, "|"),
+];
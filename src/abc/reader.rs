@@ -0,0 +1,736 @@
+use std::collections::HashMap;
+
+use super::{ACCIDENTALS, BAR_LINES};
+use crate::{
+    duration::Duration,
+    error::Error,
+    has_semitones::HasSemitones,
+    multiplier::Multiplier,
+    pitch::{Accidental, DiatonicPitchClass, KeySignature, Mode, Pitch, PitchClass},
+    score_object::{chord, measure, note, rest, tuplet, BarLine, ScoreBuilder, ScoreObject},
+    time_signature::TimeSignature,
+};
+
+/// The longest multi-measure rest read, in bars.
+const MAX_REST_BARS: i32 = 1024;
+
+/// Reads every tune in an ABC file, each starting at an `X:` field.
+pub fn read_abc_tunes(source: &str) -> Result<Vec<ScoreObject>, Error> {
+    let mut tunes: Vec<Vec<&str>> = vec![];
+    for line in source.lines() {
+        if line.starts_with("X:") {
+            tunes.push(vec![]);
+        }
+        if let Some(tune) = tunes.last_mut() {
+            tune.push(line);
+        }
+    }
+    tunes
+        .iter()
+        .map(|lines| read_abc(&lines.join("\n")))
+        .collect()
+}
+
+/// Reads a single ABC tune as a `Score`.
+pub fn read_abc(source: &str) -> Result<ScoreObject, Error> {
+    let mut reader = Reader::new();
+    for line in source.lines() {
+        let line = match line.find('%') {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+        let line = line.trim_end();
+        let bytes = line.as_bytes();
+        if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+            reader.field(bytes[0] as char, line[2..].trim())?;
+        } else if !line.trim().is_empty() {
+            if reader.in_header {
+                return Err(parse_error("music before the K: field"));
+            }
+            reader.body(line)?;
+        }
+    }
+    reader.finish()
+}
+
+struct TupletState {
+    multiplier: Multiplier,
+    remaining: usize,
+    contents: Vec<ScoreObject>,
+}
+
+struct VoiceState {
+    id: String,
+    name: Option<String>,
+    components: Vec<ScoreObject>,
+    bar: Vec<ScoreObject>,
+    tuplet: Option<TupletState>,
+    accidentals: HashMap<(DiatonicPitchClass, i32), Accidental>,
+    broken: Option<Duration>,
+}
+
+impl VoiceState {
+    fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: None,
+            components: vec![],
+            bar: vec![],
+            tuplet: None,
+            accidentals: HashMap::new(),
+            broken: None,
+        }
+    }
+
+    fn open_leaves(&mut self) -> &mut Vec<ScoreObject> {
+        match &mut self.tuplet {
+            Some(tuplet) => &mut tuplet.contents,
+            None => &mut self.bar,
+        }
+    }
+
+    fn last_leaf_mut(&mut self) -> Option<&mut ScoreObject> {
+        let mut last = self.open_leaves().last_mut()?;
+        while last.is_container() {
+            last = last.contents_mut()?.last_mut()?;
+        }
+        Some(last)
+    }
+
+    fn push(&mut self, mut leaf: ScoreObject) -> Result<(), Error> {
+        if let Some(factor) = self.broken.take() {
+            scale(&mut leaf, factor)?;
+        }
+        match &mut self.tuplet {
+            Some(state) => {
+                state.contents.push(leaf);
+                state.remaining -= 1;
+                if state.remaining == 0 {
+                    let state = self.tuplet.take().unwrap();
+                    self.bar.push(tuplet(state.multiplier, state.contents)?);
+                }
+            }
+            None => self.bar.push(leaf),
+        }
+        Ok(())
+    }
+
+    fn close_bar(
+        &mut self,
+        bar_line: BarLine,
+        time_signature: Option<TimeSignature>,
+    ) -> Result<(), Error> {
+        if self.tuplet.is_some() {
+            return Err(parse_error("tuplet interrupted by a bar line"));
+        }
+        self.accidentals.clear();
+        let contents = std::mem::take(&mut self.bar);
+        if contents.is_empty() {
+            if let Some(last @ ScoreObject::Measure { .. }) = self.components.last_mut() {
+                last.set_bar_line(bar_line);
+            }
+            return Ok(());
+        }
+        let duration: Duration = contents
+            .iter()
+            .fold(Duration::new(0, 1), |sum, c| sum + c.duration());
+        match time_signature {
+            Some(time_signature) if time_signature.duration() == duration => {
+                let mut m = measure(time_signature, contents)?;
+                m.set_bar_line(bar_line);
+                self.components.push(m);
+            }
+            _ => self.components.extend(contents),
+        }
+        Ok(())
+    }
+}
+
+struct Reader {
+    in_header: bool,
+    title: Option<String>,
+    time_signature: Option<TimeSignature>,
+    unit: Duration,
+    key: HashMap<DiatonicPitchClass, Accidental>,
+    voices: Vec<VoiceState>,
+    current: usize,
+}
+
+impl Reader {
+    fn new() -> Self {
+        Self {
+            in_header: true,
+            title: None,
+            time_signature: None,
+            unit: Duration::new(1, 8),
+            key: HashMap::new(),
+            voices: vec![],
+            current: 0,
+        }
+    }
+
+    fn field(&mut self, field: char, value: &str) -> Result<(), Error> {
+        match field {
+            'T' if self.title.is_none() => self.title = Some(value.to_string()),
+            'M' => {
+                self.time_signature = match value {
+                    "C" => Some(TimeSignature::new(4, 4)),
+                    "C|" => Some(TimeSignature::new(2, 2)),
+                    "none" | "" => None,
+                    _ => Some(value.parse().map_err(|_| parse_error(value))?),
+                }
+            }
+            'L' => self.unit = value.parse().map_err(|_| parse_error(value))?,
+            'K' => {
+                self.key = parse_key(value)?;
+                self.in_header = false;
+            }
+            'V' => self.select_voice(value),
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn select_voice(&mut self, value: &str) {
+        let id = value.split_whitespace().next().unwrap_or("1");
+        self.current = match self.voices.iter().position(|v| v.id == id) {
+            Some(index) => index,
+            None => {
+                self.voices.push(VoiceState::new(id));
+                self.voices.len() - 1
+            }
+        };
+        for key in ["name=\"", "nm=\""] {
+            if let Some(start) = value.find(key) {
+                let rest = &value[start + key.len()..];
+                let name = rest.split('"').next().unwrap_or_default();
+                self.voices[self.current].name = Some(name.to_string());
+            }
+        }
+    }
+
+    fn voice(&mut self) -> &mut VoiceState {
+        if self.voices.is_empty() {
+            self.voices.push(VoiceState::new("1"));
+        }
+        &mut self.voices[self.current]
+    }
+
+    fn body(&mut self, line: &str) -> Result<(), Error> {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                ' ' | '\t' | '`' | '\\' | 'y' | ')' => i += 1,
+                '"' | '!' | '+' => i = skip_past(&chars, i + 1, c)?,
+                '{' => i = skip_past(&chars, i + 1, '}')?,
+                '.' | '~' | 'H' | 'L' | 'M' | 'O' | 'P' | 'S' | 'T' | 'u' | 'v' => i += 1,
+                '(' if chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) => {
+                    i = self.tuplet(&chars, i + 1)?
+                }
+                '(' => i += 1,
+                '[' if chars.get(i + 2) == Some(&':') => {
+                    let end = skip_past(&chars, i + 1, ']')?;
+                    let value: String = chars[i + 3..end - 1].iter().collect();
+                    self.field(chars[i + 1], value.trim())?;
+                    i = end;
+                }
+                '[' if chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) => i += 1,
+                '[' if chars.get(i + 1) != Some(&'|') => i = self.chord(&chars, i + 1)?,
+                '|' | ':' | '[' => i = self.bar_line(&chars, i)?,
+                '-' => {
+                    let leaf = self.voice().last_leaf_mut();
+                    leaf.ok_or_else(|| parse_error("tie without a note"))?
+                        .set_is_tied(true);
+                    i += 1;
+                }
+                '>' | '<' => {
+                    let count = chars[i..].iter().take_while(|d| **d == c).count();
+                    let denominator = u32::try_from(count)
+                        .ok()
+                        .and_then(|count| 1i32.checked_shl(count))
+                        .filter(|denominator| *denominator > 0)
+                        .ok_or_else(|| parse_error("broken rhythm too long"))?;
+                    let short = Duration::new(1, denominator);
+                    let long = denominator
+                        .checked_mul(2)
+                        .map(|twice| Duration::new(twice - 1, denominator))
+                        .ok_or_else(|| parse_error("broken rhythm too long"))?;
+                    let (before, after) = if c == '>' {
+                        (long, short)
+                    } else {
+                        (short, long)
+                    };
+                    let leaf = self.voice().last_leaf_mut();
+                    scale(
+                        leaf.ok_or_else(|| parse_error("broken rhythm without a note"))?,
+                        before,
+                    )?;
+                    self.voice().broken = Some(after);
+                    i += count;
+                }
+                'z' | 'x' | 'Z' => {
+                    let (length, end) = parse_length(&chars, i + 1)?;
+                    i = end;
+                    self.rest(c, length)?;
+                }
+                '^' | '_' | '=' | 'A'..='G' | 'a'..='g' => {
+                    let (pitch, length, end) = self.note(&chars, i)?;
+                    i = end;
+                    let leaf = note(pitch, product(self.unit, length)?)?;
+                    self.voice().push(leaf)?;
+                }
+                _ => return Err(parse_error(format!("unexpected `{c}`"))),
+            }
+        }
+        Ok(())
+    }
+
+    fn rest(&mut self, c: char, length: Duration) -> Result<(), Error> {
+        if c != 'Z' {
+            let written_duration = product(self.unit, length)?;
+            let leaf = match c {
+                'z' => rest(written_duration)?,
+                _ if written_duration.is_printable() => ScoreObject::Spacer { written_duration },
+                _ => return Err(Error::UnprintableDuration(written_duration)),
+            };
+            return self.voice().push(leaf);
+        }
+        let time_signature = self
+            .time_signature
+            .ok_or_else(|| parse_error("multi-measure rest without a meter"))?;
+        let (bars, _) = length.to_pair();
+        if bars > MAX_REST_BARS {
+            return Err(parse_error(format!("multi-measure rest of {bars} bars")));
+        }
+        for _ in 0..bars {
+            let leaf = rest(time_signature.duration())?;
+            self.voice().push(leaf)?;
+            self.voice()
+                .close_bar(BarLine::Single, Some(time_signature))?;
+        }
+        Ok(())
+    }
+
+    fn tuplet(&mut self, chars: &[char], mut i: usize) -> Result<usize, Error> {
+        let mut numbers = vec![];
+        loop {
+            numbers.push(number(chars, &mut i)?);
+            if chars.get(i) == Some(&':') && numbers.len() < 3 {
+                i += 1;
+            } else {
+                break;
+            }
+        }
+        let p = numbers[0]
+            .filter(|p| *p > 1)
+            .ok_or_else(|| parse_error("bad tuplet"))?;
+        let default_q = match p {
+            3 | 6 => 2,
+            2 | 4 | 8 => 3,
+            _ => 2,
+        };
+        let q = numbers.get(1).copied().flatten().unwrap_or(default_q);
+        if q == 0 {
+            return Err(parse_error("bad tuplet"));
+        }
+        let r = numbers.get(2).copied().flatten().unwrap_or(p);
+        self.voice().tuplet = Some(TupletState {
+            multiplier: Multiplier::new(q, p),
+            remaining: r.max(1) as usize,
+            contents: vec![],
+        });
+        Ok(i)
+    }
+
+    fn chord(&mut self, chars: &[char], mut i: usize) -> Result<usize, Error> {
+        let mut pitches = vec![];
+        let mut inner = None;
+        loop {
+            match chars.get(i) {
+                Some(']') => break,
+                Some(' ') | Some('-') => i += 1,
+                Some(_) => {
+                    let (pitch, length, end) = self.note(chars, i)?;
+                    pitches.push(pitch);
+                    inner.get_or_insert(length);
+                    i = end;
+                }
+                None => return Err(parse_error("unterminated chord")),
+            }
+        }
+        let (outer, end) = parse_length(chars, i + 1)?;
+        let duration = product(
+            product(self.unit, inner.unwrap_or(Duration::new(1, 1)))?,
+            outer,
+        )?;
+        let leaf = chord(pitches, duration)?;
+        self.voice().push(leaf)?;
+        Ok(end)
+    }
+
+    fn bar_line(&mut self, chars: &[char], i: usize) -> Result<usize, Error> {
+        let rest: String = chars[i..].iter().take(3).collect();
+        let (bar_line, symbol) = BAR_LINES
+            .iter()
+            .find(|(_, symbol)| rest.starts_with(symbol))
+            .ok_or_else(|| parse_error(format!("unexpected `{}`", chars[i])))?;
+        let time_signature = self.time_signature;
+        self.voice().close_bar(*bar_line, time_signature)?;
+        let mut end = i + symbol.len();
+        while chars.get(end).is_some_and(|c| c.is_ascii_digit()) {
+            end += 1;
+        }
+        Ok(end)
+    }
+
+    /// Parses an accidental, letter, octave marks and length, resolving the
+    /// accidental against the bar and the key signature.
+    fn note(&mut self, chars: &[char], mut i: usize) -> Result<(Pitch, Duration, usize), Error> {
+        let rest: String = chars[i..].iter().take(4).collect();
+        let explicit = ACCIDENTALS
+            .iter()
+            .find(|(_, symbol)| rest.starts_with(symbol));
+        if let Some((_, symbol)) = explicit {
+            i += symbol.len();
+        }
+        let letter = chars
+            .get(i)
+            .copied()
+            .ok_or_else(|| parse_error("accidental without a note"))?;
+        let diatonic_pitch_class: DiatonicPitchClass = letter
+            .to_ascii_lowercase()
+            .to_string()
+            .parse()
+            .map_err(|_| parse_error(format!("unexpected `{letter}`")))?;
+        let mut octave = if letter.is_ascii_uppercase() { 4 } else { 5 };
+        i += 1;
+        while let Some(mark) = chars.get(i).filter(|c| **c == '\'' || **c == ',') {
+            octave += if *mark == '\'' { 1 } else { -1 };
+            i += 1;
+        }
+        let (length, end) = parse_length(chars, i)?;
+
+        let key = self.key.get(&diatonic_pitch_class).copied();
+        let voice = self.voice();
+        let accidental = match explicit {
+            Some((accidental, _)) => {
+                voice
+                    .accidentals
+                    .insert((diatonic_pitch_class, octave), *accidental);
+                *accidental
+            }
+            None => voice
+                .accidentals
+                .get(&(diatonic_pitch_class, octave))
+                .copied()
+                .or(key)
+                .unwrap_or(Accidental::Natural),
+        };
+        let pitch = Pitch::new(PitchClass::new(diatonic_pitch_class, accidental), octave);
+        Ok((pitch, length, end))
+    }
+
+    fn finish(mut self) -> Result<ScoreObject, Error> {
+        let time_signature = self.time_signature;
+        for voice in self.voices.iter_mut() {
+            if !voice.bar.is_empty() || voice.tuplet.is_some() {
+                voice.close_bar(BarLine::Single, time_signature)?;
+            }
+        }
+        let mut builder = ScoreBuilder::new();
+        if let Some(title) = self.title {
+            builder = builder.name(title);
+        }
+        for voice in self.voices {
+            let name = voice.name.unwrap_or(voice.id);
+            let components = voice.components;
+            builder = builder.staff(name.clone(), |s| s.voice(name, |v| v.extend(components)));
+        }
+        builder.build()
+    }
+}
+
+/// Parses a length multiplier such as `2`, `3/2`, `/` or `//`.
+fn parse_length(chars: &[char], mut i: usize) -> Result<(Duration, usize), Error> {
+    let numerator = number(chars, &mut i)?.unwrap_or(1);
+    let mut denominator: i32 = 1;
+    while chars.get(i) == Some(&'/') {
+        i += 1;
+        denominator = number(chars, &mut i)?
+            .unwrap_or(2)
+            .checked_mul(denominator)
+            .ok_or_else(|| parse_error("length too short"))?;
+    }
+    if numerator == 0 {
+        return Err(parse_error("zero length"));
+    }
+    Ok((Duration::new(numerator, denominator), i))
+}
+
+/// Reads the run of digits at `i`, if any, and moves past it.
+fn number(chars: &[char], i: &mut usize) -> Result<Option<i32>, Error> {
+    let start = *i;
+    while chars.get(*i).is_some_and(|c| c.is_ascii_digit()) {
+        *i += 1;
+    }
+    if start == *i {
+        return Ok(None);
+    }
+    let digits: String = chars[start..*i].iter().collect();
+    digits
+        .parse()
+        .map(Some)
+        .map_err(|_| parse_error(format!("number `{digits}` too large")))
+}
+
+fn skip_past(chars: &[char], start: usize, delimiter: char) -> Result<usize, Error> {
+    chars[start..]
+        .iter()
+        .position(|c| *c == delimiter)
+        .map(|offset| start + offset + 1)
+        .ok_or_else(|| parse_error(format!("missing closing `{delimiter}`")))
+}
+
+/// `a * b`, or an error where the fraction would overflow.
+fn product(a: Duration, b: Duration) -> Result<Duration, Error> {
+    let ((n, d), (m, e)) = (a.to_pair(), b.to_pair());
+    match (n.checked_mul(m), d.checked_mul(e)) {
+        (Some(numerator), Some(denominator)) => Ok(Duration::new(numerator, denominator)),
+        _ => Err(parse_error("length out of range")),
+    }
+}
+
+fn scale(leaf: &mut ScoreObject, factor: Duration) -> Result<(), Error> {
+    let duration = product(
+        leaf.written_duration().unwrap_or(Duration::new(0, 1)),
+        factor,
+    )?;
+    if !duration.is_printable() {
+        return Err(Error::UnprintableDuration(duration));
+    }
+    leaf.set_written_duration(duration);
+    Ok(())
+}
+
+/// The key signature's accidental for each letter. Modes other than major and
+/// minor are read through their relative major.
+fn parse_key(value: &str) -> Result<HashMap<DiatonicPitchClass, Accidental>, Error> {
+    let mut tokens = value.split_whitespace();
+    let Some(first) = tokens.next().filter(|t| !t.contains('=') && *t != "none") else {
+        return Ok(HashMap::new());
+    };
+    if first.eq_ignore_ascii_case("hp") {
+        return Ok(HashMap::new());
+    }
+    let mut chars = first.chars();
+    let letter: DiatonicPitchClass = chars
+        .next()
+        .map(|c| c.to_ascii_lowercase().to_string())
+        .unwrap_or_default()
+        .parse()
+        .map_err(|_| parse_error(format!("bad key `{value}`")))?;
+    let rest = chars.as_str();
+    let (accidental, mode) = match rest.chars().next() {
+        Some('#') => (Accidental::Sharp, &rest[1..]),
+        Some('b') => (Accidental::Flat, &rest[1..]),
+        _ => (Accidental::Natural, rest),
+    };
+    let mode = match (mode, tokens.next()) {
+        ("", Some(word)) if !word.contains('=') => word,
+        (mode, _) => mode,
+    }
+    .to_ascii_lowercase();
+    let degree = match mode.get(..3).unwrap_or(&mode) {
+        "" | "maj" | "ion" => 0,
+        "m" | "min" | "aeo" => 5,
+        "dor" => 1,
+        "phr" => 2,
+        "lyd" => 3,
+        "mix" => 4,
+        "loc" => 6,
+        _ => return Err(parse_error(format!("bad key `{value}`"))),
+    };
+
+    let tonic = PitchClass::new(letter, accidental);
+    let major_letter = DiatonicPitchClass::from_index(letter.index() - degree);
+    let offset = [0, 2, 4, 5, 7, 9, 11][degree as usize];
+    let alteration = (tonic.quarter_tones() / 2 - offset - major_letter.quarter_tones() / 2 + 6)
+        .rem_euclid(12)
+        - 6;
+    let major_accidental = Accidental::from_quarter_tones(alteration * 2)
+        .ok_or_else(|| parse_error(format!("bad key `{value}`")))?;
+    let major = KeySignature::new(PitchClass::new(major_letter, major_accidental), Mode::Major)
+        .map_err(|_| parse_error(format!("bad key `{value}`")))?;
+    Ok(major.scale().iter().map(|pc| pc.to_pair()).collect())
+}
+
+fn parse_error(message: impl Into<String>) -> Error {
+    Error::AbcParse(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_abc, read_abc_tunes};
+    use crate::duration::Duration;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{BarLine, ScoreObject};
+    use crate::time_signature::TimeSignature;
+
+    fn pitch_of(leaf: &ScoreObject) -> Pitch {
+        match leaf {
+            ScoreObject::Note { written_pitch, .. } => *written_pitch,
+            _ => panic!("not a note: {leaf:?}"),
+        }
+    }
+
+    #[test]
+    fn header_and_measures() {
+        let score = read_abc("X:1\nT:The Kesh\nM:6/8\nL:1/8\nK:G\n|:GAG GAB|ABA ABd:|\n").unwrap();
+        assert_eq!(score.name(), &Some("The Kesh".to_string()));
+        let voice = &score[0][0];
+        assert_eq!(voice.measures().len(), 2);
+        assert_eq!(voice[0].time_signature(), Some(&TimeSignature::new(6, 8)));
+        assert_eq!(voice[1].bar_line(), Some(&BarLine::EndRepeat));
+        assert_eq!(voice[0].leaves().len(), 6);
+    }
+
+    #[test]
+    fn pitches_and_accidentals() {
+        let score = read_abc("X:1\nK:D\nC, c c' ^G =F _B G | F __E ^/D _3/2a\n").unwrap();
+        let leaves = score.leaves();
+        let expected = [
+            (C, Sharp, 3),
+            (C, Sharp, 5),
+            (C, Sharp, 6),
+            (G, Sharp, 4),
+            (F, Natural, 4),
+            (B, Flat, 4),
+            (G, Sharp, 4),
+            (F, Sharp, 4),
+            (E, DoubleFlat, 4),
+            (D, QuarterSharp, 4),
+            (A, ThreeQuarterFlat, 5),
+        ];
+        assert_eq!(leaves.len(), expected.len());
+        for (leaf, (dpc, accidental, octave)) in leaves.iter().zip(expected) {
+            assert_eq!(
+                pitch_of(leaf),
+                Pitch::new(PitchClass::new(dpc, accidental), octave)
+            );
+        }
+    }
+
+    #[test]
+    fn modes() {
+        let dorian = read_abc("X:1\nK:E dorian\nC F\n").unwrap();
+        assert_eq!(
+            pitch_of(dorian.leaves()[0]).pitch_class(),
+            PitchClass::new(C, Sharp)
+        );
+        assert_eq!(
+            pitch_of(dorian.leaves()[1]).pitch_class(),
+            PitchClass::new(F, Sharp)
+        );
+
+        let minor = read_abc("X:1\nK:Bbm\nD G\n").unwrap();
+        assert_eq!(
+            pitch_of(minor.leaves()[0]).pitch_class(),
+            PitchClass::new(D, Flat)
+        );
+        assert_eq!(
+            pitch_of(minor.leaves()[1]).pitch_class(),
+            PitchClass::new(G, Flat)
+        );
+    }
+
+    #[test]
+    fn lengths_rests_chords_and_ties() {
+        let score =
+            read_abc("X:1\nL:1/4\nK:C\nA2 A/ A// A3/2 z x [CEG]2 [C/E/]- [CE]/ a>b\n").unwrap();
+        let durations: Vec<Duration> = score.leaves().iter().map(|l| l.duration()).collect();
+        assert_eq!(
+            durations,
+            vec![
+                Duration::new(1, 2),
+                Duration::new(1, 8),
+                Duration::new(1, 16),
+                Duration::new(3, 8),
+                Duration::new(1, 4),
+                Duration::new(1, 4),
+                Duration::new(1, 2),
+                Duration::new(1, 8),
+                Duration::new(1, 8),
+                Duration::new(3, 8),
+                Duration::new(1, 8),
+            ]
+        );
+        let leaves = score.leaves();
+        assert!(matches!(leaves[4], ScoreObject::Rest { .. }));
+        assert!(matches!(leaves[5], ScoreObject::Spacer { .. }));
+        assert!(matches!(leaves[6], ScoreObject::Chord { .. }));
+        assert!(leaves[7].is_tied());
+    }
+
+    #[test]
+    fn tuplets() {
+        let score = read_abc("X:1\nM:2/4\nL:1/8\nK:C\n(3abc d2 | (3:2:2a4b2 |\n").unwrap();
+        let voice = &score[0][0];
+        assert_eq!(voice.measures().len(), 2);
+        assert_eq!(voice[0][0].multiplier(), Some(&Multiplier::new(2, 3)));
+        assert_eq!(voice[0][0].leaves().len(), 3);
+        assert_eq!(voice[1][0].leaves().len(), 2);
+        assert!(read_abc("X:1\nK:C\n(3ab|c\n").is_err());
+    }
+
+    #[test]
+    fn voices_and_pickups() {
+        let score = read_abc(
+            "X:1\nM:3/4\nK:C\nV:1 name=\"Fiddle\"\nV:2\n[V:1] c | e2 g2 e2 |]\n[V:2] z | C6 |]\n",
+        )
+        .unwrap();
+        assert_eq!(score.contents().unwrap().len(), 2);
+        let fiddle = &score[0][0];
+        assert_eq!(fiddle.name(), &Some("Fiddle".to_string()));
+        assert!(matches!(fiddle[0], ScoreObject::Note { .. }));
+        assert_eq!(fiddle[1].bar_line(), Some(&BarLine::Final));
+        assert_eq!(score[1][0].name(), &Some("2".to_string()));
+        assert_eq!(score[1][0].measures().len(), 1);
+    }
+
+    #[test]
+    fn skips_decorations_and_reads_many_tunes() {
+        let tunes = read_abc_tunes(
+            "% archive\nX:1\nT:One\nK:C\n\"Am\"!trill!{g}A .B ~c % comment\nw: la la la\n\nX:2\nT:Two\nK:G\nF\n",
+        )
+        .unwrap();
+        assert_eq!(tunes.len(), 2);
+        assert_eq!(tunes[0].leaves().len(), 3);
+        assert_eq!(
+            pitch_of(tunes[1].leaves()[0]).pitch_class(),
+            PitchClass::new(F, Sharp)
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert!(read_abc("X:1\nabc\n").is_err());
+        assert!(read_abc("X:1\nK:C\na#\n").is_err());
+        assert!(read_abc("X:1\nK:C\n[ab\n").is_err());
+        assert!(read_abc("X:1\nK:C\na/3\n").is_err());
+        assert!(read_abc("X:1\nK:é\nC\n").is_err());
+        assert!(read_abc("X:1\nK:C\nC99999999999\n").is_err());
+        assert!(read_abc(&format!("X:1\nK:C\nC{}\n", "/".repeat(40))).is_err());
+        assert!(read_abc(&format!("X:1\nK:C\nC{}D\n", ">".repeat(40))).is_err());
+        assert!(read_abc("X:1\nK:C\n(3:0:3abc\n").is_err());
+        assert!(read_abc("X:1\nK:C\nc/1073741824\n").is_err());
+        assert!(read_abc("X:1\nL:1/1073741824\nK:C\nc/2\n").is_err());
+        assert!(read_abc("X:1\nL:2147483647/1\nK:C\nc2\n").is_err());
+        assert!(read_abc(&format!("X:1\nK:C\nc{}d\n", ">".repeat(30))).is_err());
+        // Unknown inline fields are skipped, whatever their letter.
+        assert!(read_abc("X:1\nK:C\n[€:x]c\n").is_ok());
+        assert!(read_abc("X:1\nM:4/4\nK:C\nZ2147483647\n").is_err());
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+
+use num::Integer;
+
+use super::{ACCIDENTALS, BAR_LINES};
+use crate::{
+    duration::Duration,
+    error::Error,
+    pitch::{Accidental, DiatonicPitchClass, Pitch},
+    score_object::ScoreObject,
+    time_signature::TimeSignature,
+};
+
+const BARS_PER_LINE: usize = 4;
+
+/// Writes `score` as a single ABC tune. Each `Voice` (or the whole score, if
+/// it has none) becomes a `V:` section; lyrics are written as spacers.
+pub fn write_abc(score: &ScoreObject) -> Result<String, Error> {
+    let time_signature = score
+        .measures()
+        .first()
+        .and_then(|m| m.time_signature())
+        .copied();
+
+    let mut lines = vec!["X:1".to_string()];
+    if let Some(name) = score.name() {
+        lines.push(format!("T:{name}"));
+    }
+    lines.push(match time_signature {
+        Some(time_signature) => format!("M:{time_signature}"),
+        None => "M:none".to_string(),
+    });
+    lines.push("L:1/8".to_string());
+    lines.push("K:C".to_string());
+
+    let voices = voices(score);
+    for (index, voice) in voices.iter().enumerate() {
+        let id = (index + 1).to_string();
+        match voice.name() {
+            Some(name) if *name != id => lines.push(format!("V:{id} name=\"{name}\"")),
+            _ if voices.len() > 1 => lines.push(format!("V:{id}")),
+            _ => (),
+        }
+        let mut writer = VoiceWriter::new(time_signature);
+        for component in voice.contents().into_iter().flatten() {
+            writer.component(component, false)?;
+        }
+        lines.extend(writer.finish());
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+fn voices(component: &ScoreObject) -> Vec<&ScoreObject> {
+    fn collect<'a>(component: &'a ScoreObject, voices: &mut Vec<&'a ScoreObject>) {
+        match component {
+            ScoreObject::Voice { .. } => voices.push(component),
+            _ => {
+                for child in component.contents().into_iter().flatten() {
+                    collect(child, voices);
+                }
+            }
+        }
+    }
+    let mut voices = vec![];
+    collect(component, &mut voices);
+    if voices.is_empty() && component.is_container() {
+        voices.push(component);
+    }
+    voices
+}
+
+struct VoiceWriter {
+    time_signature: Option<TimeSignature>,
+    accidentals: HashMap<(DiatonicPitchClass, i32), Accidental>,
+    prefix: String,
+    is_bar_open: bool,
+    tokens: Vec<String>,
+    lines: Vec<String>,
+    bars: usize,
+}
+
+impl VoiceWriter {
+    fn new(time_signature: Option<TimeSignature>) -> Self {
+        Self {
+            time_signature,
+            accidentals: HashMap::new(),
+            prefix: String::new(),
+            is_bar_open: false,
+            tokens: vec![],
+            lines: vec![],
+            bars: 0,
+        }
+    }
+
+    fn push(&mut self, token: String) {
+        self.is_bar_open = true;
+        let prefix = std::mem::take(&mut self.prefix);
+        self.tokens.push(prefix + &token);
+    }
+
+    fn component(&mut self, component: &ScoreObject, in_tuplet: bool) -> Result<(), Error> {
+        match component {
+            ScoreObject::Note {
+                written_pitch,
+                written_duration,
+                is_tied,
+            } => {
+                let token = self.pitch(written_pitch) + &length(*written_duration);
+                self.push(token + tie(*is_tied));
+            }
+            ScoreObject::Chord {
+                written_pitches,
+                written_duration,
+                is_tied,
+            } => {
+                let pitches: String = written_pitches.iter().map(|p| self.pitch(p)).collect();
+                let token = format!("[{pitches}]{}", length(*written_duration));
+                self.push(token + tie(*is_tied));
+            }
+            ScoreObject::Rest { written_duration } => {
+                self.push(format!("z{}", length(*written_duration)))
+            }
+            ScoreObject::Spacer { written_duration }
+            | ScoreObject::Lyric {
+                written_duration, ..
+            } => self.push(format!("x{}", length(*written_duration))),
+            ScoreObject::Tuplet {
+                multiplier,
+                contents,
+                ..
+            } => {
+                if in_tuplet {
+                    return Err(Error::AbcExport("nested tuplets".to_string()));
+                }
+                let (n, d) = multiplier.to_pair();
+                let (p, q) = (d / n.gcd(&d), n / n.gcd(&d));
+                let r = component.leaves().len() as i32;
+                let default_q = if matches!(p, 2 | 4 | 8) { 3 } else { 2 };
+                self.prefix = if q == default_q && r == p {
+                    format!("({p}")
+                } else {
+                    format!("({p}:{q}:{r}")
+                };
+                for child in contents.iter() {
+                    self.component(child, true)?;
+                }
+            }
+            ScoreObject::Measure {
+                time_signature,
+                bar_line,
+                contents,
+            } => {
+                if self.is_bar_open {
+                    self.tokens.push("|".to_string());
+                    self.accidentals.clear();
+                }
+                if self.time_signature != Some(*time_signature) {
+                    self.push(format!("[M:{time_signature}]"));
+                    self.time_signature = Some(*time_signature);
+                }
+                for child in contents.iter() {
+                    self.component(child, in_tuplet)?;
+                }
+                let (_, symbol) = BAR_LINES.iter().find(|(b, _)| b == bar_line).unwrap();
+                self.tokens.push(symbol.to_string());
+                self.accidentals.clear();
+                self.is_bar_open = false;
+                self.bars += 1;
+                if self.bars.is_multiple_of(BARS_PER_LINE) {
+                    self.lines.push(self.tokens.join(" "));
+                    self.tokens.clear();
+                }
+            }
+            _ if component.is_simultaneous() => {
+                return Err(Error::AbcExport(format!(
+                    "simultaneous {} inside a voice",
+                    component.kind_name()
+                )));
+            }
+            _ => {
+                for child in component.contents().into_iter().flatten() {
+                    self.component(child, in_tuplet)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Spells `pitch`, writing its accidental only when the bar so far does
+    /// not already imply it.
+    fn pitch(&mut self, pitch: &Pitch) -> String {
+        let (letter, accidental) = pitch.pitch_class().to_pair();
+        let octave = pitch.octave();
+        let current = self
+            .accidentals
+            .insert((letter, octave), accidental)
+            .unwrap_or(Accidental::Natural);
+        let mut token = String::new();
+        if current != accidental {
+            let (_, symbol) = ACCIDENTALS.iter().find(|(a, _)| *a == accidental).unwrap();
+            token.push_str(symbol);
+        }
+        let name = letter.to_string();
+        if octave >= 5 {
+            token.push_str(&name);
+            token.push_str(&"'".repeat((octave - 5) as usize));
+        } else {
+            token.push_str(&name.to_uppercase());
+            token.push_str(&",".repeat((4 - octave) as usize));
+        }
+        token
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        if !self.tokens.is_empty() {
+            self.lines.push(self.tokens.join(" "));
+        }
+        self.lines
+    }
+}
+
+/// The length of `duration` in unit notes of `1/8`.
+fn length(duration: Duration) -> String {
+    match (duration * 8).to_pair() {
+        (1, 1) => String::new(),
+        (n, 1) => n.to_string(),
+        (1, 2) => "/".to_string(),
+        (1, d) => format!("/{d}"),
+        (n, d) => format!("{n}/{d}"),
+    }
+}
+
+fn tie(is_tied: bool) -> &'static str {
+    if is_tied {
+        "-"
+    } else {
+        ""
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_abc;
+    use crate::abc::read_abc;
+    use crate::duration::Duration;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{measure, note, rest, tuplet, BarLine, ScoreBuilder};
+    use crate::time_signature::TimeSignature;
+
+    fn pitch(
+        dpc: crate::pitch::DiatonicPitchClass,
+        accidental: crate::pitch::Accidental,
+        octave: i32,
+    ) -> Pitch {
+        Pitch::new(PitchClass::new(dpc, accidental), octave)
+    }
+
+    #[test]
+    fn writes_header_and_notes() {
+        let mut second = measure(
+            TimeSignature::new(2, 4),
+            vec![
+                tuplet(
+                    Multiplier::new(2, 3),
+                    vec![
+                        note(pitch(F, Sharp, 5), Duration::new(1, 8)).unwrap(),
+                        note(pitch(F, Sharp, 5), Duration::new(1, 8)).unwrap(),
+                        note(pitch(F, Natural, 5), Duration::new(1, 8)).unwrap(),
+                    ],
+                )
+                .unwrap(),
+                rest(Duration::new(1, 4)).unwrap(),
+            ],
+        )
+        .unwrap();
+        second.set_bar_line(BarLine::Final);
+        let score = ScoreBuilder::new()
+            .name("Tune")
+            .staff("Staff", |s| {
+                s.voice("1", |v| {
+                    v.try_push(measure(
+                        TimeSignature::new(2, 4),
+                        vec![
+                            note(pitch(C, Natural, 3), Duration::new(3, 16)).unwrap(),
+                            note(pitch(B, Flat, 6), Duration::new(1, 16)).unwrap(),
+                            note(pitch(E, Natural, 4), Duration::new(1, 4)).unwrap(),
+                        ],
+                    ))
+                    .push(second)
+                })
+            })
+            .build()
+            .unwrap();
+        assert_eq!(
+            write_abc(&score).unwrap(),
+            "X:1\nT:Tune\nM:2/4\nL:1/8\nK:C\nC,3/2 _b'/ E2 | (3^f f =f z2 |]\n"
+        );
+    }
+
+    #[test]
+    fn nested_tuplets_are_rejected() {
+        let inner = tuplet(
+            Multiplier::new(2, 3),
+            vec![note(pitch(C, Natural, 4), Duration::new(1, 8)).unwrap(); 3],
+        )
+        .unwrap();
+        let outer = tuplet(
+            Multiplier::new(4, 5),
+            vec![
+                inner,
+                note(pitch(C, Natural, 4), Duration::new(1, 4)).unwrap(),
+            ],
+        )
+        .unwrap();
+        let score = ScoreBuilder::new()
+            .staff("1", |s| s.voice("1", |v| v.push(outer)))
+            .build()
+            .unwrap();
+        assert!(write_abc(&score).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let source = "X:1\nT:Round Trip\nM:3/4\nL:1/4\nK:F\nV:1 name=\"Upper\"\nV:2\n\
+                      [V:1] c/ | (3ABc [FAc]- | [FAc] ^G>A |]\n\
+                      [V:2] z/ | F,,3 | _E,2 z |]\n";
+        let score = read_abc(source).unwrap();
+        assert_eq!(score[0][0].measures().len(), 2);
+        assert_eq!(score[1][0].measures().len(), 2);
+        let written = write_abc(&score).unwrap();
+        assert_eq!(read_abc(&written).unwrap(), score);
+    }
+}
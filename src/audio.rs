@@ -0,0 +1,432 @@
+//! Offline rendering to PCM WAV, for auditioning without a synthesiser.
+//!
+//! Every note and chord tone becomes a voice with a short attack and release.
+//! Onsets follow the leaves' offsets, so tuplets are honoured, and pitches
+//! sound at their exact equal-tempered frequency (quarter tones included)
+//! after any instrument transposition. Tied notes of the same pitch are
+//! joined into one tone.
+
+use std::{collections::HashMap, f64::consts::PI};
+
+use crate::{
+    duration::Duration, error::Error, has_semitones::HasSemitones, score_object::ScoreObject,
+    tuning::TunedPitch,
+};
+
+const A4: f64 = 440.;
+const ATTACK: f64 = 0.005;
+const RELEASE: f64 = 0.03;
+const VOICE_AMPLITUDE: f64 = 0.2;
+/// The highest sample rate accepted, well above any audio interface's.
+const MAX_SAMPLE_RATE: u32 = 384_000;
+
+/// The timbre of every rendered tone.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Waveform {
+    Sine,
+    /// Amplitudes of the harmonics, starting with the fundamental.
+    Additive(Vec<f64>),
+    /// One cycle of samples, read with linear interpolation.
+    Wavetable(Vec<f64>),
+}
+
+impl Waveform {
+    /// The value at `phase`, in cycles.
+    fn sample(&self, phase: f64) -> f64 {
+        match self {
+            Self::Sine => (2. * PI * phase).sin(),
+            Self::Additive(harmonics) => {
+                let total: f64 = harmonics.iter().map(|a| a.abs()).sum();
+                if total == 0. {
+                    return 0.;
+                }
+                harmonics
+                    .iter()
+                    .enumerate()
+                    .map(|(k, a)| a * (2. * PI * phase * (k + 1) as f64).sin())
+                    .sum::<f64>()
+                    / total
+            }
+            Self::Wavetable(table) if table.is_empty() => 0.,
+            Self::Wavetable(table) => {
+                let position = phase.fract() * table.len() as f64;
+                let index = position.floor() as usize;
+                let next = table[(index + 1) % table.len()];
+                table[index] + (next - table[index]) * position.fract()
+            }
+        }
+    }
+}
+
+/// Where a staff sits in the stereo field, from `-1.` (left) to `1.`
+/// (right), and how loud it plays.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct StaffMix {
+    pan: f64,
+    gain: f64,
+}
+
+impl Default for StaffMix {
+    fn default() -> Self {
+        Self { pan: 0., gain: 1. }
+    }
+}
+
+/// A tone to render, in seconds.
+#[derive(Clone, Debug, PartialEq)]
+struct Tone {
+    start: f64,
+    stop: f64,
+    frequency: f64,
+    mix: StaffMix,
+}
+
+/// Renders scores to stereo audio. Staves are mixed by name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioRenderer {
+    sample_rate: u32,
+    tempo: f64,
+    waveform: Waveform,
+    mixes: HashMap<String, StaffMix>,
+}
+
+impl Default for AudioRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioRenderer {
+    /// Sine tones at 44.1 kHz and 60 quarter notes per minute.
+    pub fn new() -> Self {
+        Self {
+            sample_rate: 44100,
+            tempo: 60.,
+            waveform: Waveform::Sine,
+            mixes: HashMap::new(),
+        }
+    }
+
+    /// Sets the sample rate in hertz, clamped to between 1 Hz and 384 kHz.
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = sample_rate.clamp(1, MAX_SAMPLE_RATE);
+        self
+    }
+
+    /// Sets the tempo in quarter notes per minute. Tempos that are not
+    /// positive and finite are ignored.
+    pub fn with_tempo(mut self, tempo: f64) -> Self {
+        if tempo.is_finite() && tempo > 0. {
+            self.tempo = tempo;
+        }
+        self
+    }
+
+    pub fn with_waveform(mut self, waveform: Waveform) -> Self {
+        self.waveform = waveform;
+        self
+    }
+
+    pub fn with_staff_mix(mut self, staff_name: impl Into<String>, pan: f64, gain: f64) -> Self {
+        let pan = pan.clamp(-1., 1.);
+        self.mixes.insert(staff_name.into(), StaffMix { pan, gain });
+        self
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn seconds(&self, offset: Duration) -> f64 {
+        let (n, d) = offset.to_pair();
+        n as f64 / d as f64 * 240. / self.tempo
+    }
+
+    /// The score's tones, joining tied notes of the same sounding pitch. A
+    /// tie that is not continued by the next leaf of its voice, or that ends
+    /// the score, ends with the tied note.
+    fn tones(&self, score: &ScoreObject) -> Vec<Tone> {
+        let mut tones = vec![];
+        let mut pending: HashMap<(Vec<usize>, i32), Tone> = HashMap::new();
+        for timespan in score.leaf_timespans() {
+            let pitches = score.sounding_pitches(&timespan.path).unwrap_or_default();
+            let (voice_path, staff_name) = enclosing(score, &timespan.path);
+            let broken: Vec<_> = pending
+                .keys()
+                .filter(|(path, quarter_tones)| {
+                    *path == voice_path
+                        && !pitches.iter().any(|p| p.quarter_tones() == *quarter_tones)
+                })
+                .cloned()
+                .collect();
+            for key in broken {
+                tones.extend(pending.remove(&key));
+            }
+            let mix = staff_name
+                .and_then(|name| self.mixes.get(name))
+                .copied()
+                .unwrap_or_default();
+            for pitch in pitches {
+                let key = (voice_path.clone(), pitch.quarter_tones());
+                let mut tone = pending.remove(&key).unwrap_or_else(|| Tone {
+                    start: self.seconds(timespan.start_offset),
+                    stop: 0.,
+                    frequency: TunedPitch::new(pitch, 0.).frequency(A4),
+                    mix,
+                });
+                tone.stop = self.seconds(timespan.stop_offset);
+                if timespan.leaf.is_tied() {
+                    pending.insert(key, tone);
+                } else {
+                    tones.push(tone);
+                }
+            }
+        }
+        let mut dangling: Vec<Tone> = pending.into_values().collect();
+        dangling.sort_by(|a, b| a.start.total_cmp(&b.start));
+        tones.extend(dangling);
+        tones
+    }
+
+    /// Renders `score` as interleaved stereo frames. The mix is scaled down
+    /// if it would otherwise clip.
+    pub fn render(&self, score: &ScoreObject) -> Vec<[f32; 2]> {
+        let rate = self.sample_rate as f64;
+        let length = self.frame_count(score);
+        let mut frames = vec![[0f64; 2]; length];
+        for tone in self.tones(score) {
+            let first = (tone.start * rate).round() as usize;
+            let last = ((tone.stop * rate).round() as usize).min(length);
+            let angle = (tone.mix.pan + 1.) * PI / 4.;
+            let (left, right) = (angle.cos(), angle.sin());
+            let duration = tone.stop - tone.start;
+            for (n, frame) in frames[first.min(last)..last].iter_mut().enumerate() {
+                let t = n as f64 / rate;
+                let envelope = (t / ATTACK).min(1.) * ((duration - t) / RELEASE).clamp(0., 1.);
+                let value = self.waveform.sample(tone.frequency * t)
+                    * envelope
+                    * VOICE_AMPLITUDE
+                    * tone.mix.gain;
+                frame[0] += value * left;
+                frame[1] += value * right;
+            }
+        }
+        let peak = frames
+            .iter()
+            .flatten()
+            .fold(0f64, |peak, sample| peak.max(sample.abs()));
+        let scale = if peak > 1. { 1. / peak } else { 1. };
+        frames
+            .iter()
+            .map(|[l, r]| [(l * scale) as f32, (r * scale) as f32])
+            .collect()
+    }
+
+    fn frame_count(&self, score: &ScoreObject) -> usize {
+        (self.seconds(score.duration()) * self.sample_rate as f64)
+            .ceil()
+            .max(0.) as usize
+    }
+
+    /// Renders `score` as a 16-bit stereo PCM WAV file. Fails for renders
+    /// too long for a WAV header to describe.
+    pub fn render_wav(&self, score: &ScoreObject) -> Result<Vec<u8>, Error> {
+        let data_size = self
+            .frame_count(score)
+            .checked_mul(4)
+            .and_then(|size| u32::try_from(size).ok())
+            .filter(|size| size.checked_add(36).is_some())
+            .ok_or_else(|| Error::AudioExport("too long for a WAV file".to_string()))?;
+        let frames = self.render(score);
+        let mut wav = Vec::with_capacity(44 + data_size as usize);
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&self.sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(self.sample_rate * 4).to_le_bytes());
+        wav.extend_from_slice(&4u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_size.to_le_bytes());
+        for sample in frames.iter().flatten() {
+            let value = (sample.clamp(-1., 1.) * i16::MAX as f32).round() as i16;
+            wav.extend_from_slice(&value.to_le_bytes());
+        }
+        Ok(wav)
+    }
+}
+
+/// The path of the innermost voice (or staff) around the leaf at `path`, and
+/// the name of its staff.
+fn enclosing<'a>(score: &'a ScoreObject, path: &[usize]) -> (Vec<usize>, Option<&'a str>) {
+    let mut component = score;
+    let mut voice_path = vec![];
+    let mut staff_name = None;
+    for (depth, index) in path.iter().enumerate() {
+        match component {
+            ScoreObject::Staff { name, .. } => {
+                staff_name = name.as_deref();
+                voice_path = path[..depth].to_vec();
+            }
+            ScoreObject::Voice { .. } => voice_path = path[..depth].to_vec(),
+            _ => (),
+        }
+        match component.contents().and_then(|c| c.get(*index)) {
+            Some(child) => component = child,
+            None => break,
+        }
+    }
+    (voice_path, staff_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AudioRenderer, Waveform};
+    use crate::duration::Duration;
+    use crate::instrument::Instrument;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{chord, note, rest, score, tuplet, ScoreBuilder, ScoreObject};
+
+    fn pitch(
+        dpc: crate::pitch::DiatonicPitchClass,
+        accidental: crate::pitch::Accidental,
+        octave: i32,
+    ) -> Pitch {
+        Pitch::new(PitchClass::new(dpc, accidental), octave)
+    }
+
+    fn melody() -> ScoreObject {
+        let mut tied = note(pitch(A, Natural, 4), Duration::new(1, 4)).unwrap();
+        tied.set_is_tied(true);
+        ScoreBuilder::new()
+            .staff("Upper", |s| {
+                s.voice("1", |v| {
+                    v.try_push(tuplet(
+                        Multiplier::new(2, 3),
+                        vec![
+                            note(pitch(C, Natural, 5), Duration::new(1, 8)).unwrap(),
+                            note(pitch(A, QuarterSharp, 4), Duration::new(1, 8)).unwrap(),
+                            rest(Duration::new(1, 8)).unwrap(),
+                        ],
+                    ))
+                    .push(tied)
+                    .try_push(note(pitch(A, Natural, 4), Duration::new(1, 4)))
+                })
+            })
+            .staff("Lower", |s| {
+                s.instrument(Instrument::clarinet_in_b_flat())
+                    .voice("1", |v| {
+                        v.try_push(chord(
+                            vec![pitch(D, Natural, 4), pitch(F, Sharp, 4)],
+                            Duration::new(3, 4),
+                        ))
+                    })
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn tones() {
+        let renderer = AudioRenderer::new().with_tempo(120.);
+        let tones = renderer.tones(&melody());
+        assert_eq!(tones.len(), 5);
+
+        let starts: Vec<f64> = tones.iter().map(|t| t.start).collect();
+        assert!((starts[1] - 1. / 6.).abs() < 1e-9);
+        assert!((starts[2] - 0.5).abs() < 1e-9);
+        assert!((tones[2].stop - 1.5).abs() < 1e-9);
+
+        assert!((tones[0].frequency - 523.2511).abs() < 1e-3);
+        assert!((tones[1].frequency - 452.8929).abs() < 1e-3);
+        assert!((tones[2].frequency - 440.).abs() < 1e-9);
+        assert!((tones[3].frequency - 261.6256).abs() < 1e-3);
+        assert!((tones[4].frequency - 329.6276).abs() < 1e-3);
+    }
+
+    #[test]
+    fn broken_and_dangling_ties() {
+        let mut tied = note(pitch(C, Natural, 4), Duration::new(1, 4)).unwrap();
+        tied.set_is_tied(true);
+        let score = ScoreBuilder::new()
+            .staff("1", |s| {
+                s.voice("1", |v| {
+                    v.push(tied.clone())
+                        .try_push(note(pitch(D, Natural, 4), Duration::new(1, 4)))
+                        .push(tied)
+                })
+            })
+            .build()
+            .unwrap();
+        let tones = AudioRenderer::new().with_tempo(60.).tones(&score);
+        assert_eq!(tones.len(), 3);
+        let spans: Vec<(f64, f64)> = tones.iter().map(|t| (t.start, t.stop)).collect();
+        assert_eq!(spans, vec![(0., 1.), (1., 2.), (2., 3.)]);
+    }
+
+    #[test]
+    fn ignores_invalid_tempos() {
+        for tempo in [0., -60., f64::NAN, f64::INFINITY] {
+            assert_eq!(AudioRenderer::new().with_tempo(tempo), AudioRenderer::new());
+        }
+    }
+
+    #[test]
+    fn panning_and_gain() {
+        let frames = AudioRenderer::new()
+            .with_sample_rate(8000)
+            .with_staff_mix("Upper", -1., 1.)
+            .with_staff_mix("Lower", -1., 0.5)
+            .render(&melody());
+        assert_eq!(frames.len(), 24000);
+        assert!(frames.iter().all(|[_, right]| right.abs() < 1e-6));
+        assert!(frames.iter().any(|[left, _]| left.abs() > 0.1));
+
+        let silent = AudioRenderer::new()
+            .with_sample_rate(8000)
+            .with_staff_mix("Upper", 0., 0.)
+            .with_staff_mix("Lower", 0., 0.)
+            .render(&melody());
+        assert!(silent.iter().flatten().all(|s| *s == 0.));
+    }
+
+    #[test]
+    fn waveforms() {
+        let additive = Waveform::Additive(vec![1., 0.5]);
+        assert!((additive.sample(0.25) - 1. / 1.5).abs() < 1e-9);
+        let table = Waveform::Wavetable(vec![0., 1., 0., -1.]);
+        assert!((table.sample(0.125) - 0.5).abs() < 1e-9);
+        assert!((table.sample(1.875) + 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn wav_header() {
+        let renderer = AudioRenderer::new().with_sample_rate(8000).with_tempo(240.);
+        let wav = renderer.render_wav(&melody()).unwrap();
+        let frames = 6000u32;
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u16::from_le_bytes([wav[22], wav[23]]), 2);
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 8000);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(
+            u32::from_le_bytes(wav[40..44].try_into().unwrap()),
+            frames * 4
+        );
+        assert_eq!(wav.len() as u32, 44 + frames * 4);
+
+        let fast = AudioRenderer::new().with_sample_rate(u32::MAX);
+        assert_eq!(fast.sample_rate(), 384_000);
+        assert!(fast.render_wav(&score(vec![]).unwrap()).is_ok());
+
+        let long = rest(Duration::new(8, 1)).unwrap();
+        let slow = AudioRenderer::new()
+            .with_sample_rate(u32::MAX)
+            .with_tempo(0.001);
+        assert!(slow.render_wav(&long).is_err());
+    }
+}
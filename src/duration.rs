@@ -1,7 +1,11 @@
+use std::cmp::Ordering;
 use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::{fmt, str::FromStr};
 
 use num::Integer;
 
+use crate::{error::Error, multiplier::Multiplier};
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Duration {
     numerator: i32,
@@ -47,6 +51,16 @@ impl Mul<i32> for Duration {
     }
 }
 
+impl Mul<Multiplier> for Duration {
+    type Output = Duration;
+
+    fn mul(self, rhs: Multiplier) -> Self::Output {
+        let (a, b) = self.to_pair();
+        let (c, d) = rhs.to_pair();
+        Duration::new(a * c, b * d)
+    }
+}
+
 impl Div<Duration> for Duration {
     type Output = Duration;
 
@@ -75,6 +89,48 @@ impl Neg for Duration {
     }
 }
 
+impl PartialOrd for Duration {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Duration {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = self.to_pair();
+        let (c, d) = other.to_pair();
+        (a as i64 * d as i64).cmp(&(c as i64 * b as i64))
+    }
+}
+
+impl Eq for Duration {}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+impl FromStr for Duration {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (numerator, denominator) = parse_fraction(s)?;
+        Ok(Self::new(numerator, denominator))
+    }
+}
+
+/// Parses `"n/d"` (or a bare integer) with a positive denominator.
+pub(crate) fn parse_fraction(s: &str) -> Result<(i32, i32), Error> {
+    let invalid = || Error::FractionParse(s.to_string());
+    let (numerator, denominator) = s.split_once('/').unwrap_or((s, "1"));
+    let numerator = numerator.trim().parse().map_err(|_| invalid())?;
+    match denominator.trim().parse() {
+        Ok(denominator) if denominator > 0 => Ok((numerator, denominator)),
+        _ => Err(invalid()),
+    }
+}
+
 impl Duration {
     pub fn new(numerator: i32, denominator: i32) -> Self {
         let (numerator, denominator) = reduce(numerator, denominator);
@@ -100,6 +156,57 @@ impl Duration {
         self.is_printable_duration() && self.has_printable_denominator() && !self.is_tied()
     }
 
+    /// The number of augmentation dots needed to print this duration.
+    pub fn dot_count(&self) -> u32 {
+        self.numerator.count_ones().saturating_sub(1)
+    }
+
+    /// The smallest power of two (`1/4`, `1/2`, `1`, `2`, ...) not less than
+    /// this duration.
+    pub fn equal_or_greater_power_of_two(&self) -> Self {
+        if self.numerator <= 0 {
+            return *self;
+        }
+
+        let mut power = Duration::new(1, 1);
+        while power < *self {
+            power = power * 2;
+        }
+        while power / 2 >= *self {
+            power = power / 2;
+        }
+        power
+    }
+
+    /// Splits the duration into the fewest printable durations that sum to it,
+    /// longest first, e.g. `5/8` becomes `[1/2, 1/8]`.
+    pub fn to_printable_parts(&self) -> Result<Vec<Duration>, Error> {
+        if self.numerator <= 0 || !self.has_printable_denominator() {
+            return Err(Error::UnprintableDuration(*self));
+        }
+
+        let mut parts = vec![];
+        let mut remaining = *self;
+        while remaining >= Duration::new(16, 1) {
+            parts.push(Duration::new(8, 1));
+            remaining = remaining - Duration::new(8, 1);
+        }
+
+        let (mut numerator, denominator) = remaining.to_pair();
+        while numerator > 0 {
+            let mut bit = 31 - numerator.leading_zeros();
+            let mut run = 1 << bit;
+            while bit > 0 && numerator & (1 << (bit - 1)) != 0 {
+                bit -= 1;
+                run |= 1 << bit;
+            }
+            parts.push(Duration::new(run, denominator));
+            numerator -= run;
+        }
+
+        Ok(parts)
+    }
+
     fn is_printable_duration(&self) -> bool {
         let f = self.to_float();
         0. < f && f < 16.
@@ -137,6 +244,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::useless_conversion)]
     fn from() {
         let d = Duration::new(1, 4);
         assert_eq!(Duration::from(d).to_pair(), (1, 4));
@@ -209,4 +317,72 @@ mod tests {
         assert!(!Duration::new(1, 3).is_printable());
         assert!(!Duration::new(5, 8).is_printable());
     }
+
+    #[test]
+    fn compare() {
+        assert!(Duration::new(1, 4) < Duration::new(1, 3));
+        assert!(Duration::new(-1, 2) < Duration::new(1, 16));
+        assert_eq!(
+            Duration::new(3, 8).max(Duration::new(1, 4)),
+            Duration::new(3, 8)
+        );
+    }
+
+    #[test]
+    fn dot_count() {
+        assert_eq!(Duration::new(1, 4).dot_count(), 0);
+        assert_eq!(Duration::new(3, 8).dot_count(), 1);
+        assert_eq!(Duration::new(7, 16).dot_count(), 2);
+    }
+
+    #[test]
+    fn equal_or_greater_power_of_two() {
+        assert_eq!(
+            Duration::new(1, 6).equal_or_greater_power_of_two(),
+            Duration::new(1, 4)
+        );
+        assert_eq!(
+            Duration::new(1, 4).equal_or_greater_power_of_two(),
+            Duration::new(1, 4)
+        );
+        assert_eq!(
+            Duration::new(3, 2).equal_or_greater_power_of_two(),
+            Duration::new(2, 1)
+        );
+    }
+
+    #[test]
+    fn to_printable_parts() {
+        assert_eq!(
+            Duration::new(3, 8).to_printable_parts().unwrap(),
+            vec![Duration::new(3, 8)]
+        );
+        assert_eq!(
+            Duration::new(5, 8).to_printable_parts().unwrap(),
+            vec![Duration::new(1, 2), Duration::new(1, 8)]
+        );
+        assert_eq!(
+            Duration::new(13, 16).to_printable_parts().unwrap(),
+            vec![Duration::new(3, 4), Duration::new(1, 16)]
+        );
+        assert_eq!(
+            Duration::new(17, 1).to_printable_parts().unwrap(),
+            vec![
+                Duration::new(8, 1),
+                Duration::new(8, 1),
+                Duration::new(1, 1)
+            ]
+        );
+        assert!(Duration::new(1, 3).to_printable_parts().is_err());
+        assert!(Duration::new(-1, 4).to_printable_parts().is_err());
+    }
+
+    #[test]
+    fn display_and_parse() {
+        assert_eq!(Duration::new(6, 16).to_string(), "3/8");
+        assert_eq!("3/8".parse::<Duration>().unwrap(), Duration::new(3, 8));
+        assert_eq!("2".parse::<Duration>().unwrap(), Duration::new(2, 1));
+        assert!("3/0".parse::<Duration>().is_err());
+        assert!("three/8".parse::<Duration>().is_err());
+    }
 }
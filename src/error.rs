@@ -1,8 +1,74 @@
-use crate::duration::Duration;
+use crate::{
+    duration::Duration,
+    multiplier::Multiplier,
+    pitch::{Mode, PitchClass},
+    time_signature::TimeSignature,
+    wellformedness::Violation,
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Duration {:?} cannot be printed as a single notehead.", .0.to_pair())]
     UnprintableDuration(Duration),
+    #[error("Duration {:?} must be positive.", .0.to_pair())]
+    NonPositiveDuration(Duration),
+    #[error("Measure in {:?} has contents lasting {:?}.", .0.to_pair(), .1.to_pair())]
+    MeasureDurationMismatch(TimeSignature, Duration),
+    #[error("Component at offset {:?} crosses a barline and cannot be split.", .0.to_pair())]
+    UnsplittableComponent(Duration),
+    #[error("Meter lasting {:?} does not match duration {:?}.", .0.to_pair(), .1.to_pair())]
+    MeterDurationMismatch(Duration, Duration),
+    #[error("Time signature {0} has no meter.")]
+    InvalidTimeSignature(TimeSignature),
+    #[error("Invalid talea: {0}.")]
+    InvalidTalea(String),
+    #[error("Division {0} lasting {:?} leaves no positive duration to fill.", .1.to_pair())]
+    NonPositiveDivision(usize, Duration),
+    #[error("A division cannot be divided into {0} parts.")]
+    InvalidPartCount(i32),
+    #[error("Invalid rhythm tree: {0}.")]
+    RhythmTreeParse(String),
+    #[error("Display ratio {0:?} does not match tuplet multiplier {:?}.", .1.to_pair())]
+    InvalidDisplayRatio((i32, i32), Multiplier),
+    #[error("Tuplet multiplier {0} must be positive.")]
+    NonPositiveMultiplier(Multiplier),
+    #[error("{0} cannot be nested inside {1}.")]
+    IllegalNesting(&'static str, &'static str),
+    #[error("{0:?} is not an ordering of the twelve pitch classes.")]
+    InvalidTwelveToneRow(Vec<i32>),
+    #[error("Quarter-tone pitch class {0} cannot appear in a twelve-tone row.")]
+    QuarterToneInRow(PitchClass),
+    #[error("No {0} to cycle through.")]
+    EmptyCycle(&'static str),
+    #[error("The {1:?} scale on {0} needs more than a double accidental.")]
+    UnspellableKey(PitchClass, Mode),
+    #[error("Invalid interval name {0:?}.")]
+    IntervalParse(String),
+    #[error("An octave cannot be divided into {0} steps.")]
+    InvalidEdo(u32),
+    #[error("Ratio {0}/{1} is not a positive 11-limit ratio.")]
+    UnsupportedRatio(u64, u64),
+    #[error("Invalid pitch name {0:?}.")]
+    PitchParse(String),
+    #[error("Invalid fraction {0:?}.")]
+    FractionParse(String),
+    #[error("Unsupported score format version {0}.")]
+    UnsupportedFormatVersion(u32),
+    #[error("Ill-formed score at {:?}: {:?}.", .0.path, .0.kind)]
+    IllFormedScore(Violation),
+    #[error("Invalid ABC notation: {0}.")]
+    AbcParse(String),
+    #[error("Cannot write ABC notation: {0}.")]
+    AbcExport(String),
+    #[error("Invalid kern: {0}.")]
+    KernParse(String),
+    #[error("Cannot write kern: {0}.")]
+    KernExport(String),
+    #[error("Cannot write MEI: {0}.")]
+    MeiExport(String),
+    #[error("Cannot write LilyPond: {0}.")]
+    LilyPondExport(String),
+    #[error("Cannot write audio: {0}.")]
+    AudioExport(String),
 }
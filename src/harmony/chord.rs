@@ -0,0 +1,535 @@
+use std::fmt;
+
+use crate::{
+    error::Error,
+    has_semitones::HasSemitones,
+    pitch::{Accidental, Pitch, PitchClass},
+    score_object::{ScoreObject, VoiceContext},
+};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ChordQuality {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+    Suspended2,
+    Suspended4,
+    Power,
+    Major6,
+    Minor6,
+    Dominant7,
+    Major7,
+    Minor7,
+    MinorMajor7,
+    HalfDiminished7,
+    Diminished7,
+    Augmented7,
+    AugmentedMajor7,
+    Dominant7Suspended4,
+}
+
+impl ChordQuality {
+    /// All qualities, most common first, so that ties in analysis favour the
+    /// plainer reading.
+    const ALL: [ChordQuality; 18] = [
+        Self::Dominant7,
+        Self::Major7,
+        Self::Minor7,
+        Self::HalfDiminished7,
+        Self::Diminished7,
+        Self::MinorMajor7,
+        Self::Augmented7,
+        Self::AugmentedMajor7,
+        Self::Dominant7Suspended4,
+        Self::Major,
+        Self::Minor,
+        Self::Diminished,
+        Self::Augmented,
+        Self::Major6,
+        Self::Minor6,
+        Self::Suspended4,
+        Self::Suspended2,
+        Self::Power,
+    ];
+
+    /// Chord tones as semitones above the root, in stacking order.
+    pub fn intervals(&self) -> &'static [i32] {
+        match self {
+            Self::Major => &[0, 4, 7],
+            Self::Minor => &[0, 3, 7],
+            Self::Diminished => &[0, 3, 6],
+            Self::Augmented => &[0, 4, 8],
+            Self::Suspended2 => &[0, 2, 7],
+            Self::Suspended4 => &[0, 5, 7],
+            Self::Power => &[0, 7],
+            Self::Major6 => &[0, 4, 7, 9],
+            Self::Minor6 => &[0, 3, 7, 9],
+            Self::Dominant7 => &[0, 4, 7, 10],
+            Self::Major7 => &[0, 4, 7, 11],
+            Self::Minor7 => &[0, 3, 7, 10],
+            Self::MinorMajor7 => &[0, 3, 7, 11],
+            Self::HalfDiminished7 => &[0, 3, 6, 10],
+            Self::Diminished7 => &[0, 3, 6, 9],
+            Self::Augmented7 => &[0, 4, 8, 10],
+            Self::AugmentedMajor7 => &[0, 4, 8, 11],
+            Self::Dominant7Suspended4 => &[0, 5, 7, 10],
+        }
+    }
+
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Self::Major => "",
+            Self::Minor => "m",
+            Self::Diminished => "dim",
+            Self::Augmented => "aug",
+            Self::Suspended2 => "sus2",
+            Self::Suspended4 => "sus4",
+            Self::Power => "5",
+            Self::Major6 => "6",
+            Self::Minor6 => "m6",
+            Self::Dominant7 => "7",
+            Self::Major7 => "maj7",
+            Self::Minor7 => "m7",
+            Self::MinorMajor7 => "m(maj7)",
+            Self::HalfDiminished7 => "m7b5",
+            Self::Diminished7 => "dim7",
+            Self::Augmented7 => "aug7",
+            Self::AugmentedMajor7 => "maj7#5",
+            Self::Dominant7Suspended4 => "7sus4",
+        }
+    }
+
+    fn has_seventh(&self) -> bool {
+        self.intervals().len() == 4 && !matches!(self, Self::Major6 | Self::Minor6)
+    }
+
+    /// A perfect fifth may be left out of a seventh chord.
+    fn omittable(&self, interval: i32) -> bool {
+        interval == 7 && self.has_seventh() && self != &Self::Dominant7Suspended4
+    }
+}
+
+/// A tone added above a chord's basic sonority.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Extension {
+    FlatNinth,
+    Ninth,
+    SharpNinth,
+    Eleventh,
+    SharpEleventh,
+    FlatThirteenth,
+    Thirteenth,
+}
+
+impl Extension {
+    /// Semitones above the root, reduced to within an octave.
+    pub fn interval(&self) -> i32 {
+        match self {
+            Self::FlatNinth => 1,
+            Self::Ninth => 2,
+            Self::SharpNinth => 3,
+            Self::Eleventh => 5,
+            Self::SharpEleventh => 6,
+            Self::FlatThirteenth => 8,
+            Self::Thirteenth => 9,
+        }
+    }
+
+    fn from_interval(interval: i32, quality: ChordQuality) -> Option<Self> {
+        let intervals = quality.intervals();
+        match interval {
+            1 => Some(Self::FlatNinth),
+            2 => Some(Self::Ninth),
+            3 if intervals.contains(&4) => Some(Self::SharpNinth),
+            5 => Some(Self::Eleventh),
+            6 if intervals.contains(&7) => Some(Self::SharpEleventh),
+            8 if intervals.contains(&7) => Some(Self::FlatThirteenth),
+            9 => Some(Self::Thirteenth),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Extension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::FlatNinth => "b9",
+            Self::Ninth => "9",
+            Self::SharpNinth => "#9",
+            Self::Eleventh => "11",
+            Self::SharpEleventh => "#11",
+            Self::FlatThirteenth => "b13",
+            Self::Thirteenth => "13",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The root, quality, extensions and inversion of a collection of pitches.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChordAnalysis {
+    root: PitchClass,
+    quality: ChordQuality,
+    extensions: Vec<Extension>,
+    bass: PitchClass,
+    inversion: Option<usize>,
+}
+
+impl ChordAnalysis {
+    /// Identifies the chord the pitches spell, preferring the reading with
+    /// the fewest extensions, then one rooted on the bass. Returns `None` for
+    /// fewer than two distinct pitch classes, for quarter tones, or when no
+    /// reading accounts for every pitch class.
+    pub fn analyze(pitches: &[Pitch]) -> Option<Self> {
+        if pitches.iter().any(|p| p.quarter_tones() % 2 != 0) {
+            return None;
+        }
+        let bass = pitches.iter().min()?;
+        let mut pitch_classes: Vec<PitchClass> = vec![];
+        for pitch in pitches {
+            let pitch_class = pitch.pitch_class();
+            if !pitch_classes
+                .iter()
+                .any(|pc| pc.is_enharmonic_with(&pitch_class))
+            {
+                pitch_classes.push(pitch_class);
+            }
+        }
+        if pitch_classes.len() < 2 {
+            return None;
+        }
+
+        let number = |pc: &PitchClass| pc.quarter_tones() / 2;
+        let bass_number = number(&bass.pitch_class());
+        let pitch_classes = &pitch_classes;
+        pitch_classes
+            .iter()
+            .flat_map(|root| {
+                ChordQuality::ALL
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(priority, quality)| {
+                        let intervals: Vec<i32> = pitch_classes
+                            .iter()
+                            .map(|pc| (number(pc) - number(root)).rem_euclid(12))
+                            .collect();
+                        let complete = quality
+                            .intervals()
+                            .iter()
+                            .all(|i| intervals.contains(i) || quality.omittable(*i));
+                        if !complete {
+                            return None;
+                        }
+                        let mut extensions = intervals
+                            .iter()
+                            .filter(|i| !quality.intervals().contains(i))
+                            .map(|i| Extension::from_interval(*i, *quality))
+                            .collect::<Option<Vec<_>>>()?;
+                        extensions.sort();
+                        let bass_interval = (bass_number - number(root)).rem_euclid(12);
+                        let inversion =
+                            quality.intervals().iter().position(|i| *i == bass_interval);
+                        let analysis = Self {
+                            root: *root,
+                            quality: *quality,
+                            extensions,
+                            bass: bass.pitch_class(),
+                            inversion,
+                        };
+                        let rank = (analysis.extensions.len(), inversion != Some(0), priority);
+                        Some((rank, analysis))
+                    })
+            })
+            .min_by_key(|(rank, _)| *rank)
+            .map(|(_, analysis)| analysis)
+    }
+
+    pub fn root(&self) -> PitchClass {
+        self.root
+    }
+
+    pub fn quality(&self) -> ChordQuality {
+        self.quality
+    }
+
+    pub fn extensions(&self) -> &[Extension] {
+        &self.extensions
+    }
+
+    pub fn bass(&self) -> PitchClass {
+        self.bass
+    }
+
+    /// 0 for root position, 1 for first inversion and so on; `None` when the
+    /// bass is an extension.
+    pub fn inversion(&self) -> Option<usize> {
+        self.inversion
+    }
+
+    /// A lead-sheet symbol such as `Cmaj7/E`, `G7(b9)` or `Dmadd9`.
+    pub fn symbol(&self) -> String {
+        let mut symbol = format!("{}{}", name(&self.root), self.quality.suffix());
+        if !self.extensions.is_empty() {
+            let extensions: Vec<String> = self.extensions.iter().map(|e| e.to_string()).collect();
+            if self.quality.has_seventh() {
+                symbol += &format!("({})", extensions.join(","));
+            } else {
+                symbol += &format!("add{}", extensions.join("add"));
+            }
+        }
+        if self.inversion != Some(0) {
+            symbol += &format!("/{}", name(&self.bass));
+        }
+        symbol
+    }
+}
+
+impl fmt::Display for ChordAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+fn name(pitch_class: &PitchClass) -> String {
+    let (diatonic_pitch_class, accidental) = pitch_class.to_pair();
+    let accidental = match accidental {
+        Accidental::DoubleFlat => "bb",
+        Accidental::Flat => "b",
+        Accidental::Sharp => "#",
+        Accidental::DoubleSharp => "##",
+        _ => "",
+    };
+    format!("{diatonic_pitch_class:?}{accidental}")
+}
+
+impl ScoreObject {
+    /// Analyses a chord's written pitches. `None` for anything but a chord.
+    pub fn chord_analysis(&self) -> Option<ChordAnalysis> {
+        match self {
+            Self::Chord {
+                written_pitches, ..
+            } => ChordAnalysis::analyze(written_pitches),
+            _ => None,
+        }
+    }
+}
+
+/// Builds a `ChordNames` voice shadowing `contents`: chords that can be
+/// analysed are kept, and every other leaf becomes a spacer of the same
+/// duration, so the names line up with the music they label.
+pub fn chord_names(contents: &[ScoreObject]) -> Result<ScoreObject, Error> {
+    fn shadow(component: &ScoreObject) -> ScoreObject {
+        match component {
+            ScoreObject::Chord { .. } if component.chord_analysis().is_some() => {
+                let mut chord = component.clone();
+                chord.set_is_tied(false);
+                chord
+            }
+            leaf if leaf.is_leaf() => ScoreObject::Spacer {
+                written_duration: leaf.duration(),
+            },
+            container => {
+                let mut container = container.clone();
+                if let Some(contents) = container.contents_mut() {
+                    *contents = contents.iter().map(shadow).collect();
+                }
+                container
+            }
+        }
+    }
+
+    let mut voice = crate::score_object::voice(contents.iter().map(shadow).collect())?;
+    voice.set_voice_context(VoiceContext::ChordNames);
+    Ok(voice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chord_names, ChordAnalysis, ChordQuality, Extension};
+    use crate::duration::Duration;
+    use crate::pitch::{
+        Accidental::*, DiatonicPitchClass, DiatonicPitchClass::*, Pitch, PitchClass,
+    };
+    use crate::score_object::{chord, note, rest, ScoreObject, VoiceContext};
+
+    fn p(
+        diatonic_pitch_class: DiatonicPitchClass,
+        accidental: crate::pitch::Accidental,
+        octave: i32,
+    ) -> Pitch {
+        Pitch::new(PitchClass::new(diatonic_pitch_class, accidental), octave)
+    }
+
+    fn symbol(pitches: &[Pitch]) -> String {
+        ChordAnalysis::analyze(pitches).unwrap().symbol()
+    }
+
+    #[test]
+    fn triads() {
+        let c_major =
+            ChordAnalysis::analyze(&[p(C, Natural, 4), p(E, Natural, 4), p(G, Natural, 4)])
+                .unwrap();
+        assert_eq!(c_major.quality(), ChordQuality::Major);
+        assert_eq!(c_major.inversion(), Some(0));
+        assert_eq!(c_major.to_string(), "C");
+
+        assert_eq!(
+            symbol(&[p(A, Natural, 3), p(C, Natural, 4), p(E, Natural, 4)]),
+            "Am"
+        );
+        assert_eq!(
+            symbol(&[p(B, Natural, 3), p(D, Natural, 4), p(F, Natural, 4)]),
+            "Bdim"
+        );
+        assert_eq!(
+            symbol(&[p(C, Natural, 4), p(E, Natural, 4), p(G, Sharp, 4)]),
+            "Caug"
+        );
+        assert_eq!(
+            symbol(&[p(D, Natural, 4), p(G, Natural, 4), p(A, Natural, 4)]),
+            "Dsus4"
+        );
+        assert_eq!(symbol(&[p(E, Flat, 3), p(B, Flat, 3)]), "Eb5");
+    }
+
+    #[test]
+    fn inversions() {
+        let first = ChordAnalysis::analyze(&[p(E, Natural, 3), p(G, Natural, 3), p(C, Natural, 4)])
+            .unwrap();
+        assert_eq!(first.inversion(), Some(1));
+        assert_eq!(first.bass(), PitchClass::new(E, Natural));
+        assert_eq!(first.to_string(), "C/E");
+
+        assert_eq!(
+            symbol(&[
+                p(E, Natural, 3),
+                p(G, Natural, 3),
+                p(B, Natural, 3),
+                p(C, Natural, 4)
+            ]),
+            "Cmaj7/E"
+        );
+        assert_eq!(
+            symbol(&[
+                p(F, Natural, 3),
+                p(G, Natural, 3),
+                p(B, Natural, 3),
+                p(D, Natural, 4)
+            ]),
+            "G7/F"
+        );
+    }
+
+    #[test]
+    fn sevenths() {
+        assert_eq!(
+            symbol(&[
+                p(B, Natural, 3),
+                p(D, Natural, 4),
+                p(F, Natural, 4),
+                p(A, Natural, 4)
+            ]),
+            "Bm7b5"
+        );
+        assert_eq!(
+            symbol(&[
+                p(C, Sharp, 4),
+                p(E, Natural, 4),
+                p(G, Natural, 4),
+                p(B, Flat, 4)
+            ]),
+            "C#dim7"
+        );
+        assert_eq!(
+            symbol(&[
+                p(D, Natural, 4),
+                p(F, Natural, 4),
+                p(A, Natural, 4),
+                p(C, Natural, 5)
+            ]),
+            "Dm7"
+        );
+        // A root-position sixth chord is preferred to its relative minor seventh.
+        assert_eq!(
+            symbol(&[
+                p(F, Natural, 3),
+                p(A, Natural, 3),
+                p(C, Natural, 4),
+                p(D, Natural, 4)
+            ]),
+            "F6"
+        );
+        // The fifth may be omitted.
+        assert_eq!(
+            symbol(&[p(G, Natural, 3), p(B, Natural, 3), p(F, Natural, 4)]),
+            "G7"
+        );
+        // ...but not from a sixth chord, which would hide a first-inversion triad.
+        assert_eq!(
+            symbol(&[p(F, Natural, 3), p(A, Natural, 3), p(D, Natural, 4)]),
+            "Dm/F"
+        );
+    }
+
+    #[test]
+    fn extensions() {
+        let altered = ChordAnalysis::analyze(&[
+            p(G, Natural, 3),
+            p(B, Natural, 3),
+            p(D, Natural, 4),
+            p(F, Natural, 4),
+            p(A, Flat, 4),
+            p(E, Natural, 5),
+        ])
+        .unwrap();
+        assert_eq!(altered.quality(), ChordQuality::Dominant7);
+        assert_eq!(
+            altered.extensions(),
+            &[Extension::FlatNinth, Extension::Thirteenth]
+        );
+        assert_eq!(altered.to_string(), "G7(b9,13)");
+
+        assert_eq!(
+            symbol(&[
+                p(D, Natural, 4),
+                p(E, Natural, 4),
+                p(F, Natural, 4),
+                p(A, Natural, 4)
+            ]),
+            "Dmadd9"
+        );
+    }
+
+    #[test]
+    fn unanalysable() {
+        assert!(ChordAnalysis::analyze(&[p(C, Natural, 4), p(C, Natural, 5)]).is_none());
+        assert!(ChordAnalysis::analyze(&[p(C, Natural, 4), p(E, QuarterSharp, 4)]).is_none());
+        assert!(ChordAnalysis::analyze(&[]).is_none());
+    }
+
+    #[test]
+    fn chord_names_voice() {
+        let quarter = Duration::new(1, 4);
+        let mut tied = chord(
+            vec![p(C, Natural, 4), p(E, Natural, 4), p(G, Natural, 4)],
+            quarter,
+        )
+        .unwrap();
+        tied.set_is_tied(true);
+        let contents = vec![
+            tied,
+            note(p(C, Natural, 4), quarter).unwrap(),
+            rest(quarter).unwrap(),
+        ];
+
+        let names = chord_names(&contents).unwrap();
+        assert_eq!(names.voice_context(), Some(&VoiceContext::ChordNames));
+        assert_eq!(names.duration(), Duration::new(3, 4));
+        let leaves = names.leaves();
+        assert_eq!(leaves[0].chord_analysis().unwrap().to_string(), "C");
+        assert!(!leaves[0].is_tied());
+        assert!(matches!(leaves[1], ScoreObject::Spacer { .. }));
+        assert!(matches!(leaves[2], ScoreObject::Spacer { .. }));
+    }
+}
@@ -0,0 +1,10 @@
+//! Tonal harmony: chord identification, naming, Roman-numeral analysis and
+//! voice leading.
+
+mod chord;
+mod roman_numeral;
+mod voice_leading;
+
+pub use chord::{chord_names, ChordAnalysis, ChordQuality, Extension};
+pub use roman_numeral::{analysis_voice, roman_numeral_analysis, HarmonicEvent, RomanNumeral};
+pub use voice_leading::{check_voice_leading, VoiceLeadingKind, VoiceLeadingViolation};
@@ -0,0 +1,507 @@
+use std::fmt;
+
+use super::{ChordAnalysis, ChordQuality};
+use crate::{
+    duration::Duration,
+    error::Error,
+    has_semitones::HasSemitones,
+    pitch::{Accidental, DiatonicPitchClass, KeySignature, Mode, Pitch, PitchClass},
+    score_object::{lyric, voice, ScoreObject, VoiceContext},
+};
+
+const NUMERALS: [&str; 7] = ["I", "II", "III", "IV", "V", "VI", "VII"];
+
+/// Tonicised degrees to try for secondary functions, most common first.
+const SECONDARY_TARGETS: [usize; 6] = [5, 2, 6, 4, 3, 7];
+
+/// A chord's function in a key, such as `V65`, `bVI` or `viio7/V`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RomanNumeral {
+    degree: usize,
+    alteration: i32,
+    quality: ChordQuality,
+    inversion: Option<usize>,
+    secondary: Option<(usize, bool)>,
+}
+
+impl RomanNumeral {
+    /// Reads a chord in `key`. Diatonic chords (including the raised leading
+    /// tone in minor) take their scale degree; chromatic dominants and
+    /// leading-tone chords of another degree become secondary functions; any
+    /// other chromatic chord is named by its altered root, as in `bVI`.
+    pub fn from_chord(chord: &ChordAnalysis, key: &KeySignature) -> Self {
+        let (root, quality, inversion) = tertian(chord);
+        let scale = key.scale();
+        let root_number = number(&root);
+
+        let (tonic_letter, _) = key.tonic().to_pair();
+        let degree = (root.to_pair().0.index() - tonic_letter.index()).rem_euclid(7) as usize;
+        let mut alteration = (root_number - number(&scale[degree]) + 6).rem_euclid(12) - 6;
+        if key.mode() == Mode::Minor && degree == 6 && alteration == 1 {
+            alteration = 0;
+        }
+
+        let allowed = allowed_numbers(key);
+        let is_diatonic = alteration == 0
+            && quality
+                .intervals()
+                .iter()
+                .all(|i| allowed.contains(&(root_number + i).rem_euclid(12)));
+        if !is_diatonic {
+            for target in SECONDARY_TARGETS {
+                let (third, fifth) = triad_on(key, target - 1);
+                if third == 3 && fifth == 6 {
+                    continue;
+                }
+                let target_number = number(&scale[target - 1]);
+                let function = match quality {
+                    ChordQuality::Major | ChordQuality::Dominant7
+                        if root_number == (target_number + 7).rem_euclid(12) =>
+                    {
+                        Some(5)
+                    }
+                    ChordQuality::Diminished
+                    | ChordQuality::Diminished7
+                    | ChordQuality::HalfDiminished7
+                        if root_number == (target_number - 1).rem_euclid(12) =>
+                    {
+                        Some(7)
+                    }
+                    _ => None,
+                };
+                if let Some(function) = function {
+                    return Self {
+                        degree: function,
+                        alteration: 0,
+                        quality,
+                        inversion,
+                        secondary: Some((target, third == 3)),
+                    };
+                }
+            }
+        }
+
+        Self {
+            degree: degree + 1,
+            alteration,
+            quality,
+            inversion,
+            secondary: None,
+        }
+    }
+
+    /// The scale degree, from 1 to 7. For a secondary function this is the
+    /// degree within the tonicised key.
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// How far the root lies from the diatonic degree, in semitones.
+    pub fn alteration(&self) -> i32 {
+        self.alteration
+    }
+
+    pub fn quality(&self) -> ChordQuality {
+        self.quality
+    }
+
+    pub fn inversion(&self) -> Option<usize> {
+        self.inversion
+    }
+
+    /// The tonicised degree of a secondary function, such as 5 in `V/V`.
+    pub fn secondary(&self) -> Option<usize> {
+        self.secondary.map(|(degree, _)| degree)
+    }
+
+    /// Figured-bass inversion figures: `6` and `64` for triads, `7`, `65`,
+    /// `43` and `42` for seventh chords.
+    pub fn figures(&self) -> &'static str {
+        let sevenths = ["7", "65", "43", "42"];
+        match (self.quality.intervals().len(), self.inversion) {
+            (4, inversion) => sevenths[inversion.unwrap_or(0)],
+            (_, Some(1)) => "6",
+            (_, Some(2)) => "64",
+            _ => "",
+        }
+    }
+}
+
+impl fmt::Display for RomanNumeral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let alteration = match self.alteration {
+            a if a < 0 => "b".repeat(a.unsigned_abs() as usize),
+            a => "#".repeat(a as usize),
+        };
+        let numeral = NUMERALS[self.degree - 1];
+        let (is_minor, marker) = match self.quality {
+            ChordQuality::Minor | ChordQuality::Minor7 => (true, ""),
+            ChordQuality::Diminished | ChordQuality::Diminished7 => (true, "o"),
+            ChordQuality::HalfDiminished7 => (true, "ø"),
+            ChordQuality::MinorMajor7 => (true, "M"),
+            ChordQuality::Major7 => (false, "M"),
+            ChordQuality::Augmented | ChordQuality::Augmented7 => (false, "+"),
+            ChordQuality::AugmentedMajor7 => (false, "+M"),
+            ChordQuality::Suspended2 | ChordQuality::Suspended4 | ChordQuality::Power => {
+                (false, self.quality.suffix())
+            }
+            _ => (false, ""),
+        };
+        let numeral = match is_minor {
+            true => numeral.to_lowercase(),
+            false => numeral.to_string(),
+        };
+        write!(f, "{alteration}{numeral}{marker}{}", self.figures())?;
+        if let Some((target, is_minor)) = self.secondary {
+            let target = NUMERALS[target - 1];
+            match is_minor {
+                true => write!(f, "/{}", target.to_lowercase())?,
+                false => write!(f, "/{target}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The analysis of one vertical moment, lasting until the next.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HarmonicEvent {
+    pub start_offset: Duration,
+    pub stop_offset: Duration,
+    pub roman_numeral: Option<RomanNumeral>,
+}
+
+/// Reads each vertical moment of `component` in `key`. Moments whose notes do
+/// not form a recognisable chord have no numeral.
+pub fn roman_numeral_analysis(component: &ScoreObject, key: &KeySignature) -> Vec<HarmonicEvent> {
+    let moments = component.vertical_moments();
+    let end = component
+        .leaf_timespans()
+        .iter()
+        .map(|timespan| timespan.stop_offset)
+        .max()
+        .unwrap_or(Duration::new(0, 1));
+    moments
+        .iter()
+        .enumerate()
+        .map(|(i, moment)| {
+            let pitches: Vec<Pitch> = moment
+                .timespans
+                .iter()
+                .flat_map(|timespan| match timespan.leaf {
+                    ScoreObject::Note { written_pitch, .. } => vec![*written_pitch],
+                    ScoreObject::Chord {
+                        written_pitches, ..
+                    } => written_pitches.to_vec(),
+                    _ => vec![],
+                })
+                .collect();
+            HarmonicEvent {
+                start_offset: moment.offset,
+                stop_offset: moments.get(i + 1).map_or(end, |next| next.offset),
+                roman_numeral: ChordAnalysis::analyze(&pitches)
+                    .map(|chord| RomanNumeral::from_chord(&chord, key)),
+            }
+        })
+        .collect()
+}
+
+/// Writes an analysis out as a `Lyrics` voice of numerals, or as a
+/// `FiguredBass` voice of inversion figures, separated by spaces (`6 5`).
+/// Unanalysed moments, and root position triads in figured bass, become
+/// spacers.
+pub fn analysis_voice(
+    events: &[HarmonicEvent],
+    context: VoiceContext,
+) -> Result<ScoreObject, Error> {
+    let mut contents = vec![];
+    let mut offset = Duration::new(0, 1);
+    for event in events {
+        let text = event.roman_numeral.as_ref().map(|numeral| match context {
+            VoiceContext::FiguredBass => numeral
+                .figures()
+                .chars()
+                .map(String::from)
+                .collect::<Vec<_>>()
+                .join(" "),
+            _ => numeral.to_string(),
+        });
+        let gap = event.start_offset - offset;
+        if gap > Duration::new(0, 1) {
+            for part in gap.to_printable_parts()? {
+                contents.push(ScoreObject::Spacer {
+                    written_duration: part,
+                });
+            }
+        }
+        for (i, part) in (event.stop_offset - event.start_offset)
+            .to_printable_parts()?
+            .into_iter()
+            .enumerate()
+        {
+            contents.push(match &text {
+                Some(text) if i == 0 && !text.is_empty() => lyric(text.clone(), part)?,
+                _ => ScoreObject::Spacer {
+                    written_duration: part,
+                },
+            });
+        }
+        offset = event.stop_offset;
+    }
+    let mut voice = voice(contents)?;
+    voice.set_voice_context(context);
+    Ok(voice)
+}
+
+/// Recasts sixth chords as the seventh chords they invert, which is how
+/// Roman numerals name them.
+fn tertian(chord: &ChordAnalysis) -> (PitchClass, ChordQuality, Option<usize>) {
+    let sixth = |quality| {
+        let (letter, _) = chord.root().to_pair();
+        let root = DiatonicPitchClass::from_index(letter.index() + 5);
+        let accidental = Accidental::from_quarter_tones(
+            (((number(&chord.root()) + 9 - letter_number(root)) + 6).rem_euclid(12) - 6) * 2,
+        )
+        .unwrap_or(Accidental::Natural);
+        let inversion = chord.inversion().map(|i| (i + 1) % 4);
+        (PitchClass::new(root, accidental), quality, inversion)
+    };
+    match chord.quality() {
+        ChordQuality::Major6 => sixth(ChordQuality::Minor7),
+        ChordQuality::Minor6 => sixth(ChordQuality::HalfDiminished7),
+        quality => (chord.root(), quality, chord.inversion()),
+    }
+}
+
+fn number(pitch_class: &PitchClass) -> i32 {
+    pitch_class.quarter_tones().div_euclid(2)
+}
+
+fn letter_number(letter: DiatonicPitchClass) -> i32 {
+    letter.quarter_tones() / 2
+}
+
+/// Pitch numbers native to the key: the scale, plus the raised leading tone
+/// in minor.
+fn allowed_numbers(key: &KeySignature) -> Vec<i32> {
+    let mut numbers: Vec<i32> = key.scale().iter().map(number).collect();
+    if key.mode() == Mode::Minor {
+        numbers.push((number(&key.tonic()) - 1).rem_euclid(12));
+    }
+    numbers
+}
+
+/// The third and fifth above a degree of the harmonic scale, in semitones.
+fn triad_on(key: &KeySignature, index: usize) -> (i32, i32) {
+    let mut numbers: Vec<i32> = key.scale().iter().map(number).collect();
+    if key.mode() == Mode::Minor {
+        numbers[6] = (numbers[0] - 1).rem_euclid(12);
+    }
+    let above = |steps: usize| (numbers[(index + steps) % 7] - numbers[index]).rem_euclid(12);
+    (above(2), above(4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{analysis_voice, roman_numeral_analysis, RomanNumeral};
+    use crate::duration::Duration;
+    use crate::harmony::ChordAnalysis;
+    use crate::pitch::{
+        Accidental, Accidental::*, DiatonicPitchClass, DiatonicPitchClass::*, KeySignature, Mode,
+        Pitch, PitchClass,
+    };
+    use crate::score_object::{chord, rest, staff, voice, ScoreObject, VoiceContext};
+
+    fn p(dpc: DiatonicPitchClass, accidental: Accidental, octave: i32) -> Pitch {
+        Pitch::new(PitchClass::new(dpc, accidental), octave)
+    }
+
+    fn numeral(pitches: &[Pitch], key: &KeySignature) -> String {
+        RomanNumeral::from_chord(&ChordAnalysis::analyze(pitches).unwrap(), key).to_string()
+    }
+
+    fn c_major() -> KeySignature {
+        KeySignature::new(PitchClass::new(C, Natural), Mode::Major).unwrap()
+    }
+
+    #[test]
+    fn diatonic() {
+        let key = c_major();
+        assert_eq!(
+            numeral(
+                &[p(C, Natural, 4), p(E, Natural, 4), p(G, Natural, 4)],
+                &key
+            ),
+            "I"
+        );
+        assert_eq!(
+            numeral(
+                &[p(F, Natural, 3), p(A, Natural, 3), p(D, Natural, 4)],
+                &key
+            ),
+            "ii6"
+        );
+        assert_eq!(
+            numeral(
+                &[p(D, Natural, 3), p(G, Natural, 3), p(B, Natural, 3)],
+                &key
+            ),
+            "V64"
+        );
+        assert_eq!(
+            numeral(
+                &[
+                    p(B, Natural, 2),
+                    p(D, Natural, 3),
+                    p(F, Natural, 3),
+                    p(G, Natural, 3)
+                ],
+                &key
+            ),
+            "V65"
+        );
+        assert_eq!(
+            numeral(
+                &[
+                    p(B, Natural, 3),
+                    p(D, Natural, 4),
+                    p(F, Natural, 4),
+                    p(A, Natural, 4)
+                ],
+                &key
+            ),
+            "viiø7"
+        );
+        // Spelled as F6, read as ii65.
+        assert_eq!(
+            numeral(
+                &[
+                    p(F, Natural, 3),
+                    p(A, Natural, 3),
+                    p(C, Natural, 4),
+                    p(D, Natural, 4)
+                ],
+                &key
+            ),
+            "ii65"
+        );
+    }
+
+    #[test]
+    fn minor_keys() {
+        let key = KeySignature::new(PitchClass::new(A, Natural), Mode::Minor).unwrap();
+        assert_eq!(
+            numeral(
+                &[p(A, Natural, 3), p(C, Natural, 4), p(E, Natural, 4)],
+                &key
+            ),
+            "i"
+        );
+        assert_eq!(
+            numeral(&[p(E, Natural, 3), p(G, Sharp, 3), p(B, Natural, 3)], &key),
+            "V"
+        );
+        assert_eq!(
+            numeral(
+                &[
+                    p(G, Sharp, 3),
+                    p(B, Natural, 3),
+                    p(D, Natural, 4),
+                    p(F, Natural, 4)
+                ],
+                &key
+            ),
+            "viio7"
+        );
+        assert_eq!(
+            numeral(
+                &[p(G, Natural, 3), p(B, Natural, 3), p(D, Natural, 4)],
+                &key
+            ),
+            "VII"
+        );
+    }
+
+    #[test]
+    fn secondary_and_chromatic() {
+        let key = c_major();
+        let v_of_v =
+            ChordAnalysis::analyze(&[p(D, Natural, 4), p(F, Sharp, 4), p(A, Natural, 4)]).unwrap();
+        let numeral = RomanNumeral::from_chord(&v_of_v, &key);
+        assert_eq!(numeral.secondary(), Some(5));
+        assert_eq!(numeral.to_string(), "V/V");
+
+        assert_eq!(
+            super::tests::numeral(
+                &[
+                    p(E, Natural, 3),
+                    p(G, Sharp, 3),
+                    p(B, Natural, 3),
+                    p(D, Natural, 4)
+                ],
+                &key
+            ),
+            "V7/vi"
+        );
+        assert_eq!(
+            super::tests::numeral(
+                &[
+                    p(F, Sharp, 3),
+                    p(A, Natural, 3),
+                    p(C, Natural, 4),
+                    p(E, Flat, 4)
+                ],
+                &key
+            ),
+            "viio7/V"
+        );
+        assert_eq!(
+            super::tests::numeral(&[p(A, Flat, 3), p(C, Natural, 4), p(E, Flat, 4)], &key),
+            "bVI"
+        );
+    }
+
+    fn cadence() -> ScoreObject {
+        let half = Duration::new(1, 2);
+        let upper = voice(vec![
+            chord(vec![p(E, Natural, 4), p(G, Natural, 4)], half).unwrap(),
+            chord(vec![p(D, Natural, 4), p(F, Natural, 4)], half).unwrap(),
+            rest(half).unwrap(),
+        ])
+        .unwrap();
+        let lower = voice(vec![
+            chord(vec![p(C, Natural, 3)], half).unwrap(),
+            chord(vec![p(B, Natural, 2), p(G, Natural, 3)], half).unwrap(),
+        ])
+        .unwrap();
+        let mut s = staff(vec![upper, lower]).unwrap();
+        s.set_is_simultaneous(true);
+        s
+    }
+
+    #[test]
+    fn timeline() {
+        let events = roman_numeral_analysis(&cadence(), &c_major());
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].roman_numeral.as_ref().unwrap().to_string(), "I");
+        assert_eq!(events[1].roman_numeral.as_ref().unwrap().to_string(), "V65");
+        assert_eq!(events[1].stop_offset, Duration::new(1, 1));
+        assert!(events[2].roman_numeral.is_none());
+    }
+
+    #[test]
+    fn export() {
+        let events = roman_numeral_analysis(&cadence(), &c_major());
+
+        let lyrics = analysis_voice(&events, VoiceContext::Lyrics).unwrap();
+        assert_eq!(lyrics.voice_context(), Some(&VoiceContext::Lyrics));
+        assert_eq!(lyrics.duration(), Duration::new(3, 2));
+        assert_eq!(lyrics[0].text(), Some("I"));
+        assert_eq!(lyrics[1].text(), Some("V65"));
+        assert!(matches!(lyrics[2], ScoreObject::Spacer { .. }));
+
+        let figures = analysis_voice(&events, VoiceContext::FiguredBass).unwrap();
+        assert!(matches!(figures[0], ScoreObject::Spacer { .. }));
+        assert_eq!(figures[1].text(), Some("6 5"));
+    }
+}
@@ -0,0 +1,306 @@
+use crate::{
+    duration::Duration,
+    has_semitones::HasSemitones,
+    pitch::{Interval, KeySignature, Pitch},
+    score_object::ScoreObject,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum VoiceLeadingKind {
+    ParallelFifths,
+    ParallelOctaves,
+    HiddenFifths,
+    HiddenOctaves,
+    VoiceCrossing,
+    VoiceOverlap,
+    Spacing,
+    UnresolvedLeadingTone,
+}
+
+/// A voice-leading fault at `offset`, with the paths of the notes involved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VoiceLeadingViolation {
+    pub offset: Duration,
+    pub paths: Vec<Vec<usize>>,
+    pub kind: VoiceLeadingKind,
+}
+
+#[derive(Clone, Copy)]
+struct Sounding<'a> {
+    pitch: Pitch,
+    path: &'a [usize],
+}
+
+/// Checks chorale-style writing in `score`. Each `Voice` (or each staff
+/// without voices) is read as one line, ordered from the top of the score
+/// down; chords, rests and spacers count as silence. Parallels and crossings
+/// are checked between every pair of lines, hidden fifths and octaves and
+/// leading-tone resolution in the outer lines only, and spacing between
+/// adjacent lines above the bass.
+pub fn check_voice_leading(score: &ScoreObject, key: &KeySignature) -> Vec<VoiceLeadingViolation> {
+    let mut lines = vec![];
+    find_lines(score, &mut vec![], &mut lines);
+    let timespans = score.leaf_timespans();
+    let moments: Vec<(Duration, Vec<Option<Sounding>>)> = score
+        .vertical_moments()
+        .iter()
+        .map(|moment| {
+            let sounding = lines
+                .iter()
+                .map(|line| {
+                    timespans
+                        .iter()
+                        .filter(|t| t.path.starts_with(line))
+                        .filter(|t| {
+                            t.start_offset <= moment.offset && moment.offset < t.stop_offset
+                        })
+                        .find_map(|t| match t.leaf {
+                            ScoreObject::Note { written_pitch, .. } => Some(Sounding {
+                                pitch: *written_pitch,
+                                path: &t.path,
+                            }),
+                            _ => None,
+                        })
+                })
+                .collect();
+            (moment.offset, sounding)
+        })
+        .collect();
+
+    let mut violations = vec![];
+    let mut report = |offset, paths: &[&[usize]], kind| {
+        violations.push(VoiceLeadingViolation {
+            offset,
+            paths: paths.iter().map(|p| p.to_vec()).collect(),
+            kind,
+        })
+    };
+
+    for (offset, sounding) in moments.iter() {
+        let active: Vec<Sounding> = sounding.iter().flatten().copied().collect();
+        for pair in active.windows(2) {
+            if pair[0].pitch.quarter_tones() < pair[1].pitch.quarter_tones() {
+                report(
+                    *offset,
+                    &[pair[0].path, pair[1].path],
+                    VoiceLeadingKind::VoiceCrossing,
+                );
+            }
+        }
+        if active.len() > 2 {
+            for pair in active[..active.len() - 1].windows(2) {
+                if pair[0].pitch.quarter_tones() - pair[1].pitch.quarter_tones() > 24 {
+                    report(
+                        *offset,
+                        &[pair[0].path, pair[1].path],
+                        VoiceLeadingKind::Spacing,
+                    );
+                }
+            }
+        }
+    }
+
+    for window in moments.windows(2) {
+        let (_, before) = &window[0];
+        let (offset, after) = &window[1];
+        let moving: Vec<usize> = (0..lines.len())
+            .filter(|i| match (before[*i], after[*i]) {
+                (Some(b), Some(a)) => b.pitch != a.pitch,
+                _ => false,
+            })
+            .collect();
+        let outer = (
+            (0..lines.len()).find(|i| before[*i].is_some() && after[*i].is_some()),
+            (0..lines.len())
+                .rev()
+                .find(|i| before[*i].is_some() && after[*i].is_some()),
+        );
+
+        for (upper, lower) in pairs(lines.len()) {
+            let (Some(ub), Some(ua), Some(lb), Some(la)) =
+                (before[upper], after[upper], before[lower], after[lower])
+            else {
+                continue;
+            };
+            if !moving.contains(&upper) && !moving.contains(&lower) {
+                continue;
+            }
+            let paths: [&[usize]; 2] = [ua.path, la.path];
+
+            let crossed = ub.pitch.quarter_tones() < lb.pitch.quarter_tones()
+                || ua.pitch.quarter_tones() < la.pitch.quarter_tones();
+            if !crossed
+                && (ua.pitch.quarter_tones() < lb.pitch.quarter_tones()
+                    || la.pitch.quarter_tones() > ub.pitch.quarter_tones())
+            {
+                report(*offset, &paths, VoiceLeadingKind::VoiceOverlap);
+            }
+
+            let from = Interval::between(&lb.pitch, &ub.pitch);
+            let to = Interval::between(&la.pitch, &ua.pitch);
+            let upper_motion = ua.pitch.quarter_tones() - ub.pitch.quarter_tones();
+            let lower_motion = la.pitch.quarter_tones() - lb.pitch.quarter_tones();
+            let both_move = upper_motion != 0 && lower_motion != 0;
+            let similar = upper_motion.signum() * lower_motion.signum() > 0;
+
+            if both_move && from.is_perfect_fifth() && to.is_perfect_fifth() {
+                report(*offset, &paths, VoiceLeadingKind::ParallelFifths);
+            } else if both_move && from.is_perfect_octave() && to.is_perfect_octave() {
+                report(*offset, &paths, VoiceLeadingKind::ParallelOctaves);
+            } else if outer == (Some(upper), Some(lower))
+                && similar
+                && Interval::between(&ub.pitch, &ua.pitch).number() > 2
+            {
+                if to.is_perfect_fifth() && !from.is_perfect_fifth() {
+                    report(*offset, &paths, VoiceLeadingKind::HiddenFifths);
+                } else if to.is_perfect_octave() && !from.is_perfect_octave() {
+                    report(*offset, &paths, VoiceLeadingKind::HiddenOctaves);
+                }
+            }
+        }
+
+        let tonic = key.tonic().quarter_tones();
+        for line in [outer.0, outer.1].into_iter().flatten() {
+            if !moving.contains(&line) {
+                continue;
+            }
+            let (Some(b), Some(a)) = (before[line], after[line]) else {
+                continue;
+            };
+            let is_leading_tone = (b.pitch.quarter_tones() - tonic + 2).rem_euclid(24) == 0;
+            let resolves = Interval::between(&b.pitch, &a.pitch) == Interval::new(1, 2);
+            if is_leading_tone && !resolves && b.pitch != a.pitch {
+                report(
+                    *offset,
+                    &[b.path, a.path],
+                    VoiceLeadingKind::UnresolvedLeadingTone,
+                );
+            }
+        }
+    }
+
+    violations
+}
+
+fn find_lines(component: &ScoreObject, path: &mut Vec<usize>, lines: &mut Vec<Vec<usize>>) {
+    let contents = component.contents().map(|c| c.as_slice()).unwrap_or(&[]);
+    let has_voices = contents
+        .iter()
+        .any(|child| matches!(child, ScoreObject::Voice { .. }));
+    match component {
+        ScoreObject::Voice { .. } => lines.push(path.clone()),
+        ScoreObject::Staff { .. } if !has_voices => lines.push(path.clone()),
+        _ => {
+            for (i, child) in contents.iter().enumerate() {
+                path.push(i);
+                find_lines(child, path, lines);
+                path.pop();
+            }
+        }
+    }
+}
+
+fn pairs(count: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..count).flat_map(move |upper| (upper + 1..count).map(move |lower| (upper, lower)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_voice_leading, VoiceLeadingKind, VoiceLeadingKind::*};
+    use crate::duration::Duration;
+    use crate::pitch::Accidental::Natural;
+    use crate::pitch::{
+        DiatonicPitchClass, DiatonicPitchClass::*, KeySignature, Mode, Pitch, PitchClass,
+    };
+    use crate::score_object::{note, ScoreBuilder, ScoreObject};
+
+    fn line(pitches: &[(DiatonicPitchClass, i32)]) -> Vec<ScoreObject> {
+        pitches
+            .iter()
+            .map(|(dpc, octave)| {
+                note(
+                    Pitch::new(PitchClass::new(*dpc, Natural), *octave),
+                    Duration::new(1, 4),
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn chorale(voices: &[&[(DiatonicPitchClass, i32)]]) -> ScoreObject {
+        let mut builder = ScoreBuilder::new();
+        for (i, pitches) in voices.iter().enumerate() {
+            builder = builder.staff(format!("staff {i}"), |s| {
+                s.voice(format!("voice {i}"), |v| v.extend(line(pitches)))
+            });
+        }
+        builder.build().unwrap()
+    }
+
+    fn kinds(score: &ScoreObject) -> Vec<VoiceLeadingKind> {
+        let key = KeySignature::new(PitchClass::new(C, Natural), Mode::Major).unwrap();
+        check_voice_leading(score, &key)
+            .into_iter()
+            .map(|v| v.kind)
+            .collect()
+    }
+
+    #[test]
+    fn parallels() {
+        let fifths = chorale(&[&[(G, 4), (A, 4)], &[(C, 4), (D, 4)]]);
+        assert_eq!(kinds(&fifths), vec![ParallelFifths]);
+
+        let octaves = chorale(&[&[(C, 5), (D, 5)], &[(C, 4), (D, 4)]]);
+        assert_eq!(kinds(&octaves), vec![ParallelOctaves]);
+
+        let contrary = chorale(&[&[(E, 4), (D, 4)], &[(C, 4), (G, 3)]]);
+        assert!(kinds(&contrary).is_empty());
+    }
+
+    #[test]
+    fn hidden() {
+        let hidden_fifth = chorale(&[&[(E, 4), (A, 4)], &[(C, 4), (D, 4)]]);
+        assert_eq!(kinds(&hidden_fifth), vec![HiddenFifths]);
+
+        let hidden_octave = chorale(&[&[(A, 4), (C, 5)], &[(F, 3), (C, 4)]]);
+        assert_eq!(kinds(&hidden_octave), vec![HiddenOctaves]);
+
+        // Stepwise motion in the soprano is allowed.
+        let stepwise = chorale(&[&[(A, 4), (G, 4)], &[(F, 3), (C, 4)]]);
+        assert!(kinds(&stepwise).is_empty());
+    }
+
+    #[test]
+    fn crossing_overlap_and_spacing() {
+        let crossing = chorale(&[&[(C, 4), (C, 4)], &[(E, 4), (E, 4)]]);
+        assert_eq!(kinds(&crossing), vec![VoiceCrossing, VoiceCrossing]);
+
+        let overlap = chorale(&[&[(E, 4), (A, 4)], &[(C, 4), (F, 4)], &[(C, 3), (C, 3)]]);
+        assert_eq!(kinds(&overlap), vec![VoiceOverlap]);
+
+        let spaced = chorale(&[&[(G, 5)], &[(E, 4)], &[(C, 3)]]);
+        assert_eq!(kinds(&spaced), vec![Spacing]);
+
+        // Wide spacing above the bass is fine.
+        let open = chorale(&[&[(G, 4)], &[(E, 4)], &[(C, 2)]]);
+        assert!(kinds(&open).is_empty());
+    }
+
+    #[test]
+    fn leading_tones() {
+        let unresolved = chorale(&[&[(B, 4), (A, 4)], &[(G, 3), (F, 3)]]);
+        assert!(kinds(&unresolved).contains(&UnresolvedLeadingTone));
+
+        let resolved = chorale(&[&[(B, 4), (C, 5)], &[(G, 3), (C, 3)]]);
+        assert!(!kinds(&resolved).contains(&UnresolvedLeadingTone));
+    }
+
+    #[test]
+    fn reports_paths_and_offsets() {
+        let fifths = chorale(&[&[(G, 4), (A, 4)], &[(C, 4), (D, 4)]]);
+        let key = KeySignature::new(PitchClass::new(C, Natural), Mode::Major).unwrap();
+        let violations = check_voice_leading(&fifths, &key);
+        assert_eq!(violations[0].offset, Duration::new(1, 4));
+        assert_eq!(violations[0].paths, vec![vec![0, 0, 1], vec![1, 0, 1]]);
+    }
+}
@@ -1,3 +1,9 @@
+/// Pitch height measured exactly in quarter tones: above C for pitch classes
+/// and their parts, and above middle C for pitches.
 pub trait HasSemitones {
-    fn semitones(&self) -> f32;
+    fn quarter_tones(&self) -> i32;
+
+    fn semitones(&self) -> f32 {
+        self.quarter_tones() as f32 / 2.
+    }
 }
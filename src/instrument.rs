@@ -0,0 +1,313 @@
+use crate::{
+    has_semitones::HasSemitones,
+    pitch::{Accidental, DiatonicPitchClass, Interval, Pitch, PitchClass},
+    score_object::StaffContext,
+};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Clef {
+    Treble,
+    /// Treble clef sounding an octave lower, as for tenor voice and guitar.
+    TrebleOctaveDown,
+    Alto,
+    Tenor,
+    Bass,
+    Percussion,
+}
+
+/// A player's instrument. The range is given in sounding pitch, and the
+/// transposition is the interval from written to sounding pitch, so a
+/// B-flat clarinet transposes by `-M2`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Instrument {
+    name: String,
+    short_name: String,
+    lowest_pitch: Pitch,
+    highest_pitch: Pitch,
+    transposition: Interval,
+    clef: Clef,
+    staff_context: StaffContext,
+}
+
+impl Instrument {
+    /// A non-transposing instrument in treble clef on an ordinary staff.
+    pub fn new(
+        name: impl Into<String>,
+        short_name: impl Into<String>,
+        lowest_pitch: Pitch,
+        highest_pitch: Pitch,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            short_name: short_name.into(),
+            lowest_pitch,
+            highest_pitch,
+            transposition: Interval::new(0, 0),
+            clef: Clef::Treble,
+            staff_context: StaffContext::Staff,
+        }
+    }
+
+    pub fn with_transposition(mut self, transposition: Interval) -> Self {
+        self.transposition = transposition;
+        self
+    }
+
+    pub fn with_clef(mut self, clef: Clef) -> Self {
+        self.clef = clef;
+        self
+    }
+
+    pub fn with_staff_context(mut self, staff_context: StaffContext) -> Self {
+        self.staff_context = staff_context;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn short_name(&self) -> &str {
+        &self.short_name
+    }
+
+    /// The lowest and highest sounding pitches.
+    pub fn range(&self) -> (Pitch, Pitch) {
+        (self.lowest_pitch, self.highest_pitch)
+    }
+
+    pub fn transposition(&self) -> Interval {
+        self.transposition
+    }
+
+    pub fn is_transposing(&self) -> bool {
+        self.transposition != Interval::new(0, 0)
+    }
+
+    pub fn clef(&self) -> Clef {
+        self.clef
+    }
+
+    pub fn staff_context(&self) -> &StaffContext {
+        &self.staff_context
+    }
+
+    /// Whether a sounding pitch lies within the range, compared by height so
+    /// that enharmonic spellings of the extremes are accepted.
+    pub fn contains(&self, sounding_pitch: &Pitch) -> bool {
+        let height = sounding_pitch.quarter_tones();
+        self.lowest_pitch.quarter_tones() <= height && height <= self.highest_pitch.quarter_tones()
+    }
+
+    pub fn piccolo() -> Self {
+        Self::new("Piccolo", "Picc.", pitch("d", 5), pitch("c", 8))
+            .with_transposition(interval("P8"))
+    }
+
+    pub fn flute() -> Self {
+        Self::new("Flute", "Fl.", pitch("c", 4), pitch("d", 7))
+    }
+
+    pub fn oboe() -> Self {
+        Self::new("Oboe", "Ob.", pitch("bf", 3), pitch("a", 6))
+    }
+
+    pub fn english_horn() -> Self {
+        Self::new("English Horn", "E.H.", pitch("e", 3), pitch("c", 6))
+            .with_transposition(interval("-P5"))
+    }
+
+    pub fn clarinet_in_b_flat() -> Self {
+        Self::new("Clarinet in B-flat", "Cl.", pitch("d", 3), pitch("bf", 6))
+            .with_transposition(interval("-M2"))
+    }
+
+    pub fn clarinet_in_a() -> Self {
+        Self::new("Clarinet in A", "Cl.", pitch("cs", 3), pitch("a", 6))
+            .with_transposition(interval("-m3"))
+    }
+
+    pub fn bass_clarinet() -> Self {
+        Self::new("Bass Clarinet", "B. Cl.", pitch("df", 2), pitch("f", 5))
+            .with_transposition(interval("-M9"))
+    }
+
+    pub fn bassoon() -> Self {
+        Self::new("Bassoon", "Bsn.", pitch("bf", 1), pitch("e", 5)).with_clef(Clef::Bass)
+    }
+
+    pub fn contrabassoon() -> Self {
+        Self::new("Contrabassoon", "Cbsn.", pitch("bf", 0), pitch("f", 3))
+            .with_transposition(interval("-P8"))
+            .with_clef(Clef::Bass)
+    }
+
+    pub fn horn_in_f() -> Self {
+        Self::new("Horn in F", "Hn.", pitch("b", 1), pitch("f", 5))
+            .with_transposition(interval("-P5"))
+    }
+
+    pub fn trumpet_in_b_flat() -> Self {
+        Self::new("Trumpet in B-flat", "Tpt.", pitch("e", 3), pitch("c", 6))
+            .with_transposition(interval("-M2"))
+    }
+
+    pub fn trumpet_in_c() -> Self {
+        Self::new("Trumpet in C", "Tpt.", pitch("fs", 3), pitch("d", 6))
+    }
+
+    pub fn trombone() -> Self {
+        Self::new("Trombone", "Tbn.", pitch("e", 2), pitch("f", 5)).with_clef(Clef::Bass)
+    }
+
+    pub fn bass_trombone() -> Self {
+        Self::new("Bass Trombone", "B. Tbn.", pitch("bf", 1), pitch("bf", 4)).with_clef(Clef::Bass)
+    }
+
+    pub fn tuba() -> Self {
+        Self::new("Tuba", "Tba.", pitch("d", 1), pitch("f", 4)).with_clef(Clef::Bass)
+    }
+
+    pub fn timpani() -> Self {
+        Self::new("Timpani", "Timp.", pitch("d", 2), pitch("c", 4)).with_clef(Clef::Bass)
+    }
+
+    pub fn guitar() -> Self {
+        Self::new("Guitar", "Gtr.", pitch("e", 2), pitch("b", 5))
+            .with_transposition(interval("-P8"))
+            .with_clef(Clef::TrebleOctaveDown)
+    }
+
+    pub fn violin() -> Self {
+        Self::new("Violin", "Vn.", pitch("g", 3), pitch("a", 7))
+    }
+
+    pub fn viola() -> Self {
+        Self::new("Viola", "Va.", pitch("c", 3), pitch("e", 6)).with_clef(Clef::Alto)
+    }
+
+    pub fn cello() -> Self {
+        Self::new("Cello", "Vc.", pitch("c", 2), pitch("a", 5)).with_clef(Clef::Bass)
+    }
+
+    pub fn contrabass() -> Self {
+        Self::new("Contrabass", "Cb.", pitch("e", 1), pitch("g", 4))
+            .with_transposition(interval("-P8"))
+            .with_clef(Clef::Bass)
+    }
+
+    pub fn soprano_voice() -> Self {
+        Self::new("Soprano", "S.", pitch("c", 4), pitch("c", 6))
+    }
+
+    pub fn alto_voice() -> Self {
+        Self::new("Alto", "A.", pitch("f", 3), pitch("f", 5))
+    }
+
+    pub fn tenor_voice() -> Self {
+        Self::new("Tenor", "T.", pitch("c", 3), pitch("c", 5)).with_clef(Clef::TrebleOctaveDown)
+    }
+
+    pub fn bass_voice() -> Self {
+        Self::new("Bass", "B.", pitch("e", 2), pitch("e", 4)).with_clef(Clef::Bass)
+    }
+
+    pub fn percussion() -> Self {
+        Self::new("Percussion", "Perc.", pitch("c", 0), pitch("c", 8))
+            .with_clef(Clef::Percussion)
+            .with_staff_context(StaffContext::DrumStaff)
+    }
+
+    /// Every instrument above, in score order.
+    pub fn catalogue() -> Vec<Self> {
+        vec![
+            Self::piccolo(),
+            Self::flute(),
+            Self::oboe(),
+            Self::english_horn(),
+            Self::clarinet_in_b_flat(),
+            Self::clarinet_in_a(),
+            Self::bass_clarinet(),
+            Self::bassoon(),
+            Self::contrabassoon(),
+            Self::horn_in_f(),
+            Self::trumpet_in_b_flat(),
+            Self::trumpet_in_c(),
+            Self::trombone(),
+            Self::bass_trombone(),
+            Self::tuba(),
+            Self::timpani(),
+            Self::percussion(),
+            Self::guitar(),
+            Self::soprano_voice(),
+            Self::alto_voice(),
+            Self::tenor_voice(),
+            Self::bass_voice(),
+            Self::violin(),
+            Self::viola(),
+            Self::cello(),
+            Self::contrabass(),
+        ]
+    }
+}
+
+/// A catalogue pitch from a LilyPond-style name such as `bf` or `cs`.
+fn pitch(name: &str, octave: i32) -> Pitch {
+    let letter = match &name[..1] {
+        "c" => DiatonicPitchClass::C,
+        "d" => DiatonicPitchClass::D,
+        "e" => DiatonicPitchClass::E,
+        "f" => DiatonicPitchClass::F,
+        "g" => DiatonicPitchClass::G,
+        "a" => DiatonicPitchClass::A,
+        _ => DiatonicPitchClass::B,
+    };
+    let accidental = match &name[1..] {
+        "f" => Accidental::Flat,
+        "s" => Accidental::Sharp,
+        _ => Accidental::Natural,
+    };
+    Pitch::new(PitchClass::new(letter, accidental), octave)
+}
+
+fn interval(name: &str) -> Interval {
+    name.parse().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pitch, Clef, Instrument};
+    use crate::pitch::Interval;
+    use crate::score_object::StaffContext;
+
+    #[test]
+    fn catalogue() {
+        let catalogue = Instrument::catalogue();
+        assert!(catalogue.len() > 20);
+        for instrument in catalogue.iter() {
+            let (lowest, highest) = instrument.range();
+            assert!(lowest < highest, "{}", instrument.name());
+        }
+
+        let clarinet = Instrument::clarinet_in_b_flat();
+        assert_eq!(clarinet.transposition(), Interval::new(-1, -4));
+        assert!(clarinet.is_transposing());
+        assert!(!Instrument::flute().is_transposing());
+        assert_eq!(Instrument::viola().clef(), Clef::Alto);
+        assert_eq!(
+            Instrument::percussion().staff_context(),
+            &StaffContext::DrumStaff
+        );
+    }
+
+    #[test]
+    fn contains() {
+        let violin = Instrument::violin();
+        assert!(violin.contains(&pitch("g", 3)));
+        assert!(violin.contains(&pitch("a", 7)));
+        assert!(!violin.contains(&pitch("fs", 3)));
+        assert!(!violin.contains(&pitch("as", 7)));
+    }
+}
@@ -0,0 +1,28 @@
+//! Humdrum `**kern`.
+//!
+//! Spines are read right to left as staves from top to bottom, following the
+//! Humdrum convention that the lowest part is leftmost. Split spines (`*^`)
+//! become voices of their staff and are padded with spacers up to the point
+//! where they begin. Recip values that are not powers of two, such as `6` or
+//! `12`, are read as tuplet notes: consecutive notes sharing a tuplet ratio
+//! are grouped into a `Tuplet` that closes once it fills a power-of-two
+//! duration. Bars that fill the current `*M` meter become `Measure`s.
+//!
+//! Non-`**kern` spines are skipped, as are grace notes, articulations, beams
+//! and comments. Invisible rests (`ryy`) are read and written as spacers.
+
+mod reader;
+mod writer;
+
+pub use reader::read_kern;
+pub use writer::write_kern;
+
+use crate::pitch::Accidental;
+
+const ACCIDENTALS: [(Accidental, &str); 5] = [
+    (Accidental::DoubleSharp, "##"),
+    (Accidental::Sharp, "#"),
+    (Accidental::Natural, ""),
+    (Accidental::Flat, "-"),
+    (Accidental::DoubleFlat, "--"),
+];
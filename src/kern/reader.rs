@@ -0,0 +1,630 @@
+use super::ACCIDENTALS;
+use crate::{
+    duration::Duration,
+    error::Error,
+    multiplier::Multiplier,
+    pitch::{DiatonicPitchClass, Pitch, PitchClass},
+    score_object::{chord, measure, note, rest, tuplet, BarLine, ScoreBuilder, ScoreObject},
+    time_signature::TimeSignature,
+};
+
+/// Reads a Humdrum file's `**kern` spines as a `Score`.
+pub fn read_kern(source: &str) -> Result<ScoreObject, Error> {
+    let mut reader = Reader::default();
+    for (number, line) in source.lines().enumerate() {
+        reader.line(line.trim_end()).map_err(|error| match error {
+            Error::KernParse(message) => {
+                Error::KernParse(format!("line {}: {message}", number + 1))
+            }
+            error => error,
+        })?;
+    }
+    reader.finish()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Column {
+    Kern { staff: usize, voice: usize },
+    Other,
+}
+
+struct Bar {
+    contents: Vec<ScoreObject>,
+    bar_line: BarLine,
+    time_signature: Option<TimeSignature>,
+}
+
+impl Bar {
+    fn duration(&self) -> Duration {
+        self.contents
+            .iter()
+            .fold(Duration::new(0, 1), |sum, c| sum + c.duration())
+    }
+}
+
+/// A voice's finished bars and the leaves of the bar in progress, each with
+/// the tuplet multiplier implied by its recip.
+#[derive(Default)]
+struct VoiceState {
+    bars: Vec<Bar>,
+    open: Vec<(ScoreObject, Multiplier)>,
+}
+
+impl VoiceState {
+    fn open_duration(&self) -> Duration {
+        self.open
+            .iter()
+            .fold(Duration::new(0, 1), |sum, (leaf, m)| {
+                sum + leaf.duration() * *m
+            })
+    }
+
+    fn close_bar(
+        &mut self,
+        bar_line: BarLine,
+        time_signature: Option<TimeSignature>,
+    ) -> Result<(), Error> {
+        if self.open.is_empty() {
+            if let Some(last) = self.bars.last_mut() {
+                last.bar_line = bar_line;
+            }
+            return Ok(());
+        }
+        let contents = group_tuplets(std::mem::take(&mut self.open))?;
+        self.bars.push(Bar {
+            contents,
+            bar_line,
+            time_signature,
+        });
+        Ok(())
+    }
+
+    /// Fills the time `other` has already covered with spacers.
+    fn pad_to(&mut self, other: &VoiceState) -> Result<(), Error> {
+        for bar in other.bars.iter().skip(self.bars.len()) {
+            self.bars.push(Bar {
+                contents: spacers(bar.duration())?,
+                bar_line: bar.bar_line,
+                time_signature: bar.time_signature,
+            });
+        }
+        if self.open.is_empty() && !other.open.is_empty() {
+            for spacer in spacers(other.open_duration())? {
+                self.open.push((spacer, Multiplier::new(1, 1)));
+            }
+        }
+        Ok(())
+    }
+
+    fn into_components(mut self) -> Result<Vec<ScoreObject>, Error> {
+        if !self.open.is_empty() {
+            let time_signature = self.bars.last().and_then(|bar| bar.time_signature);
+            self.close_bar(BarLine::Single, time_signature)?;
+        }
+        let mut components = vec![];
+        for bar in self.bars {
+            let duration = bar.duration();
+            match bar.time_signature {
+                Some(time_signature) if time_signature.duration() == duration => {
+                    let mut m = measure(time_signature, bar.contents)?;
+                    m.set_bar_line(bar.bar_line);
+                    components.push(m);
+                }
+                _ => components.extend(bar.contents),
+            }
+        }
+        Ok(components)
+    }
+}
+
+#[derive(Default)]
+struct StaffState {
+    name: Option<String>,
+    time_signature: Option<TimeSignature>,
+    voices: Vec<VoiceState>,
+}
+
+#[derive(Default)]
+struct Reader {
+    title: Option<String>,
+    columns: Vec<Column>,
+    staves: Vec<StaffState>,
+}
+
+impl Reader {
+    fn line(&mut self, line: &str) -> Result<(), Error> {
+        if let Some(title) = line.strip_prefix("!!!OTL:") {
+            self.title.get_or_insert_with(|| title.trim().to_string());
+            return Ok(());
+        }
+        if line.is_empty() || line.starts_with('!') {
+            return Ok(());
+        }
+        let tokens: Vec<&str> = line.split('\t').collect();
+        if line.starts_with("**") {
+            return self.exclusive_interpretations(&tokens);
+        }
+        if tokens.len() != self.columns.len() {
+            return Err(parse_error(format!(
+                "expected {} spines, found {}",
+                self.columns.len(),
+                tokens.len()
+            )));
+        }
+        if line.starts_with('*') {
+            self.interpretations(&tokens)
+        } else if line.starts_with('=') {
+            self.bar_lines(&tokens)
+        } else {
+            self.data(&tokens)
+        }
+    }
+
+    fn exclusive_interpretations(&mut self, tokens: &[&str]) -> Result<(), Error> {
+        if !self.columns.is_empty() {
+            return Err(parse_error("more than one exclusive interpretation record"));
+        }
+        for token in tokens {
+            self.columns.push(match *token {
+                "**kern" => {
+                    self.staves.push(StaffState {
+                        voices: vec![VoiceState::default()],
+                        ..Default::default()
+                    });
+                    Column::Kern {
+                        staff: self.staves.len() - 1,
+                        voice: 0,
+                    }
+                }
+                _ => Column::Other,
+            });
+        }
+        Ok(())
+    }
+
+    fn interpretations(&mut self, tokens: &[&str]) -> Result<(), Error> {
+        let mut columns = vec![];
+        let mut splits = vec![];
+        let mut i = 0;
+        while i < tokens.len() {
+            let column = self.columns[i];
+            match tokens[i] {
+                "*^" => {
+                    columns.push(column);
+                    splits.push(columns.len());
+                    columns.push(column);
+                }
+                "*v" => {
+                    columns.push(column);
+                    while tokens.get(i + 1) == Some(&"*v") {
+                        i += 1;
+                    }
+                }
+                "*-" => (),
+                "*+" | "*x" => {
+                    return Err(parse_error(format!(
+                        "unsupported spine operation `{}`",
+                        tokens[i]
+                    )))
+                }
+                token => {
+                    if let Column::Kern { staff, .. } = column {
+                        self.interpretation(staff, token)?;
+                    }
+                    columns.push(column);
+                }
+            }
+            i += 1;
+        }
+
+        for index in splits {
+            let Column::Kern { staff, voice } = columns[index] else {
+                continue;
+            };
+            let new_voice = (0..)
+                .find(|v| !columns.contains(&Column::Kern { staff, voice: *v }))
+                .unwrap();
+            columns[index] = Column::Kern {
+                staff,
+                voice: new_voice,
+            };
+            let voices = &mut self.staves[staff].voices;
+            if voices.len() <= new_voice {
+                voices.resize_with(new_voice + 1, VoiceState::default);
+            }
+            let mut state = std::mem::take(&mut voices[new_voice]);
+            state.pad_to(&voices[voice])?;
+            voices[new_voice] = state;
+        }
+        self.columns = columns;
+        Ok(())
+    }
+
+    fn interpretation(&mut self, staff: usize, token: &str) -> Result<(), Error> {
+        let staff = &mut self.staves[staff];
+        if let Some(name) = token.strip_prefix("*I\"") {
+            staff.name = Some(name.to_string());
+        } else if let Some(meter) = token
+            .strip_prefix("*M")
+            .filter(|m| m.starts_with(|c: char| c.is_ascii_digit()))
+        {
+            staff.time_signature = Some(meter.parse().map_err(|_| parse_error(token))?);
+        }
+        Ok(())
+    }
+
+    fn bar_lines(&mut self, tokens: &[&str]) -> Result<(), Error> {
+        for (column, token) in self.columns.iter().zip(tokens) {
+            if let Column::Kern { staff, voice } = column {
+                let staff = &mut self.staves[*staff];
+                let time_signature = staff.time_signature;
+                staff.voices[*voice].close_bar(bar_line(token), time_signature)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn data(&mut self, tokens: &[&str]) -> Result<(), Error> {
+        for (column, token) in self.columns.iter().zip(tokens) {
+            if let (Column::Kern { staff, voice }, false) = (column, *token == ".") {
+                if let Some(event) = event(token)? {
+                    self.staves[*staff].voices[*voice].open.push(event);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<ScoreObject, Error> {
+        let mut builder = ScoreBuilder::new();
+        if let Some(title) = self.title {
+            builder = builder.name(title);
+        }
+        let staff_count = self.staves.len();
+        for (index, staff) in self.staves.into_iter().enumerate().rev() {
+            let voices = staff
+                .voices
+                .into_iter()
+                .map(VoiceState::into_components)
+                .collect::<Result<Vec<_>, _>>()?;
+            let name = staff
+                .name
+                .unwrap_or_else(|| (staff_count - index).to_string());
+            // Voice names must be unique across the score, not just the staff.
+            builder = builder.staff(name.clone(), |mut s| {
+                for (index, components) in voices.into_iter().enumerate() {
+                    s = s.voice(format!("{name}:{}", index + 1), |v| v.extend(components));
+                }
+                s
+            });
+        }
+        builder.build()
+    }
+}
+
+fn bar_line(token: &str) -> BarLine {
+    if token.starts_with("==") {
+        BarLine::Final
+    } else if token.contains(":|") && token.contains("|:") {
+        BarLine::DoubleRepeat
+    } else if token.contains(":|") {
+        BarLine::EndRepeat
+    } else if token.contains("|:") {
+        BarLine::StartRepeat
+    } else if token.contains("||") {
+        BarLine::Double
+    } else {
+        BarLine::Single
+    }
+}
+
+struct Subtoken {
+    pitch: Option<Pitch>,
+    is_rest: bool,
+    is_invisible: bool,
+    is_tied: bool,
+    written_duration: Duration,
+    multiplier: Multiplier,
+}
+
+/// Reads a note, chord or rest token. Grace notes yield `None`.
+fn event(token: &str) -> Result<Option<(ScoreObject, Multiplier)>, Error> {
+    let subtokens = token
+        .split(' ')
+        .filter(|s| !s.is_empty())
+        .map(subtoken)
+        .collect::<Result<Vec<_>, _>>()?;
+    let subtokens: Vec<Subtoken> = subtokens.into_iter().flatten().collect();
+    let Some(first) = subtokens.first() else {
+        return Ok(None);
+    };
+    let written_duration = first.written_duration;
+    let pitches: Vec<Pitch> = subtokens.iter().filter_map(|s| s.pitch).collect();
+    let is_tied = subtokens.iter().any(|s| s.is_tied);
+    let mut leaf = match pitches.len() {
+        0 if first.is_rest && first.is_invisible => ScoreObject::Spacer { written_duration },
+        0 if first.is_rest => rest(written_duration)?,
+        0 => return Err(parse_error(format!("no pitch or rest in `{token}`"))),
+        1 => note(pitches[0], written_duration)?,
+        _ => chord(pitches, written_duration)?,
+    };
+    leaf.set_is_tied(is_tied);
+    Ok(Some((leaf, first.multiplier)))
+}
+
+fn subtoken(token: &str) -> Result<Option<Subtoken>, Error> {
+    if token.contains(['q', 'Q']) {
+        return Ok(None);
+    }
+    let mut recip = String::new();
+    let mut dots = 0;
+    let mut letters = String::new();
+    let mut accidental = String::new();
+    let mut is_rest = false;
+    let mut is_invisible = false;
+    let mut is_tied = false;
+    for c in token.chars() {
+        match c {
+            '0'..='9' | '%' => recip.push(c),
+            '.' => dots += 1,
+            'a'..='g' | 'A'..='G' => letters.push(c),
+            '#' | '-' => accidental.push(c),
+            'r' => is_rest = true,
+            'y' => is_invisible = true,
+            '[' | '_' => is_tied = true,
+            _ => (),
+        }
+    }
+    let (written_duration, multiplier) = recip_duration(&recip, dots)
+        .ok_or_else(|| parse_error(format!("bad duration in `{token}`")))?;
+    let pitch = match letters.chars().next() {
+        Some(letter) if !is_rest => {
+            if letters.chars().any(|c| c != letter) {
+                return Err(parse_error(format!("bad pitch in `{token}`")));
+            }
+            let count = letters.len() as i32;
+            let octave = if letter.is_ascii_lowercase() {
+                3 + count
+            } else {
+                4 - count
+            };
+            let diatonic_pitch_class: DiatonicPitchClass =
+                letter.to_ascii_lowercase().to_string().parse()?;
+            let (accidental, _) = ACCIDENTALS
+                .iter()
+                .find(|(_, symbol)| *symbol == accidental)
+                .ok_or_else(|| parse_error(format!("bad accidental in `{token}`")))?;
+            Some(Pitch::new(
+                PitchClass::new(diatonic_pitch_class, *accidental),
+                octave,
+            ))
+        }
+        _ => None,
+    };
+    if pitch.is_none() && !is_rest {
+        return Err(parse_error(format!("no pitch or rest in `{token}`")));
+    }
+    Ok(Some(Subtoken {
+        pitch,
+        is_rest,
+        is_invisible,
+        is_tied,
+        written_duration,
+        multiplier,
+    }))
+}
+
+/// Recips beyond these limits are refused rather than overflowing: the
+/// largest number in a recip,
+const MAX_RECIP: i32 = 1024;
+/// the most zeros (`000` is a maxima),
+const MAX_ZEROS: usize = 3;
+/// and the most dots.
+const MAX_DOTS: u32 = 8;
+
+/// The written duration and tuplet multiplier of a recip: `4` is a quarter,
+/// `0` a breve, `6` a triplet quarter and `3%2` two triplet halves.
+fn recip_duration(recip: &str, dots: u32) -> Option<(Duration, Multiplier)> {
+    let number = |digits: &str| {
+        digits
+            .parse::<i32>()
+            .ok()
+            .filter(|n| (1..=MAX_RECIP).contains(n))
+    };
+    if dots > MAX_DOTS {
+        return None;
+    }
+    let undotted = match recip.split_once('%') {
+        Some((d, n)) => Duration::new(number(n)?, number(d)?),
+        None if !recip.is_empty() && recip.chars().all(|c| c == '0') => {
+            if recip.len() > MAX_ZEROS {
+                return None;
+            }
+            Duration::new(1 << recip.len(), 1)
+        }
+        None => Duration::new(1, number(recip)?),
+    };
+    let base = undotted.equal_or_greater_power_of_two();
+    let (n, d) = (undotted / base).to_pair();
+    let written_duration = base * Duration::new((1 << (dots + 1)) - 1, 1 << dots);
+    written_duration
+        .is_printable()
+        .then_some((written_duration, Multiplier::new(n, d)))
+}
+
+/// Gathers runs of leaves with the same tuplet multiplier into tuplets, each
+/// closing once its contents fill a power-of-two duration.
+fn group_tuplets(leaves: Vec<(ScoreObject, Multiplier)>) -> Result<Vec<ScoreObject>, Error> {
+    let mut components = vec![];
+    let mut group: Vec<ScoreObject> = vec![];
+    let mut group_multiplier = Multiplier::new(1, 1);
+    let mut group_duration = Duration::new(0, 1);
+    for (leaf, multiplier) in leaves {
+        if multiplier != group_multiplier && !group.is_empty() {
+            components.push(tuplet(group_multiplier, std::mem::take(&mut group))?);
+            group_duration = Duration::new(0, 1);
+        }
+        group_multiplier = multiplier;
+        if multiplier == Multiplier::new(1, 1) {
+            components.push(leaf);
+            continue;
+        }
+        group_duration = group_duration + leaf.duration() * multiplier;
+        group.push(leaf);
+        let (_, d) = group_duration.to_pair();
+        if d & (d - 1) == 0 {
+            components.push(tuplet(multiplier, std::mem::take(&mut group))?);
+            group_duration = Duration::new(0, 1);
+        }
+    }
+    if !group.is_empty() {
+        components.push(tuplet(group_multiplier, group)?);
+    }
+    Ok(components)
+}
+
+fn spacers(duration: Duration) -> Result<Vec<ScoreObject>, Error> {
+    Ok(duration
+        .to_printable_parts()?
+        .into_iter()
+        .map(|written_duration| ScoreObject::Spacer { written_duration })
+        .collect())
+}
+
+fn parse_error(message: impl Into<String>) -> Error {
+    Error::KernParse(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_kern;
+    use crate::duration::Duration;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{BarLine, ScoreObject};
+    use crate::time_signature::TimeSignature;
+    use crate::wellformedness::check_wellformedness;
+
+    fn pitch_of(leaf: &ScoreObject) -> Pitch {
+        match leaf {
+            ScoreObject::Note { written_pitch, .. } => *written_pitch,
+            _ => panic!("not a note: {leaf:?}"),
+        }
+    }
+
+    const CHORALE: &str = "!!!OTL: Chorale\n\
+        **kern\t**dynam\t**kern\n\
+        *I\"Bass\t*\t*I\"Soprano\n\
+        *M3/4\t*\t*M3/4\n\
+        4GG\tp\t4g\n\
+        =1\t=1\t=1\n\
+        2C#\t.\t2ee-L\n\
+        4D\t.\t[4dd\n\
+        =2\t=2\t=2\n\
+        2.r\t.\t2.dd]\n\
+        ==\t==\t==\n\
+        *-\t*-\t*-\n";
+
+    #[test]
+    fn spines_become_staves() {
+        let score = read_kern(CHORALE).unwrap();
+        assert_eq!(score.name(), &Some("Chorale".to_string()));
+        assert_eq!(score.contents().unwrap().len(), 2);
+        let soprano = &score[0];
+        let bass = &score[1];
+        assert_eq!(soprano.name(), &Some("Soprano".to_string()));
+        assert_eq!(bass.name(), &Some("Bass".to_string()));
+        assert_eq!(soprano[0].name(), &Some("Soprano:1".to_string()));
+        assert!(check_wellformedness(&score).is_empty());
+
+        let unnamed = read_kern("**kern\t**kern\n4c\t4d\n*-\t*-\n").unwrap();
+        assert!(check_wellformedness(&unnamed).is_empty());
+
+        let voice = &soprano[0];
+        assert!(matches!(voice[0], ScoreObject::Note { .. }));
+        assert_eq!(voice.measures().len(), 2);
+        assert_eq!(voice[1].time_signature(), Some(&TimeSignature::new(3, 4)));
+        assert_eq!(voice[2].bar_line(), Some(&BarLine::Final));
+    }
+
+    #[test]
+    fn pitches_durations_and_ties() {
+        let score = read_kern(CHORALE).unwrap();
+        let soprano = score[0].leaves();
+        let bass = score[1].leaves();
+        assert_eq!(
+            pitch_of(soprano[0]),
+            Pitch::new(PitchClass::new(G, Natural), 4)
+        );
+        assert_eq!(
+            pitch_of(soprano[1]),
+            Pitch::new(PitchClass::new(E, Flat), 5)
+        );
+        assert_eq!(
+            pitch_of(soprano[2]),
+            Pitch::new(PitchClass::new(D, Natural), 5)
+        );
+        assert_eq!(
+            pitch_of(bass[0]),
+            Pitch::new(PitchClass::new(G, Natural), 2)
+        );
+        assert_eq!(pitch_of(bass[1]), Pitch::new(PitchClass::new(C, Sharp), 3));
+        assert!(soprano[2].is_tied());
+        assert!(!soprano[3].is_tied());
+        assert_eq!(soprano[3].duration(), Duration::new(3, 4));
+        assert!(matches!(bass[3], ScoreObject::Rest { .. }));
+    }
+
+    #[test]
+    fn tuplet_recips() {
+        let score = read_kern(
+            "**kern\n*M3/4\n12c\n12d\n12e\n6f\n6g\n6a\n=\n*M2/4\n20c\n20d\n20e\n20f\n20g\n8.a\n16b\n==\n*-\n",
+        )
+        .unwrap();
+        let voice = &score[0][0];
+        assert_eq!(voice.measures().len(), 2);
+        let first = &voice[0];
+        assert_eq!(first[0].multiplier(), Some(&Multiplier::new(2, 3)));
+        assert_eq!(first[0].leaves().len(), 3);
+        assert_eq!(first[0][0].duration(), Duration::new(1, 8));
+        assert_eq!(first[1][0].duration(), Duration::new(1, 4));
+        let second = &voice[1];
+        assert_eq!(second.time_signature(), Some(&TimeSignature::new(2, 4)));
+        assert_eq!(second[0].multiplier(), Some(&Multiplier::new(4, 5)));
+        assert_eq!(second[0].leaves().len(), 5);
+        assert_eq!(second[1].duration(), Duration::new(3, 16));
+    }
+
+    #[test]
+    fn split_spines_become_padded_voices() {
+        let score = read_kern(
+            "**kern\n*M2/4\n2c\n=\n*^\n4e\t4G\n4f\t8A\n.\t8B\n=\t=\n*v\t*v\n2g [2cc 2ee]\n==\n*-\n",
+        )
+        .unwrap();
+        let staff = &score[0];
+        assert!(staff.is_simultaneous());
+        assert_eq!(staff.contents().unwrap().len(), 2);
+        let upper = &staff[0];
+        let lower = &staff[1];
+        assert_eq!(upper.measures().len(), 3);
+        assert_eq!(lower.measures().len(), 2);
+        assert!(matches!(lower[0][0], ScoreObject::Spacer { .. }));
+        assert_eq!(lower[1].leaves().len(), 3);
+        let chord = upper[2].leaves()[0];
+        assert!(matches!(chord, ScoreObject::Chord { .. }));
+        assert!(chord.is_tied());
+    }
+
+    #[test]
+    fn errors() {
+        assert!(read_kern("**kern\t**kern\n4c\n").is_err());
+        assert!(read_kern("**kern\n4h\n").is_err());
+        assert!(read_kern("**kern\nc\n").is_err());
+        assert!(read_kern("**kern\n*x\n").is_err());
+        assert!(read_kern("**kern\n3%0c\n").is_err());
+        assert!(read_kern("**kern\n0%3c\n").is_err());
+        assert!(read_kern(&format!("**kern\n{}c\n", "0".repeat(32))).is_err());
+        assert!(read_kern(&format!("**kern\n4{}c\n", ".".repeat(30))).is_err());
+        assert!(read_kern("**kern\n2147483647c\n").is_err());
+    }
+}
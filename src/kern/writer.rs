@@ -0,0 +1,384 @@
+use std::collections::BTreeMap;
+
+use super::ACCIDENTALS;
+use crate::{
+    duration::Duration,
+    error::Error,
+    multiplier::Multiplier,
+    pitch::Pitch,
+    score_object::{BarLine, ScoreObject},
+    time_signature::TimeSignature,
+};
+
+/// Writes the staves of `score` as `**kern` spines, lowest staff first. A
+/// staff with several voices is split into one sub-spine per voice.
+pub fn write_kern(score: &ScoreObject) -> Result<String, Error> {
+    let mut staves = vec![];
+    collect_staves(score, &mut staves);
+    staves.reverse();
+
+    let mut spines = vec![];
+    for staff in staves.iter() {
+        let layers = staff_layers(staff);
+        let mut columns = vec![];
+        for layer in layers {
+            columns.push(spine(layer)?);
+        }
+        spines.push(columns);
+    }
+
+    let mut lines = vec![];
+    if let Some(name) = score.name() {
+        lines.push(format!("!!!OTL: {name}"));
+    }
+    lines.push(vec!["**kern"; staves.len()].join("\t"));
+    if staves.iter().any(|staff| staff.name().is_some()) {
+        let names: Vec<String> = staves
+            .iter()
+            .map(|staff| match staff.name() {
+                Some(name) => format!("*I\"{name}"),
+                None => "*".to_string(),
+            })
+            .collect();
+        lines.push(names.join("\t"));
+    }
+
+    let mut widths = vec![1; spines.len()];
+    while widths.iter().zip(&spines).any(|(w, s)| *w < s.len()) {
+        let tokens: Vec<String> = widths
+            .iter_mut()
+            .zip(&spines)
+            .flat_map(|(width, columns)| {
+                let mut tokens = vec!["*"; *width];
+                if *width < columns.len() {
+                    tokens[*width - 1] = "*^";
+                    *width += 1;
+                }
+                tokens
+            })
+            .map(str::to_string)
+            .collect();
+        lines.push(tokens.join("\t"));
+    }
+
+    let columns: Vec<&Spine> = spines.iter().flatten().collect();
+    let mut offsets: Vec<Duration> = columns
+        .iter()
+        .flat_map(|c| c.data.keys().chain(c.bars.keys()).chain(c.meters.keys()))
+        .copied()
+        .collect();
+    offsets.sort();
+    offsets.dedup();
+    for offset in offsets {
+        let bars: Vec<Option<&String>> = columns.iter().map(|c| c.bars.get(&offset)).collect();
+        if let Some(fallback) = bars.iter().flatten().next() {
+            let tokens: Vec<&str> = bars
+                .iter()
+                .map(|bar| bar.unwrap_or(fallback).as_str())
+                .collect();
+            lines.push(tokens.join("\t"));
+        }
+        if columns.iter().any(|c| c.meters.contains_key(&offset)) {
+            let tokens: Vec<String> = columns
+                .iter()
+                .map(|c| match c.meters.get(&offset) {
+                    Some(meter) => format!("*M{meter}"),
+                    None => "*".to_string(),
+                })
+                .collect();
+            lines.push(tokens.join("\t"));
+        }
+        if columns.iter().any(|c| c.data.contains_key(&offset)) {
+            let tokens: Vec<&str> = columns
+                .iter()
+                .map(|c| c.data.get(&offset).map_or(".", String::as_str))
+                .collect();
+            lines.push(tokens.join("\t"));
+        }
+    }
+
+    while widths.iter().any(|w| *w > 1) {
+        let tokens: Vec<&str> = widths
+            .iter_mut()
+            .flat_map(|width| {
+                let mut tokens = vec!["*"; *width];
+                if *width > 1 {
+                    tokens[*width - 2] = "*v";
+                    tokens[*width - 1] = "*v";
+                    *width -= 1;
+                }
+                tokens
+            })
+            .collect();
+        lines.push(tokens.join("\t"));
+    }
+    lines.push(vec!["*-"; staves.len()].join("\t"));
+    Ok(lines.join("\n") + "\n")
+}
+
+fn collect_staves<'a>(component: &'a ScoreObject, staves: &mut Vec<&'a ScoreObject>) {
+    match component {
+        ScoreObject::Staff { .. } => staves.push(component),
+        ScoreObject::Score { contents, .. } | ScoreObject::StaffGroup { contents, .. } => {
+            for child in contents.iter() {
+                collect_staves(child, staves);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// The staff's voices, or the staff itself when it has none.
+fn staff_layers(staff: &ScoreObject) -> Vec<&ScoreObject> {
+    let voices: Vec<&ScoreObject> = staff
+        .contents()
+        .into_iter()
+        .flatten()
+        .filter(|child| matches!(child, ScoreObject::Voice { .. }))
+        .collect();
+    if voices.is_empty() {
+        vec![staff]
+    } else {
+        voices
+    }
+}
+
+/// One spine's records, keyed by offset.
+struct Spine {
+    data: BTreeMap<Duration, String>,
+    bars: BTreeMap<Duration, String>,
+    meters: BTreeMap<Duration, TimeSignature>,
+}
+
+fn spine(layer: &ScoreObject) -> Result<Spine, Error> {
+    let mut spine = Spine {
+        data: BTreeMap::new(),
+        bars: BTreeMap::new(),
+        meters: BTreeMap::new(),
+    };
+    let mut offset = Duration::new(0, 1);
+    let mut meter = None;
+    let mut bar_number = 1;
+    let mut is_tied = false;
+    for component in layer.contents().into_iter().flatten() {
+        if let ScoreObject::Measure { time_signature, .. } = component {
+            if meter != Some(*time_signature) {
+                spine.meters.insert(offset, *time_signature);
+                meter = Some(*time_signature);
+            }
+        }
+        for timespan in component.leaf_timespans() {
+            let prolation = component
+                .implied_prolation(&timespan.path)
+                .unwrap_or(Multiplier::new(1, 1));
+            let token = event(timespan.leaf, prolation, &mut is_tied)?;
+            spine.data.insert(offset + timespan.start_offset, token);
+        }
+        offset = offset + component.duration();
+        if let Some(bar_line) = component.bar_line() {
+            bar_number += 1;
+            spine.bars.insert(offset, bar_token(*bar_line, bar_number));
+        }
+    }
+    Ok(spine)
+}
+
+fn bar_token(bar_line: BarLine, number: i32) -> String {
+    match bar_line {
+        BarLine::Single => format!("={number}"),
+        BarLine::Double => format!("={number}||"),
+        BarLine::Final => "==".to_string(),
+        BarLine::StartRepeat => format!("={number}!|:"),
+        BarLine::EndRepeat => format!("={number}:|!"),
+        BarLine::DoubleRepeat => format!("={number}:|!|:"),
+    }
+}
+
+/// The recip of a written duration under `prolation`: a triplet eighth is
+/// `12`, a breve `0`, and durations with no whole-number recip use `%`.
+fn recip(written_duration: Duration, prolation: Multiplier) -> String {
+    let dots = written_duration.dot_count();
+    let base = if dots == 0 {
+        written_duration
+    } else {
+        written_duration.equal_or_greater_power_of_two() / 2
+    };
+    let recip = match (base * prolation).to_pair() {
+        (1, d) => d.to_string(),
+        (n, 1) if n.count_ones() == 1 => "0".repeat(n.trailing_zeros() as usize),
+        (n, d) => format!("{d}%{n}"),
+    };
+    recip + &".".repeat(dots as usize)
+}
+
+fn pitch(pitch: &Pitch) -> Result<String, Error> {
+    let (letter, accidental) = pitch.pitch_class().to_pair();
+    let (_, symbol) = ACCIDENTALS
+        .iter()
+        .find(|(a, _)| *a == accidental)
+        .ok_or_else(|| Error::KernExport(format!("{accidental:?} has no kern spelling")))?;
+    let octave = pitch.octave();
+    let name = letter.to_string();
+    let letters = if octave >= 4 {
+        name.repeat((octave - 3) as usize)
+    } else {
+        name.to_uppercase().repeat((4 - octave) as usize)
+    };
+    Ok(letters + symbol)
+}
+
+fn event(leaf: &ScoreObject, prolation: Multiplier, was_tied: &mut bool) -> Result<String, Error> {
+    let recip = recip(leaf.written_duration().unwrap(), prolation);
+    let pitches = match leaf {
+        ScoreObject::Note { written_pitch, .. } => vec![*written_pitch],
+        ScoreObject::Chord {
+            written_pitches, ..
+        } => written_pitches.to_vec(),
+        ScoreObject::Rest { .. } => {
+            *was_tied = false;
+            return Ok(format!("{recip}r"));
+        }
+        _ => {
+            *was_tied = false;
+            return Ok(format!("{recip}ryy"));
+        }
+    };
+    let is_tied = leaf.is_tied();
+    let (start, end) = match (*was_tied, is_tied) {
+        (false, true) => ("[", ""),
+        (true, true) => ("", "_"),
+        (true, false) => ("", "]"),
+        (false, false) => ("", ""),
+    };
+    *was_tied = is_tied;
+    let subtokens = pitches
+        .iter()
+        .map(|p| Ok(format!("{start}{recip}{}{end}", pitch(p)?)))
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok(subtokens.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_kern;
+    use crate::duration::Duration;
+    use crate::kern::read_kern;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{chord, measure, note, rest, tuplet, BarLine, ScoreBuilder};
+    use crate::time_signature::TimeSignature;
+
+    fn pitch(
+        dpc: crate::pitch::DiatonicPitchClass,
+        accidental: crate::pitch::Accidental,
+        octave: i32,
+    ) -> Pitch {
+        Pitch::new(PitchClass::new(dpc, accidental), octave)
+    }
+
+    #[test]
+    fn writes_spines() {
+        let mut last = measure(
+            TimeSignature::new(2, 4),
+            vec![note(pitch(G, Natural, 2), Duration::new(1, 2)).unwrap()],
+        )
+        .unwrap();
+        last.set_bar_line(BarLine::Final);
+        let mut tied = note(pitch(E, Flat, 5), Duration::new(1, 4)).unwrap();
+        tied.set_is_tied(true);
+        let score = ScoreBuilder::new()
+            .name("Duet")
+            .staff("Upper", |s| {
+                s.voice("1", |v| {
+                    v.try_push(measure(
+                        TimeSignature::new(2, 4),
+                        vec![
+                            tuplet(
+                                Multiplier::new(2, 3),
+                                vec![
+                                    note(pitch(C, Sharp, 4), Duration::new(1, 8)).unwrap(),
+                                    rest(Duration::new(1, 8)).unwrap(),
+                                    note(pitch(B, DoubleFlat, 3), Duration::new(1, 8)).unwrap(),
+                                ],
+                            )
+                            .unwrap(),
+                            tied,
+                        ],
+                    ))
+                    .try_push(measure(
+                        TimeSignature::new(2, 4),
+                        vec![chord(
+                            vec![pitch(E, Flat, 5), pitch(C, Natural, 6)],
+                            Duration::new(1, 2),
+                        )
+                        .unwrap()],
+                    ))
+                })
+            })
+            .staff("Lower", |s| {
+                s.voice("1", |v| {
+                    v.try_push(measure(
+                        TimeSignature::new(2, 4),
+                        vec![note(pitch(C, Natural, 3), Duration::new(1, 2)).unwrap()],
+                    ))
+                    .push(last)
+                })
+            })
+            .build()
+            .unwrap();
+        assert_eq!(
+            write_kern(&score).unwrap(),
+            "!!!OTL: Duet\n\
+             **kern\t**kern\n\
+             *I\"Lower\t*I\"Upper\n\
+             *M2/4\t*M2/4\n\
+             2C\t12c#\n\
+             .\t12r\n\
+             .\t12B--\n\
+             .\t[4ee-\n\
+             =2\t=2\n\
+             2GG\t2ee-] 2ccc]\n\
+             ==\t=3\n\
+             *-\t*-\n"
+        );
+    }
+
+    #[test]
+    fn rejects_quarter_tones() {
+        let score = ScoreBuilder::new()
+            .staff("1", |s| {
+                s.voice("1", |v| {
+                    v.try_push(note(pitch(C, QuarterSharp, 4), Duration::new(1, 4)))
+                })
+            })
+            .build()
+            .unwrap();
+        assert!(write_kern(&score).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let source = "**kern\t**kern\n\
+                      *I\"Bass\t*I\"Treble\n\
+                      *\t*^\n\
+                      *M3/4\t*M3/4\t*M3/4\n\
+                      4C\t6e\t4G\n\
+                      .\t6f\t.\n\
+                      4D\t.\t8A\n\
+                      .\t6g\t.\n\
+                      .\t.\t[8B\n\
+                      4E\t4a\t4B]\n\
+                      =2\t=2\t=2\n\
+                      2.F#\t4cc\t2.c\n\
+                      .\t2ryy\t.\n\
+                      ==\t==\t==\n\
+                      *\t*v\t*v\n\
+                      *-\t*-\n";
+        let score = read_kern(source).unwrap();
+        assert_eq!(score[0].contents().unwrap().len(), 2);
+        assert_eq!(score[0][0].measures().len(), 2);
+        assert_eq!(score[0][1].measures().len(), 2);
+        let written = write_kern(&score).unwrap();
+        assert_eq!(read_kern(&written).unwrap(), score);
+    }
+}
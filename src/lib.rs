@@ -1,6 +1,23 @@
+pub mod abc;
+pub mod audio;
 pub mod duration;
 pub mod error;
+pub mod harmony;
 pub mod has_semitones;
+pub mod instrument;
+pub mod kern;
+pub mod lilypond;
+pub mod mei;
+pub mod meter;
 pub mod multiplier;
+pub mod piano_roll;
 pub mod pitch;
+pub mod rhythm_maker;
+pub mod rhythm_tree;
 pub mod score_object;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod set_theory;
+pub mod time_signature;
+pub mod tuning;
+pub mod wellformedness;
@@ -0,0 +1,218 @@
+use std::fmt;
+
+use super::{format_lilypond, quoted};
+use crate::{error::Error, score_object::ScoreObject};
+
+const INDENT: &str = "    ";
+
+/// A `\layout` or `\paper` block: top-level settings such as `indent = 0`
+/// followed by `\context` overrides such as `\remove "Bar_number_engraver"`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LilyPondBlock {
+    settings: Vec<(String, String)>,
+    contexts: Vec<(String, Vec<String>)>,
+}
+
+impl LilyPondBlock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `key = value`, with `value` written verbatim.
+    pub fn with_setting(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.settings.push((key.into(), value.into()));
+        self
+    }
+
+    /// Adds a `\context { \Name ... }` block with one override per line.
+    pub fn with_context(mut self, context: impl Into<String>, overrides: Vec<String>) -> Self {
+        self.contexts.push((context.into(), overrides));
+        self
+    }
+
+    fn lines(&self, keyword: &str) -> Vec<String> {
+        let mut lines = vec![format!("\\{keyword} {{")];
+        for (key, value) in self.settings.iter() {
+            lines.push(format!("{INDENT}{key} = {value}"));
+        }
+        for (context, overrides) in self.contexts.iter() {
+            lines.push(format!("{INDENT}\\context {{"));
+            lines.push(format!("{INDENT}{INDENT}\\{context}"));
+            for line in overrides.iter() {
+                lines.push(format!("{INDENT}{INDENT}{line}"));
+            }
+            lines.push(format!("{INDENT}}}"));
+        }
+        lines.push("}".to_string());
+        lines
+    }
+}
+
+/// A complete `.ly` file around a `Score`. Pitches are written with English
+/// note names, so `\language "english"` is always included.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LilyPondFile {
+    score: ScoreObject,
+    version: String,
+    includes: Vec<String>,
+    header: Vec<(String, String)>,
+    layout: Option<LilyPondBlock>,
+    paper: Option<LilyPondBlock>,
+}
+
+impl LilyPondFile {
+    pub fn new(score: ScoreObject) -> Result<Self, Error> {
+        if !matches!(score, ScoreObject::Score { .. }) {
+            return Err(Error::LilyPondExport(format!(
+                "expected a Score, found a {}",
+                score.kind_name()
+            )));
+        }
+        Ok(Self {
+            score,
+            version: "2.24.0".to_string(),
+            includes: vec![],
+            header: vec![],
+            layout: None,
+            paper: None,
+        })
+    }
+
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    pub fn with_include(mut self, path: impl Into<String>) -> Self {
+        self.includes.push(path.into());
+        self
+    }
+
+    /// Adds a `\header` field such as `title` or `composer`; the value is
+    /// written as a quoted string.
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.header.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn with_layout(mut self, layout: LilyPondBlock) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+
+    pub fn with_paper(mut self, paper: LilyPondBlock) -> Self {
+        self.paper = Some(paper);
+        self
+    }
+
+    pub fn score(&self) -> &ScoreObject {
+        &self.score
+    }
+}
+
+impl fmt::Display for LilyPondFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\\version {}", quoted(&self.version))?;
+        writeln!(f, "\\language \"english\"")?;
+        for path in self.includes.iter() {
+            writeln!(f, "\\include {}", quoted(path))?;
+        }
+        if !self.header.is_empty() {
+            writeln!(f, "\n\\header {{")?;
+            for (key, value) in self.header.iter() {
+                writeln!(f, "{INDENT}{key} = {}", quoted(value))?;
+            }
+            writeln!(f, "}}")?;
+        }
+        if let Some(paper) = &self.paper {
+            writeln!(f, "\n{}", paper.lines("paper").join("\n"))?;
+        }
+        if let Some(layout) = &self.layout {
+            writeln!(f, "\n{}", layout.lines("layout").join("\n"))?;
+        }
+        writeln!(f, "\n\\score {{")?;
+        for line in format_lilypond(&self.score).lines() {
+            writeln!(f, "{INDENT}{line}")?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LilyPondBlock, LilyPondFile};
+    use crate::duration::Duration;
+    use crate::error::Error;
+    use crate::pitch::{Accidental, DiatonicPitchClass, Pitch, PitchClass};
+    use crate::score_object::{note, rest, ScoreBuilder};
+
+    #[test]
+    fn display() {
+        let middle_c = Pitch::new(
+            PitchClass::new(DiatonicPitchClass::C, Accidental::Natural),
+            4,
+        );
+        let score = ScoreBuilder::new()
+            .staff("Flute", |s| {
+                s.voice("Flute Voice", |v| {
+                    v.try_push(note(middle_c, Duration::new(1, 2)))
+                        .try_push(rest(Duration::new(1, 2)))
+                })
+            })
+            .build()
+            .unwrap();
+        let file = LilyPondFile::new(score)
+            .unwrap()
+            .with_include("stylesheet.ily")
+            .with_header("title", "Air \"on\" C")
+            .with_header("composer", "Anon.")
+            .with_paper(LilyPondBlock::new().with_setting("indent", "0\\mm"))
+            .with_layout(LilyPondBlock::new().with_context(
+                "Staff",
+                vec!["\\remove \"Time_signature_engraver\"".to_string()],
+            ));
+        assert_eq!(
+            file.to_string(),
+            r#"\version "2.24.0"
+\language "english"
+\include "stylesheet.ily"
+
+\header {
+    title = "Air \"on\" C"
+    composer = "Anon."
+}
+
+\paper {
+    indent = 0\mm
+}
+
+\layout {
+    \context {
+        \Staff
+        \remove "Time_signature_engraver"
+    }
+}
+
+\score {
+    \new Score <<
+        \new Staff = "Flute" {
+            \new Voice = "Flute Voice" {
+                c'2
+                r2
+            }
+        }
+    >>
+}
+"#
+        );
+    }
+
+    #[test]
+    fn requires_score() {
+        let rest = rest(Duration::new(1, 4)).unwrap();
+        assert!(matches!(
+            LilyPondFile::new(rest),
+            Err(Error::LilyPondExport(_))
+        ));
+    }
+}
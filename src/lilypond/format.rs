@@ -0,0 +1,547 @@
+use super::quoted;
+use crate::{
+    duration::Duration,
+    instrument::Clef,
+    pitch::{Accidental, DiatonicPitchClass, Pitch, PitchClass},
+    score_object::{
+        BarLine, FeatheredBeam, ScoreObject, StaffContext, StaffGroupContext, VoiceContext,
+    },
+    time_signature::TimeSignature,
+};
+
+const INDENT: &str = "    ";
+
+/// Formats `component` as LilyPond music, one leaf per line.
+pub fn format_lilypond(component: &ScoreObject) -> String {
+    let mut formatter = Formatter::default();
+    formatter.component(component);
+    formatter.lines.join("\n") + "\n"
+}
+
+/// How the leaves of the current voice are written.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Mode {
+    #[default]
+    Notes,
+    Lyrics,
+    Figures,
+}
+
+#[derive(Default)]
+struct Formatter {
+    lines: Vec<String>,
+    depth: usize,
+    mode: Mode,
+    time_signature: Option<TimeSignature>,
+}
+
+impl Formatter {
+    fn line(&mut self, line: impl AsRef<str>) {
+        self.lines
+            .push(format!("{}{}", INDENT.repeat(self.depth), line.as_ref()));
+    }
+
+    fn block(&mut self, opening: String, is_simultaneous: bool, contents: &[ScoreObject]) {
+        let (open, close) = if is_simultaneous {
+            ("<<", ">>")
+        } else {
+            ("{", "}")
+        };
+        let opening = if opening.is_empty() {
+            open.to_string()
+        } else {
+            format!("{opening} {open}")
+        };
+        self.line(opening);
+        self.depth += 1;
+        for child in contents {
+            self.component(child);
+        }
+        self.depth -= 1;
+        self.line(close);
+    }
+
+    /// Beams `contents` together, fanning out towards the faster end. The
+    /// written durations already carry the change of speed, so the durations
+    /// are feathered by `1/1` to leave playback as written.
+    fn feathered(&mut self, feathered_beam: FeatheredBeam, contents: &[ScoreObject]) {
+        let direction = match feathered_beam {
+            FeatheredBeam::Accelerando => "#RIGHT",
+            FeatheredBeam::Ritardando => "#LEFT",
+        };
+        self.line(format!("\\override Beam.grow-direction = {direction}"));
+        let first = self.lines.len() + 1;
+        self.block("\\featherDurations 1/1".to_string(), false, contents);
+        let last = self.lines.len() - 2;
+        let are_leaves = contents.first().is_some_and(ScoreObject::is_leaf)
+            && contents.last().is_some_and(ScoreObject::is_leaf);
+        if are_leaves && first < last {
+            self.lines[first].push_str(" [");
+            self.lines[last].push_str(" ]");
+        }
+        self.line("\\revert Beam.grow-direction");
+    }
+
+    fn component(&mut self, component: &ScoreObject) {
+        match component {
+            ScoreObject::Note {
+                written_pitch,
+                written_duration,
+                is_tied,
+            } => self.line(format!(
+                "{written_pitch}{}{}",
+                duration(*written_duration),
+                tie(*is_tied)
+            )),
+            ScoreObject::Chord {
+                written_pitches,
+                written_duration,
+                is_tied,
+            } => {
+                let pitches: Vec<String> = written_pitches.iter().map(Pitch::to_string).collect();
+                self.line(format!(
+                    "<{}>{}{}",
+                    pitches.join(" "),
+                    duration(*written_duration),
+                    tie(*is_tied)
+                ))
+            }
+            ScoreObject::Rest { written_duration } => {
+                self.line(format!("r{}", duration(*written_duration)))
+            }
+            ScoreObject::Spacer { written_duration } => match self.mode {
+                Mode::Notes => self.line(format!("s{}", duration(*written_duration))),
+                _ => self.line(format!("\\skip {}", duration(*written_duration))),
+            },
+            ScoreObject::Lyric {
+                text,
+                written_duration,
+            } => match self.mode {
+                Mode::Figures if text.is_empty() => {
+                    self.line(format!("\\skip {}", duration(*written_duration)))
+                }
+                Mode::Figures => {
+                    let figures: Vec<String> = text
+                        .split(|c: char| c.is_whitespace() || c == ',')
+                        .filter(|token| !token.is_empty())
+                        .map(figure)
+                        .collect();
+                    self.line(format!(
+                        "<{}>{}",
+                        figures.join(" "),
+                        duration(*written_duration)
+                    ))
+                }
+                _ => match text.strip_suffix('-').filter(|stem| !stem.is_empty()) {
+                    Some(stem) => self.line(format!(
+                        "{}{} --",
+                        quoted(stem),
+                        duration(*written_duration)
+                    )),
+                    None => self.line(format!("{}{}", quoted(text), duration(*written_duration))),
+                },
+            },
+            ScoreObject::Tuplet { contents, .. } => {
+                let (n, d) = component.display_ratio().unwrap_or((1, 1));
+                self.block(format!("\\tuplet {n}/{d}"), false, contents);
+            }
+            ScoreObject::Container {
+                contents,
+                feathered_beam: Some(feathered_beam),
+                ..
+            } => self.feathered(*feathered_beam, contents),
+            ScoreObject::Container {
+                contents,
+                is_simultaneous,
+                ..
+            } => self.block(String::new(), *is_simultaneous, contents),
+            ScoreObject::Measure {
+                time_signature,
+                bar_line,
+                contents,
+            } => {
+                if self.time_signature != Some(*time_signature) {
+                    self.line(format!("\\time {time_signature}"));
+                    self.time_signature = Some(*time_signature);
+                }
+                for child in contents.iter() {
+                    self.component(child);
+                }
+                self.line(bar(*bar_line));
+            }
+            ScoreObject::Voice {
+                contents,
+                is_simultaneous,
+                name,
+                context,
+                ..
+            } => {
+                let (mode, keyword) = match context {
+                    VoiceContext::Lyrics => (Mode::Lyrics, " \\lyricmode"),
+                    VoiceContext::FiguredBass => (Mode::Figures, " \\figuremode"),
+                    _ => (Mode::Notes, ""),
+                };
+                let opening = format!("{}{keyword}", new_context(voice_context(context), name));
+                let outer = (self.mode, self.time_signature);
+                self.mode = mode;
+                self.time_signature = None;
+                self.block(opening, *is_simultaneous, contents);
+                (self.mode, self.time_signature) = outer;
+            }
+            ScoreObject::Staff {
+                contents,
+                is_simultaneous,
+                name,
+                context,
+                instrument,
+                is_concert_pitch,
+            } => {
+                // LilyPond does not allow these contexts inside a staff, so
+                // they are written alongside it instead, and a staff holding
+                // nothing else is left out.
+                let (lifted, music): (Vec<_>, Vec<_>) =
+                    contents.iter().partition(|child| is_staffless(child));
+                if music.is_empty() && !lifted.is_empty() {
+                    for child in lifted {
+                        self.component(child);
+                    }
+                    return;
+                }
+                let mut opening = new_context(staff_context(context), name);
+                if let Some(instrument) = instrument {
+                    opening = format!(
+                        "{opening} \\with {{ instrumentName = {} shortInstrumentName = {} }}",
+                        quoted(instrument.name()),
+                        quoted(instrument.short_name())
+                    );
+                }
+                let (open, close) = if *is_simultaneous {
+                    ("<<", ">>")
+                } else {
+                    ("{", "}")
+                };
+                self.line(format!("{opening} {open}"));
+                self.depth += 1;
+                if let Some(instrument) = instrument {
+                    if *context == StaffContext::Staff {
+                        self.line(format!("\\clef {}", quoted(clef(instrument.clef()))));
+                    }
+                    if instrument.is_transposing() && !is_concert_pitch {
+                        let middle_c = Pitch::new(
+                            PitchClass::new(DiatonicPitchClass::C, Accidental::Natural),
+                            4,
+                        );
+                        self.line(format!(
+                            "\\transposition {}",
+                            middle_c.transpose(&instrument.transposition())
+                        ));
+                    }
+                }
+                let outer = self.time_signature;
+                self.time_signature = None;
+                for child in music {
+                    self.component(child);
+                }
+                self.depth -= 1;
+                self.line(close);
+                for child in lifted {
+                    self.component(child);
+                }
+                self.time_signature = outer;
+            }
+            ScoreObject::StaffGroup {
+                contents,
+                name,
+                context,
+                ..
+            } => self.block(
+                new_context(staff_group_context(context), name),
+                true,
+                contents,
+            ),
+            ScoreObject::Score {
+                contents,
+                is_simultaneous,
+                name,
+            } => self.block(new_context("Score", name), *is_simultaneous, contents),
+        }
+    }
+}
+
+fn is_staffless(component: &ScoreObject) -> bool {
+    matches!(
+        component,
+        ScoreObject::Voice {
+            context: VoiceContext::Lyrics | VoiceContext::FiguredBass | VoiceContext::ChordNames,
+            ..
+        }
+    )
+}
+
+/// A bass figure such as `6`, `#6`, `b7` or `4+`, with `#`, `b` and `n`
+/// written as LilyPond's `+`, `-` and `!`. A lone accidental applies to the
+/// third, and anything else is written as text.
+fn figure(token: &str) -> String {
+    let start = token
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(token.len());
+    let end = token
+        .rfind(|c: char| c.is_ascii_digit())
+        .map_or(start, |i| i + 1);
+    let accidentals: Option<String> = token[..start]
+        .chars()
+        .chain(token[end..].chars())
+        .map(|c| match c {
+            '#' | '+' => Some('+'),
+            'b' | '-' => Some('-'),
+            'n' | '!' => Some('!'),
+            _ => None,
+        })
+        .collect();
+    let number = &token[start..end];
+    match (number.parse::<u32>(), accidentals) {
+        (Ok(number), Some(accidentals)) => format!("{number}{accidentals}"),
+        (Err(_), Some(accidentals)) if number.is_empty() => format!("_{accidentals}"),
+        _ => format!("\\markup {}", quoted(token)),
+    }
+}
+
+fn new_context(context: &str, name: &Option<String>) -> String {
+    match name {
+        Some(name) => format!("\\new {context} = {}", quoted(name)),
+        None => format!("\\new {context}"),
+    }
+}
+
+/// The LilyPond duration of a printable written duration, such as `4.` or
+/// `\breve`.
+fn duration(written_duration: Duration) -> String {
+    let dots = written_duration.dot_count();
+    let base = if dots == 0 {
+        written_duration
+    } else {
+        written_duration.equal_or_greater_power_of_two() / 2
+    };
+    let base = match base.to_pair() {
+        (8, 1) => "\\maxima".to_string(),
+        (4, 1) => "\\longa".to_string(),
+        (2, 1) => "\\breve".to_string(),
+        (_, d) => d.to_string(),
+    };
+    base + &".".repeat(dots as usize)
+}
+
+fn tie(is_tied: bool) -> &'static str {
+    if is_tied {
+        " ~"
+    } else {
+        ""
+    }
+}
+
+fn bar(bar_line: BarLine) -> &'static str {
+    match bar_line {
+        BarLine::Single => "|",
+        BarLine::Double => "\\bar \"||\"",
+        BarLine::Final => "\\bar \"|.\"",
+        BarLine::StartRepeat => "\\bar \".|:\"",
+        BarLine::EndRepeat => "\\bar \":|.\"",
+        BarLine::DoubleRepeat => "\\bar \":..:\"",
+    }
+}
+
+fn clef(clef: Clef) -> &'static str {
+    match clef {
+        Clef::Treble => "treble",
+        Clef::TrebleOctaveDown => "treble_8",
+        Clef::Alto => "alto",
+        Clef::Tenor => "tenor",
+        Clef::Bass => "bass",
+        Clef::Percussion => "percussion",
+    }
+}
+
+fn staff_group_context(context: &StaffGroupContext) -> &'static str {
+    match context {
+        StaffGroupContext::StaffGroup => "StaffGroup",
+        StaffGroupContext::ChoirStaff => "ChoirStaff",
+        StaffGroupContext::GrandStaff => "GrandStaff",
+        StaffGroupContext::PianoStaff => "PianoStaff",
+    }
+}
+
+fn staff_context(context: &StaffContext) -> &'static str {
+    match context {
+        StaffContext::Staff => "Staff",
+        StaffContext::RhythmicStaff => "RhythmicStaff",
+        StaffContext::TabStaff => "TabStaff",
+        StaffContext::DrumStaff => "DrumStaff",
+        StaffContext::VaticanaStaff => "VaticanaStaff",
+        StaffContext::MensuralStaff => "MensuralStaff",
+    }
+}
+
+fn voice_context(context: &VoiceContext) -> &'static str {
+    match context {
+        VoiceContext::Voice => "Voice",
+        VoiceContext::VaticanaVoice => "VaticanaVoice",
+        VoiceContext::MensuralVoice => "MensuralVoice",
+        VoiceContext::Lyrics => "Lyrics",
+        VoiceContext::DrumVoice => "DrumVoice",
+        VoiceContext::FiguredBass => "FiguredBass",
+        VoiceContext::TabVoice => "TabVoice",
+        VoiceContext::CueVoice => "CueVoice",
+        VoiceContext::ChordNames => "ChordNames",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_lilypond;
+    use crate::duration::Duration;
+    use crate::instrument::Instrument;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{
+        chord, container, lyric, measure, note, rest, tuplet, BarLine, FeatheredBeam, ScoreBuilder,
+        VoiceContext,
+    };
+    use crate::time_signature::TimeSignature;
+
+    fn pitch(
+        dpc: crate::pitch::DiatonicPitchClass,
+        accidental: crate::pitch::Accidental,
+        octave: i32,
+    ) -> Pitch {
+        Pitch::new(PitchClass::new(dpc, accidental), octave)
+    }
+
+    #[test]
+    fn feathered_beams() {
+        let c4 = pitch(C, Natural, 4);
+        let mut music = container(vec![
+            note(c4, Duration::new(1, 8)).unwrap(),
+            note(c4, Duration::new(1, 16)).unwrap(),
+        ])
+        .unwrap();
+        music.set_feathered_beam(Some(FeatheredBeam::Accelerando));
+        assert_eq!(
+            format_lilypond(&music),
+            r#"\override Beam.grow-direction = #RIGHT
+\featherDurations 1/1 {
+    c'8 [
+    c'16 ]
+}
+\revert Beam.grow-direction
+"#
+        );
+    }
+
+    #[test]
+    fn leaves_and_tuplets() {
+        let mut tied = note(pitch(C, QuarterSharp, 5), Duration::new(3, 8)).unwrap();
+        tied.set_is_tied(true);
+        let music = tuplet(
+            Multiplier::new(2, 3),
+            vec![
+                tied,
+                chord(
+                    vec![pitch(B, Flat, 1), pitch(F, Natural, 3)],
+                    Duration::new(1, 8),
+                )
+                .unwrap(),
+                rest(Duration::new(2, 1)).unwrap(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            format_lilypond(&music),
+            "\\tuplet 3/2 {\n    cqs''4. ~\n    <bf,, f>8\n    r\\breve\n}\n"
+        );
+    }
+
+    #[test]
+    fn measures_and_contexts() {
+        let mut last = measure(
+            TimeSignature::new(3, 4),
+            vec![note(pitch(D, Natural, 5), Duration::new(3, 4)).unwrap()],
+        )
+        .unwrap();
+        last.set_bar_line(BarLine::Final);
+        let score = ScoreBuilder::new()
+            .name("Song")
+            .staff("Clarinet", |s| {
+                s.instrument(Instrument::clarinet_in_b_flat())
+                    .voice("melody", |v| {
+                        v.try_push(measure(
+                            TimeSignature::new(3, 4),
+                            vec![note(pitch(E, Natural, 5), Duration::new(3, 4)).unwrap()],
+                        ))
+                        .push(last)
+                    })
+            })
+            .staff("Text", |s| {
+                s.voice("words", |v| {
+                    v.context(VoiceContext::Lyrics)
+                        .try_push(lyric("Hel-", Duration::new(3, 4)))
+                        .try_push(lyric("\"lo\"", Duration::new(3, 4)))
+                })
+            })
+            .build()
+            .unwrap();
+        assert_eq!(
+            format_lilypond(&score),
+            r#"\new Score = "Song" <<
+    \new Staff = "Clarinet" \with { instrumentName = "Clarinet in B-flat" shortInstrumentName = "Cl." } {
+        \clef "treble"
+        \transposition bf
+        \new Voice = "melody" {
+            \time 3/4
+            e''2.
+            |
+            d''2.
+            \bar "|."
+        }
+    }
+    \new Lyrics = "words" \lyricmode {
+        "Hel"2. --
+        "\"lo\""2.
+    }
+>>
+"#
+        );
+
+        let mut concert = score.clone();
+        concert.to_concert_pitch();
+        let formatted = format_lilypond(&concert);
+        assert!(!formatted.contains("\\transposition"));
+        assert!(formatted.contains("c''2."));
+    }
+
+    #[test]
+    fn figured_bass() {
+        let score = ScoreBuilder::new()
+            .staff("Bass", |s| {
+                s.voice("figures", |v| {
+                    v.context(VoiceContext::FiguredBass)
+                        .try_push(lyric("6 5", Duration::new(1, 4)))
+                        .try_push(lyric("", Duration::new(1, 4)))
+                        .try_push(lyric("11", Duration::new(1, 4)))
+                        .try_push(lyric("#6, b7 n3", Duration::new(1, 4)))
+                        .try_push(lyric("#", Duration::new(1, 4)))
+                        .try_push(lyric("x", Duration::new(1, 4)))
+                })
+            })
+            .build()
+            .unwrap();
+        let formatted = format_lilypond(&score);
+        assert!(!formatted.contains("\\new Staff"));
+        assert!(formatted.contains("\\new FiguredBass = \"figures\" \\figuremode {"));
+        assert!(formatted.contains("<6 5>4\n"));
+        assert!(formatted.contains("\\skip 4\n"));
+        assert!(formatted.contains("<11>4\n"));
+        assert!(formatted.contains("<6+ 7- 3!>4\n"));
+        assert!(formatted.contains("<_+>4\n"));
+        assert!(formatted.contains("<\\markup \"x\">4\n"));
+    }
+}
@@ -0,0 +1,17 @@
+//! LilyPond output.
+//!
+//! `format_lilypond` writes any `ScoreObject` as LilyPond music, with pitches
+//! in English note names and absolute octaves. `LilyPondFile` wraps a `Score`
+//! in the version, language, includes, header, paper and layout blocks a
+//! complete `.ly` file needs.
+
+mod file;
+mod format;
+
+pub use file::{LilyPondBlock, LilyPondFile};
+pub use format::format_lilypond;
+
+/// Quotes `text` as a LilyPond string.
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
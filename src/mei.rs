@@ -0,0 +1,672 @@
+//! MEI 5 export.
+//!
+//! MEI is organised measure by measure, so the voices of every staff are cut
+//! into bars: each `Measure` is a bar, as is each run of unmeasured contents
+//! between measures. Bar `n` of every voice is written to `<measure n>`, with
+//! the staff's voices as `<layer>`s. MEI attaches lyrics to the notes they
+//! are sung to, so lyric leaves, which stand in voices of their own, are
+//! refused.
+
+use std::collections::HashMap;
+
+use crate::{
+    duration::Duration,
+    error::Error,
+    has_semitones::HasSemitones,
+    instrument::Clef,
+    pitch::{Accidental, DiatonicPitchClass, Pitch},
+    score_object::{BarLine, FeatheredBeam, ScoreObject, StaffContext, StaffGroupContext},
+    time_signature::TimeSignature,
+};
+
+const NAMESPACE: &str = "http://www.music-encoding.org/ns/mei";
+
+/// Writes a `Score` as an MEI 5 document.
+pub fn write_mei(score: &ScoreObject) -> Result<String, Error> {
+    let ScoreObject::Score { contents, name, .. } = score else {
+        return Err(Error::MeiExport(format!(
+            "expected a Score, not a {}",
+            score.kind_name()
+        )));
+    };
+
+    let mut staves = vec![];
+    collect_staves(score, &mut staves);
+    let staff_bars = staves
+        .iter()
+        .map(|staff| staff_layers(staff).into_iter().map(bars).collect())
+        .collect::<Vec<Vec<Vec<Bar>>>>();
+    let bar_count = staff_bars
+        .iter()
+        .flatten()
+        .map(|layer| layer.len())
+        .max()
+        .unwrap_or(0);
+    let meter = score
+        .measures()
+        .first()
+        .and_then(|m| m.time_signature())
+        .copied();
+
+    let mut xml = Xml::default();
+    xml.line(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.open(
+        "mei",
+        &[("xmlns", NAMESPACE.into()), ("meiversion", "5.0".into())],
+    );
+    xml.open("meiHead", &[]);
+    xml.open("fileDesc", &[]);
+    xml.open("titleStmt", &[]);
+    xml.text("title", name.as_deref().unwrap_or(""));
+    xml.close("titleStmt");
+    xml.empty("pubStmt", &[]);
+    xml.close("fileDesc");
+    xml.close("meiHead");
+    xml.open("music", &[]);
+    xml.open("body", &[]);
+    xml.open("mdiv", &[]);
+    xml.open("score", &[]);
+
+    xml.open("scoreDef", &meter_attributes(meter));
+    xml.open("staffGrp", &[]);
+    let mut n = 0;
+    for component in contents.iter() {
+        staff_definitions(&mut xml, component, &mut n);
+    }
+    xml.close("staffGrp");
+    xml.close("scoreDef");
+
+    xml.open("section", &[]);
+    let mut current_meter = meter;
+    let mut states: Vec<Vec<LayerState>> = staff_bars
+        .iter()
+        .map(|layers| layers.iter().map(|_| LayerState::default()).collect())
+        .collect();
+    for index in 0..bar_count {
+        let bar_meter = staff_bars
+            .iter()
+            .flatten()
+            .find_map(|layer| layer.get(index).and_then(|bar| bar.time_signature));
+        if bar_meter.is_some() && bar_meter != current_meter {
+            xml.empty("scoreDef", &meter_attributes(bar_meter));
+            current_meter = bar_meter;
+        }
+        let bar_line = staff_bars
+            .iter()
+            .flatten()
+            .find_map(|layer| layer.get(index).and_then(|bar| bar.bar_line));
+        let mut attributes = vec![("n", (index + 1).to_string())];
+        if let Some(bar_line) = bar_line.and_then(bar_rendition) {
+            attributes.push(("right", bar_line.into()));
+        }
+        xml.open("measure", &attributes);
+        for (staff, layers) in staff_bars.iter().enumerate() {
+            xml.open("staff", &[("n", (staff + 1).to_string())]);
+            for (layer, bars) in layers.iter().enumerate() {
+                xml.open("layer", &[("n", (layer + 1).to_string())]);
+                let state = &mut states[staff][layer];
+                state.accidentals.clear();
+                for component in bars
+                    .get(index)
+                    .map(|bar| bar.contents.as_slice())
+                    .unwrap_or(&[])
+                {
+                    layer_element(&mut xml, state, component)?;
+                }
+                xml.close("layer");
+            }
+            xml.close("staff");
+        }
+        xml.close("measure");
+    }
+    xml.close("section");
+
+    xml.close("score");
+    xml.close("mdiv");
+    xml.close("body");
+    xml.close("music");
+    xml.close("mei");
+    Ok(xml.out)
+}
+
+#[derive(Default)]
+struct Xml {
+    out: String,
+    depth: usize,
+}
+
+impl Xml {
+    fn line(&mut self, line: &str) {
+        self.out.push_str(&"  ".repeat(self.depth));
+        self.out.push_str(line);
+        self.out.push('\n');
+    }
+
+    fn tag(name: &str, attributes: &[(&str, String)]) -> String {
+        let mut tag = name.to_string();
+        for (key, value) in attributes {
+            tag.push_str(&format!(r#" {key}="{}""#, escape(value)));
+        }
+        tag
+    }
+
+    fn open(&mut self, name: &str, attributes: &[(&str, String)]) {
+        self.line(&format!("<{}>", Self::tag(name, attributes)));
+        self.depth += 1;
+    }
+
+    fn empty(&mut self, name: &str, attributes: &[(&str, String)]) {
+        self.line(&format!("<{}/>", Self::tag(name, attributes)));
+    }
+
+    fn text(&mut self, name: &str, text: &str) {
+        self.line(&format!("<{name}>{}</{name}>", escape(text)));
+    }
+
+    fn close(&mut self, name: &str) {
+        self.depth -= 1;
+        self.line(&format!("</{name}>"));
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn collect_staves<'a>(component: &'a ScoreObject, staves: &mut Vec<&'a ScoreObject>) {
+    match component {
+        ScoreObject::Staff { .. } => staves.push(component),
+        ScoreObject::Score { contents, .. } | ScoreObject::StaffGroup { contents, .. } => {
+            for child in contents.iter() {
+                collect_staves(child, staves);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// The staff's voices, or the staff itself when it has none.
+fn staff_layers(staff: &ScoreObject) -> Vec<&ScoreObject> {
+    let voices: Vec<&ScoreObject> = staff
+        .contents()
+        .into_iter()
+        .flatten()
+        .filter(|child| matches!(child, ScoreObject::Voice { .. }))
+        .collect();
+    if voices.is_empty() {
+        vec![staff]
+    } else {
+        voices
+    }
+}
+
+struct Bar<'a> {
+    time_signature: Option<TimeSignature>,
+    bar_line: Option<BarLine>,
+    contents: Vec<&'a ScoreObject>,
+}
+
+fn bars(layer: &ScoreObject) -> Vec<Bar<'_>> {
+    let mut bars: Vec<Bar> = vec![];
+    let mut is_open = false;
+    for component in layer.contents().into_iter().flatten() {
+        match component {
+            ScoreObject::Measure {
+                time_signature,
+                bar_line,
+                contents,
+            } => {
+                bars.push(Bar {
+                    time_signature: Some(*time_signature),
+                    bar_line: Some(*bar_line),
+                    contents: contents.iter().collect(),
+                });
+                is_open = false;
+            }
+            _ if is_open => bars.last_mut().unwrap().contents.push(component),
+            _ => {
+                bars.push(Bar {
+                    time_signature: None,
+                    bar_line: None,
+                    contents: vec![component],
+                });
+                is_open = true;
+            }
+        }
+    }
+    bars
+}
+
+fn meter_attributes(meter: Option<TimeSignature>) -> Vec<(&'static str, String)> {
+    match meter.map(|m| m.to_pair()) {
+        Some((count, unit)) => vec![
+            ("meter.count", count.to_string()),
+            ("meter.unit", unit.to_string()),
+        ],
+        None => vec![],
+    }
+}
+
+fn bar_rendition(bar_line: BarLine) -> Option<&'static str> {
+    match bar_line {
+        BarLine::Single => None,
+        BarLine::Double => Some("dbl"),
+        BarLine::Final => Some("end"),
+        BarLine::StartRepeat => Some("rptstart"),
+        BarLine::EndRepeat => Some("rptend"),
+        BarLine::DoubleRepeat => Some("rptboth"),
+    }
+}
+
+fn staff_definitions(xml: &mut Xml, component: &ScoreObject, n: &mut usize) {
+    match component {
+        ScoreObject::StaffGroup {
+            contents,
+            name,
+            context,
+            ..
+        } => {
+            let (symbol, bar_thru) = match context {
+                StaffGroupContext::StaffGroup => ("bracket", "true"),
+                StaffGroupContext::ChoirStaff => ("bracket", "false"),
+                StaffGroupContext::GrandStaff | StaffGroupContext::PianoStaff => ("brace", "true"),
+            };
+            let mut attributes = vec![("symbol", symbol.into()), ("bar.thru", bar_thru.into())];
+            if let Some(name) = name {
+                attributes.push(("label", name.clone()));
+            }
+            xml.open("staffGrp", &attributes);
+            for child in contents.iter() {
+                staff_definitions(xml, child, n);
+            }
+            xml.close("staffGrp");
+        }
+        ScoreObject::Staff {
+            name,
+            context,
+            instrument,
+            is_concert_pitch,
+            ..
+        } => {
+            *n += 1;
+            let lines = match context {
+                StaffContext::RhythmicStaff => 1,
+                StaffContext::TabStaff => 6,
+                StaffContext::VaticanaStaff => 4,
+                _ => 5,
+            };
+            let mut attributes = vec![("n", n.to_string()), ("lines", lines.to_string())];
+            let clef = match (context, instrument) {
+                (StaffContext::DrumStaff | StaffContext::RhythmicStaff, _) => Clef::Percussion,
+                (_, Some(instrument)) => instrument.clef(),
+                _ => Clef::Treble,
+            };
+            attributes.extend(clef_attributes(clef));
+            let label = name
+                .clone()
+                .or_else(|| instrument.as_ref().map(|i| i.name().to_string()));
+            if let Some(label) = label {
+                attributes.push(("label", label));
+            }
+            let transposing = instrument
+                .as_ref()
+                .filter(|i| i.is_transposing() && !is_concert_pitch);
+            if let Some(instrument) = transposing {
+                let transposition = instrument.transposition();
+                attributes.push(("trans.diat", transposition.staff_spaces().to_string()));
+                attributes.push(("trans.semi", (transposition.semitones() as i32).to_string()));
+            }
+            xml.empty("staffDef", &attributes);
+        }
+        _ => (),
+    }
+}
+
+fn clef_attributes(clef: Clef) -> Vec<(&'static str, String)> {
+    let (shape, line) = match clef {
+        Clef::Treble | Clef::TrebleOctaveDown => ("G", "2"),
+        Clef::Alto => ("C", "3"),
+        Clef::Tenor => ("C", "4"),
+        Clef::Bass => ("F", "4"),
+        Clef::Percussion => ("perc", "3"),
+    };
+    let mut attributes = vec![("clef.shape", shape.into()), ("clef.line", line.into())];
+    if clef == Clef::TrebleOctaveDown {
+        attributes.push(("clef.dis", "8".into()));
+        attributes.push(("clef.dis.place", "below".into()));
+    }
+    attributes
+}
+
+/// Accidentals already in force in the bar, and whether the previous event
+/// was tied into the next.
+#[derive(Default)]
+struct LayerState {
+    accidentals: HashMap<(DiatonicPitchClass, i32), Accidental>,
+    is_tied: bool,
+}
+
+impl LayerState {
+    fn tie(&mut self, is_tied: bool) -> Option<(&'static str, String)> {
+        let tie = match (self.is_tied, is_tied) {
+            (false, true) => Some("i"),
+            (true, true) => Some("m"),
+            (true, false) => Some("t"),
+            (false, false) => None,
+        };
+        self.is_tied = is_tied;
+        tie.map(|tie| ("tie", tie.into()))
+    }
+
+    /// Pitch attributes, writing `@accid` when the accidental is not implied
+    /// by the bar so far and `@accid.ges` when it is.
+    fn pitch(&mut self, pitch: &Pitch) -> Vec<(&'static str, String)> {
+        let (letter, accidental) = pitch.pitch_class().to_pair();
+        let octave = pitch.octave();
+        let mut attributes = vec![("pname", letter.to_string()), ("oct", octave.to_string())];
+        let current = self
+            .accidentals
+            .insert((letter, octave), accidental)
+            .unwrap_or(Accidental::Natural);
+        if current != accidental {
+            attributes.push(("accid", written_accidental(accidental).into()));
+        } else if accidental != Accidental::Natural {
+            attributes.push(("accid.ges", gestural_accidental(accidental).into()));
+        }
+        attributes
+    }
+}
+
+fn written_accidental(accidental: Accidental) -> &'static str {
+    match accidental {
+        Accidental::DoubleSharp => "x",
+        _ => gestural_accidental(accidental),
+    }
+}
+
+fn gestural_accidental(accidental: Accidental) -> &'static str {
+    match accidental {
+        Accidental::DoubleFlat => "ff",
+        Accidental::ThreeQuarterFlat => "3qf",
+        Accidental::Flat => "f",
+        Accidental::QuarterFlat => "1qf",
+        Accidental::Natural => "n",
+        Accidental::QuarterSharp => "1qs",
+        Accidental::Sharp => "s",
+        Accidental::ThreeQuarterSharp => "3qs",
+        Accidental::DoubleSharp => "ss",
+    }
+}
+
+/// `@dur` and `@dots` for a printable written duration.
+fn duration_attributes(duration: Duration) -> Vec<(&'static str, String)> {
+    let dots = duration.dot_count();
+    let base = if dots == 0 {
+        duration
+    } else {
+        duration.equal_or_greater_power_of_two() / 2
+    };
+    let dur = match base.to_pair() {
+        (8, 1) => "maxima".to_string(),
+        (4, 1) => "long".to_string(),
+        (2, 1) => "breve".to_string(),
+        (_, d) => d.to_string(),
+    };
+    let mut attributes = vec![("dur", dur)];
+    if dots > 0 {
+        attributes.push(("dots", dots.to_string()));
+    }
+    attributes
+}
+
+fn layer_element(
+    xml: &mut Xml,
+    state: &mut LayerState,
+    component: &ScoreObject,
+) -> Result<(), Error> {
+    match component {
+        ScoreObject::Note {
+            written_pitch,
+            written_duration,
+            is_tied,
+        } => {
+            let mut attributes = state.pitch(written_pitch);
+            attributes.extend(duration_attributes(*written_duration));
+            attributes.extend(state.tie(*is_tied));
+            xml.empty("note", &attributes);
+        }
+        ScoreObject::Chord {
+            written_pitches,
+            written_duration,
+            is_tied,
+        } => {
+            let mut attributes = duration_attributes(*written_duration);
+            attributes.extend(state.tie(*is_tied));
+            xml.open("chord", &attributes);
+            for pitch in written_pitches.iter() {
+                let attributes = state.pitch(pitch);
+                xml.empty("note", &attributes);
+            }
+            xml.close("chord");
+        }
+        ScoreObject::Rest { written_duration } => {
+            state.tie(false);
+            xml.empty("rest", &duration_attributes(*written_duration));
+        }
+        ScoreObject::Spacer { written_duration } => {
+            state.tie(false);
+            xml.empty("space", &duration_attributes(*written_duration));
+        }
+        ScoreObject::Lyric { text, .. } => {
+            return Err(Error::MeiExport(format!(
+                "lyric {text:?} is not attached to a note"
+            )));
+        }
+        ScoreObject::Tuplet { contents, .. } => {
+            let (num, numbase) = component.display_ratio().unwrap_or((1, 1));
+            xml.open(
+                "tuplet",
+                &[("num", num.to_string()), ("numbase", numbase.to_string())],
+            );
+            for child in contents.iter() {
+                layer_element(xml, state, child)?;
+            }
+            xml.close("tuplet");
+        }
+        ScoreObject::Container {
+            contents,
+            feathered_beam: Some(feathered_beam),
+            ..
+        } => {
+            let form = match feathered_beam {
+                FeatheredBeam::Accelerando => "acc",
+                FeatheredBeam::Ritardando => "rit",
+            };
+            xml.open("beam", &[("form", form.to_string())]);
+            for child in contents.iter() {
+                layer_element(xml, state, child)?;
+            }
+            xml.close("beam");
+        }
+        _ if component.is_simultaneous() => {
+            return Err(Error::MeiExport(format!(
+                "simultaneous {} inside a layer",
+                component.kind_name()
+            )));
+        }
+        _ => {
+            for child in component.contents().into_iter().flatten() {
+                layer_element(xml, state, child)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_mei;
+    use crate::duration::Duration;
+    use crate::instrument::Instrument;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{
+        chord, container, lyric, measure, note, rest, tuplet, BarLine, FeatheredBeam, ScoreBuilder,
+        StaffGroupContext, VoiceContext,
+    };
+    use crate::time_signature::TimeSignature;
+
+    fn pitch(
+        dpc: crate::pitch::DiatonicPitchClass,
+        accidental: crate::pitch::Accidental,
+        octave: i32,
+    ) -> Pitch {
+        Pitch::new(PitchClass::new(dpc, accidental), octave)
+    }
+
+    fn example() -> crate::score_object::ScoreObject {
+        let mut last = measure(
+            TimeSignature::new(3, 4),
+            vec![
+                note(pitch(F, Sharp, 4), Duration::new(3, 8)).unwrap(),
+                note(pitch(F, Sharp, 4), Duration::new(1, 8)).unwrap(),
+                note(pitch(F, Natural, 4), Duration::new(1, 4)).unwrap(),
+            ],
+        )
+        .unwrap();
+        last.set_bar_line(BarLine::Final);
+        let mut tied = note(pitch(C, QuarterSharp, 5), Duration::new(1, 4)).unwrap();
+        tied.set_is_tied(true);
+        ScoreBuilder::new()
+            .name("Study & Fugue")
+            .staff_group("Piano", |g| {
+                g.context(StaffGroupContext::PianoStaff)
+                    .staff("RH", |s| {
+                        s.voice("1", |v| {
+                            v.try_push(measure(
+                                TimeSignature::new(2, 4),
+                                vec![
+                                    tuplet(
+                                        Multiplier::new(2, 3),
+                                        vec![
+                                            note(pitch(C, Natural, 5), Duration::new(1, 8))
+                                                .unwrap(),
+                                            note(
+                                                pitch(D, ThreeQuarterFlat, 5),
+                                                Duration::new(1, 8),
+                                            )
+                                            .unwrap(),
+                                            rest(Duration::new(1, 8)).unwrap(),
+                                        ],
+                                    )
+                                    .unwrap(),
+                                    tied,
+                                ],
+                            ))
+                            .push(last)
+                        })
+                    })
+                    .staff("LH", |s| {
+                        s.voice("1", |v| {
+                            v.try_push(chord(
+                                vec![pitch(C, Natural, 3), pitch(G, Natural, 3)],
+                                Duration::new(1, 2),
+                            ))
+                        })
+                    })
+            })
+            .staff("Clarinet", |s| {
+                s.instrument(Instrument::clarinet_in_b_flat())
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn document_structure() {
+        let mei = write_mei(&example()).unwrap();
+        assert!(mei.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<mei xmlns=\"http://www.music-encoding.org/ns/mei\" meiversion=\"5.0\">"));
+        assert!(mei.contains("<title>Study &amp; Fugue</title>"));
+        assert!(mei.contains("<scoreDef meter.count=\"2\" meter.unit=\"4\">"));
+        assert!(mei.contains("<staffGrp symbol=\"brace\" bar.thru=\"true\" label=\"Piano\">"));
+        assert!(mei.contains(
+            "<staffDef n=\"3\" lines=\"5\" clef.shape=\"G\" clef.line=\"2\" label=\"Clarinet\" trans.diat=\"-1\" trans.semi=\"-2\"/>"
+        ));
+        assert_eq!(mei.matches("<measure ").count(), 2);
+        assert!(mei.contains("<scoreDef meter.count=\"3\" meter.unit=\"4\"/>"));
+        assert!(mei.contains("<measure n=\"2\" right=\"end\">"));
+        assert_eq!(mei.matches("<layer n=\"1\">").count(), 6);
+
+        let mut concert = example();
+        concert.to_concert_pitch();
+        let mei = write_mei(&concert).unwrap();
+        assert!(mei.contains(
+            "<staffDef n=\"3\" lines=\"5\" clef.shape=\"G\" clef.line=\"2\" label=\"Clarinet\"/>"
+        ));
+    }
+
+    #[test]
+    fn events() {
+        let mei = write_mei(&example()).unwrap();
+        assert!(mei.contains("<tuplet num=\"3\" numbase=\"2\">"));
+        assert!(mei.contains("<note pname=\"d\" oct=\"5\" accid=\"3qf\" dur=\"8\"/>"));
+        assert!(mei.contains("<rest dur=\"8\"/>"));
+        assert!(mei.contains("<note pname=\"c\" oct=\"5\" accid=\"1qs\" dur=\"4\" tie=\"i\"/>"));
+        assert!(mei
+            .contains("<note pname=\"f\" oct=\"4\" accid=\"s\" dur=\"4\" dots=\"1\" tie=\"t\"/>"));
+        assert!(mei.contains("<note pname=\"f\" oct=\"4\" accid.ges=\"s\" dur=\"8\"/>"));
+        assert!(mei.contains("<note pname=\"f\" oct=\"4\" accid=\"n\" dur=\"4\"/>"));
+        assert!(mei.contains("<chord dur=\"2\">"));
+        assert!(mei.contains("<note pname=\"g\" oct=\"3\"/>"));
+    }
+
+    #[test]
+    fn feathered_beams() {
+        let mut beamed = container(vec![
+            note(pitch(C, Natural, 4), Duration::new(1, 16)).unwrap(),
+            note(pitch(C, Natural, 4), Duration::new(1, 8)).unwrap(),
+        ])
+        .unwrap();
+        beamed.set_feathered_beam(Some(FeatheredBeam::Ritardando));
+        let score = ScoreBuilder::new()
+            .staff("1", |s| s.voice("1", |v| v.push(beamed)))
+            .build()
+            .unwrap();
+        let mei = write_mei(&score).unwrap();
+        assert!(mei.contains("<beam form=\"rit\">"));
+        assert!(mei.contains("</beam>"));
+    }
+
+    #[test]
+    fn maxima_and_lyrics() {
+        let score = ScoreBuilder::new()
+            .staff("1", |s| {
+                s.voice("1", |v| v.try_push(rest(Duration::new(8, 1))))
+            })
+            .build()
+            .unwrap();
+        assert!(write_mei(&score)
+            .unwrap()
+            .contains("<rest dur=\"maxima\"/>"));
+
+        let score = ScoreBuilder::new()
+            .staff("1", |s| {
+                s.voice("words", |v| {
+                    v.context(VoiceContext::Lyrics)
+                        .try_push(lyric("la", Duration::new(1, 4)))
+                })
+            })
+            .build()
+            .unwrap();
+        assert!(write_mei(&score).is_err());
+    }
+
+    #[test]
+    fn requires_a_score() {
+        let staff = ScoreBuilder::new().staff("1", |s| s).build().unwrap();
+        assert!(write_mei(&staff[0]).is_err());
+    }
+}
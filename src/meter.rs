@@ -0,0 +1,332 @@
+use crate::{
+    duration::Duration, error::Error, score_object::ScoreObject, time_signature::TimeSignature,
+};
+
+/// A beat hierarchy: each node lasts `duration` and divides into `children`
+/// whose durations sum to it. Leaf nodes are the smallest beats.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Meter {
+    duration: Duration,
+    children: Vec<Meter>,
+}
+
+impl Meter {
+    pub fn new(duration: Duration, children: Vec<Meter>) -> Result<Self, Error> {
+        let children_duration = children
+            .iter()
+            .fold(Duration::new(0, 1), |acc, child| acc + child.duration);
+        if children.is_empty() || children_duration == duration {
+            Ok(Self { duration, children })
+        } else {
+            Err(Error::MeterDurationMismatch(duration, children_duration))
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn children(&self) -> &Vec<Meter> {
+        &self.children
+    }
+
+    /// Offsets of the nodes at each depth of the hierarchy, each list
+    /// including the closing offset of the meter.
+    pub fn depthwise_offsets(&self) -> Vec<Vec<Duration>> {
+        let mut levels = vec![];
+        let mut nodes = vec![(Duration::new(0, 1), self)];
+        while !nodes.is_empty() {
+            let mut offsets: Vec<Duration> = nodes.iter().map(|(offset, _)| *offset).collect();
+            offsets.push(self.duration);
+            levels.push(offsets);
+
+            nodes = nodes
+                .iter()
+                .flat_map(|(offset, node)| {
+                    let mut child_offset = *offset;
+                    node.children.iter().map(move |child| {
+                        let entry = (child_offset, child);
+                        child_offset = child_offset + child.duration;
+                        entry
+                    })
+                })
+                .collect();
+        }
+        levels
+    }
+
+    /// Cuts the span `start..stop` into pieces that respect the beat
+    /// hierarchy: a piece is kept whole only when it begins and ends on the
+    /// boundaries of a single node's children.
+    fn fit(&self, offset: Duration, start: Duration, stop: Duration, pieces: &mut Vec<Duration>) {
+        let end = offset + self.duration;
+        let start = start.max(offset);
+        let stop = stop.min(end);
+        if start >= stop {
+            return;
+        }
+        if self.children.is_empty() {
+            pieces.push(stop - start);
+            return;
+        }
+
+        let mut boundaries = vec![offset];
+        for child in self.children.iter() {
+            boundaries.push(*boundaries.last().unwrap() + child.duration);
+        }
+        if boundaries.contains(&start)
+            && boundaries.contains(&stop)
+            && (stop - start).is_printable()
+        {
+            pieces.push(stop - start);
+            return;
+        }
+
+        for (child, child_offset) in self.children.iter().zip(boundaries) {
+            child.fit(child_offset, start, stop, pieces);
+        }
+    }
+}
+
+impl TryFrom<TimeSignature> for Meter {
+    type Error = Error;
+
+    /// Builds the conventional hierarchy for a time signature: up to four
+    /// beats sit directly under the measure, multiples of three group beats
+    /// in threes, other even numerators group them in twos, and odd ones as a
+    /// three followed by twos.
+    /// The numerator and denominator must be positive.
+    fn try_from(time_signature: TimeSignature) -> Result<Self, Self::Error> {
+        let (numerator, denominator) = time_signature.to_pair();
+        if numerator <= 0 || denominator <= 0 {
+            return Err(Error::InvalidTimeSignature(time_signature));
+        }
+        let beat = |count: i32| Meter {
+            duration: Duration::new(count, denominator),
+            children: match count {
+                1 => vec![],
+                _ => (0..count)
+                    .map(|_| Meter {
+                        duration: Duration::new(1, denominator),
+                        children: vec![],
+                    })
+                    .collect(),
+            },
+        };
+
+        let groups: Vec<i32> = match numerator {
+            1..=4 => return Ok(beat(numerator)),
+            n if n % 3 == 0 => vec![3; (n / 3) as usize],
+            n if n % 2 == 0 => vec![2; (n / 2) as usize],
+            n => std::iter::once(3)
+                .chain(std::iter::repeat_n(2, ((n - 3) / 2) as usize))
+                .collect(),
+        };
+
+        Ok(Meter {
+            duration: time_signature.duration(),
+            children: groups.into_iter().map(beat).collect(),
+        })
+    }
+}
+
+enum Event {
+    LogicalTie {
+        leaf: ScoreObject,
+        duration: Duration,
+    },
+    Opaque(ScoreObject),
+}
+
+/// Rewrites the rhythm of `component`'s direct contents to show the beat
+/// structure of `meter`. Tied notes and chords are fused into single logical
+/// ties and then re-split, tied, along beat boundaries. Tuplets and nested
+/// containers are left untouched. The total duration is preserved.
+pub fn rewrite_meter(component: &mut ScoreObject, meter: &Meter) -> Result<(), Error> {
+    if component.duration() != meter.duration {
+        return Err(Error::MeterDurationMismatch(
+            meter.duration,
+            component.duration(),
+        ));
+    }
+    let Some(contents) = component.contents_mut() else {
+        return Ok(());
+    };
+
+    let mut events: Vec<Event> = vec![];
+    for item in contents.drain(..) {
+        if let Some(Event::LogicalTie { leaf, duration }) = events.last_mut() {
+            if leaf.is_tied() && continues_tie(leaf, &item) {
+                *duration = *duration + item.duration();
+                leaf.set_is_tied(item.is_tied());
+                continue;
+            }
+        }
+        events.push(match item.is_leaf() {
+            true => Event::LogicalTie {
+                duration: item.duration(),
+                leaf: item,
+            },
+            false => Event::Opaque(item),
+        });
+    }
+
+    let mut offset = Duration::new(0, 1);
+    for event in events {
+        match event {
+            Event::Opaque(item) => {
+                offset = offset + item.duration();
+                contents.push(item);
+            }
+            Event::LogicalTie { leaf, duration } => {
+                let mut pieces = vec![];
+                meter.fit(Duration::new(0, 1), offset, offset + duration, &mut pieces);
+                let mut written_durations = vec![];
+                for piece in pieces {
+                    written_durations.extend(piece.to_printable_parts()?);
+                }
+                contents.extend(leaf.split_leaf(&written_durations));
+                offset = offset + duration;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn continues_tie(leaf: &ScoreObject, next: &ScoreObject) -> bool {
+    match (leaf, next) {
+        (
+            ScoreObject::Note { written_pitch, .. },
+            ScoreObject::Note {
+                written_pitch: next_pitch,
+                ..
+            },
+        ) => written_pitch == next_pitch,
+        (
+            ScoreObject::Chord {
+                written_pitches, ..
+            },
+            ScoreObject::Chord {
+                written_pitches: next_pitches,
+                ..
+            },
+        ) => written_pitches == next_pitches,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rewrite_meter, Meter};
+    use crate::duration::Duration;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{measure, note, rest, ScoreObject};
+    use crate::time_signature::TimeSignature;
+
+    fn c4(n: i32, d: i32) -> ScoreObject {
+        note(
+            Pitch::new(PitchClass::new(C, Natural), 4),
+            Duration::new(n, d),
+        )
+        .unwrap()
+    }
+
+    fn written_durations(component: &ScoreObject) -> Vec<(i32, i32)> {
+        component
+            .contents()
+            .unwrap()
+            .iter()
+            .map(|leaf| leaf.written_duration().unwrap().to_pair())
+            .collect()
+    }
+
+    #[test]
+    fn new() {
+        let beats = vec![
+            Meter::new(Duration::new(1, 4), vec![]).unwrap(),
+            Meter::new(Duration::new(1, 4), vec![]).unwrap(),
+        ];
+        assert!(Meter::new(Duration::new(2, 4), beats.clone()).is_ok());
+        assert!(Meter::new(Duration::new(3, 4), beats).is_err());
+    }
+
+    #[test]
+    fn from_time_signature() {
+        let simple = Meter::try_from(TimeSignature::new(3, 4)).unwrap();
+        assert_eq!(simple.children().len(), 3);
+
+        let compound = Meter::try_from(TimeSignature::new(6, 8)).unwrap();
+        assert_eq!(compound.children().len(), 2);
+        assert_eq!(compound.children()[0].duration(), Duration::new(3, 8));
+
+        let irregular = Meter::try_from(TimeSignature::new(7, 8)).unwrap();
+        let groups: Vec<Duration> = irregular.children().iter().map(|c| c.duration()).collect();
+        assert_eq!(
+            groups,
+            vec![
+                Duration::new(3, 8),
+                Duration::new(2, 8),
+                Duration::new(2, 8)
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_non_positive_time_signatures() {
+        assert!(Meter::try_from(TimeSignature::new(-3, 4)).is_err());
+        assert!(Meter::try_from(TimeSignature::new(0, 4)).is_err());
+        assert!(Meter::try_from(TimeSignature::new(3, 0)).is_err());
+    }
+
+    #[test]
+    fn depthwise_offsets() {
+        let offsets = Meter::try_from(TimeSignature::new(6, 8))
+            .unwrap()
+            .depthwise_offsets();
+        assert_eq!(offsets.len(), 3);
+        assert_eq!(
+            offsets[1],
+            vec![
+                Duration::new(0, 1),
+                Duration::new(3, 8),
+                Duration::new(3, 4)
+            ]
+        );
+        assert_eq!(offsets[2].len(), 7);
+    }
+
+    #[test]
+    fn rewrite_compound_meter() {
+        let mut m = measure(TimeSignature::new(6, 8), vec![c4(1, 4), c4(1, 4), c4(1, 4)]).unwrap();
+        rewrite_meter(&mut m, &Meter::try_from(TimeSignature::new(6, 8)).unwrap()).unwrap();
+
+        assert_eq!(written_durations(&m), vec![(1, 4), (1, 8), (1, 8), (1, 4)]);
+        assert!(m[1].is_tied());
+        assert!(!m[2].is_tied());
+        assert_eq!(m.duration(), Duration::new(3, 4));
+    }
+
+    #[test]
+    fn rewrite_fuses_ties() {
+        let mut first = c4(1, 4);
+        first.set_is_tied(true);
+        let mut m = measure(
+            TimeSignature::new(3, 4),
+            vec![first, c4(1, 4), rest(Duration::new(1, 4)).unwrap()],
+        )
+        .unwrap();
+        rewrite_meter(&mut m, &Meter::try_from(TimeSignature::new(3, 4)).unwrap()).unwrap();
+
+        assert_eq!(written_durations(&m), vec![(1, 2), (1, 4)]);
+        assert!(!m[0].is_tied());
+    }
+
+    #[test]
+    fn rewrite_rejects_mismatched_meter() {
+        let mut m = measure(TimeSignature::new(3, 4), vec![c4(1, 2), c4(1, 4)]).unwrap();
+        assert!(
+            rewrite_meter(&mut m, &Meter::try_from(TimeSignature::new(4, 4)).unwrap()).is_err()
+        );
+    }
+}
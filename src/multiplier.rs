@@ -1,4 +1,7 @@
 use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::{fmt, str::FromStr};
+
+use crate::{duration::parse_fraction, error::Error};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Multiplier {
@@ -28,6 +31,22 @@ impl Multiplier {
     }
 }
 
+/// Written as `"n/d"`, unreduced.
+impl fmt::Display for Multiplier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+impl FromStr for Multiplier {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (numerator, denominator) = parse_fraction(s)?;
+        Ok(Self::new(numerator, denominator))
+    }
+}
+
 impl Add<Multiplier> for Multiplier {
     type Output = Multiplier;
 
@@ -166,4 +185,11 @@ mod tests {
         assert_eq!(m1.abs(), Multiplier::new(2, 3));
         assert_eq!(m2.abs(), Multiplier::new(1, 4));
     }
+
+    #[test]
+    fn display_and_parse() {
+        assert_eq!(Multiplier::new(4, 6).to_string(), "4/6");
+        assert_eq!("4/6".parse::<Multiplier>().unwrap().to_pair(), (4, 6));
+        assert!("4/-6".parse::<Multiplier>().is_err());
+    }
 }
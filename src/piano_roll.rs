@@ -0,0 +1,368 @@
+//! SVG piano rolls, for checking generated material at a glance.
+//!
+//! Each note (and each chord tone) is drawn as a bar whose left edge is its
+//! offset and whose height is its written `Pitch::semitones()`, coloured by
+//! the voice (or, for staves without voices, the staff) that holds it.
+//! Measures are marked with vertical lines and tuplets with a bracket and
+//! ratio above their highest note.
+
+use crate::{
+    duration::Duration,
+    has_semitones::HasSemitones,
+    score_object::{ScoreObject, Timespan},
+};
+
+const COLOURS: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+];
+const MARGIN: f64 = 10.;
+const LABEL_HEIGHT: f64 = 14.;
+const BRACKET_HEIGHT: f64 = 16.;
+
+/// Renders piano rolls at a fixed scale.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PianoRoll {
+    pixels_per_whole_note: f64,
+    pixels_per_semitone: f64,
+    show_labels: bool,
+}
+
+impl Default for PianoRoll {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Layer<'a> {
+    path: Vec<usize>,
+    label: Option<&'a str>,
+}
+
+impl PianoRoll {
+    /// 200 pixels per whole note and 6 per semitone, without labels.
+    pub fn new() -> Self {
+        Self {
+            pixels_per_whole_note: 200.,
+            pixels_per_semitone: 6.,
+            show_labels: false,
+        }
+    }
+
+    pub fn with_pixels_per_whole_note(mut self, pixels: f64) -> Self {
+        self.pixels_per_whole_note = pixels;
+        self
+    }
+
+    pub fn with_pixels_per_semitone(mut self, pixels: f64) -> Self {
+        self.pixels_per_semitone = pixels;
+        self
+    }
+
+    /// Adds a legend naming each voice or staff from `name()`.
+    pub fn with_labels(mut self, show_labels: bool) -> Self {
+        self.show_labels = show_labels;
+        self
+    }
+
+    pub fn render(&self, component: &ScoreObject) -> String {
+        let timespans = component.leaf_timespans();
+        let notes: Vec<(&Timespan, f64)> = timespans
+            .iter()
+            .flat_map(|timespan| {
+                pitches(timespan.leaf)
+                    .into_iter()
+                    .map(move |semitones| (timespan, semitones))
+            })
+            .collect();
+        let highest = notes.iter().map(|(_, s)| *s).fold(f64::MIN, f64::max);
+        let lowest = notes.iter().map(|(_, s)| *s).fold(f64::MAX, f64::min);
+        let (highest, lowest) = if notes.is_empty() {
+            (0., 0.)
+        } else {
+            (highest + 1., lowest - 1.)
+        };
+
+        let mut layers: Vec<Layer> = vec![];
+        for (timespan, _) in notes.iter() {
+            let layer = layer(component, &timespan.path);
+            if !layers.iter().any(|l| l.path == layer.path) {
+                layers.push(layer);
+            }
+        }
+
+        let legend = if self.show_labels {
+            layers.len() as f64 * LABEL_HEIGHT
+        } else {
+            0.
+        };
+        let top = MARGIN + legend + BRACKET_HEIGHT;
+        let x = |offset: Duration| {
+            let (n, d) = offset.to_pair();
+            MARGIN + n as f64 / d as f64 * self.pixels_per_whole_note
+        };
+        let y = |semitones: f64| top + (highest - semitones) * self.pixels_per_semitone;
+        let width = x(component.duration()) + MARGIN;
+        let bottom = y(lowest);
+        let height = bottom + MARGIN;
+
+        let mut svg = vec![format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = number(width),
+            h = number(height),
+        )];
+
+        for offset in measure_lines(component) {
+            svg.push(format!(
+                r##"<line class="measure" x1="{x}" y1="{y1}" x2="{x}" y2="{y2}" stroke="#bbbbbb"/>"##,
+                x = number(x(offset)),
+                y1 = number(top),
+                y2 = number(bottom),
+            ));
+        }
+
+        for (timespan, semitones) in notes.iter() {
+            let path = layer(component, &timespan.path).path;
+            let index = layers.iter().position(|l| l.path == path).unwrap_or(0);
+            svg.push(format!(
+                r#"<rect class="note" x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                number(x(timespan.start_offset)),
+                number(y(*semitones + 0.5)),
+                number(x(timespan.stop_offset) - x(timespan.start_offset)),
+                number(self.pixels_per_semitone),
+                COLOURS[index % COLOURS.len()],
+            ));
+        }
+
+        for (path, tuplet) in tuplets(component) {
+            let inside: Vec<&(&Timespan, f64)> = notes
+                .iter()
+                .filter(|(t, _)| t.path.starts_with(&path))
+                .collect();
+            let start = timespans.iter().find(|t| t.path.starts_with(&path));
+            let stop = timespans.iter().rev().find(|t| t.path.starts_with(&path));
+            let (Some(start), Some(stop)) = (start, stop) else {
+                continue;
+            };
+            let peak = inside.iter().map(|(_, s)| *s).fold(highest - 1., f64::max);
+            let (x1, x2) = (x(start.start_offset), x(stop.stop_offset));
+            let bracket = y(peak + 0.5) - 4.;
+            let (n, d) = tuplet.display_ratio().unwrap_or((1, 1));
+            svg.push(format!(
+                r#"<path class="tuplet" d="M{x1} {low} V{high} H{x2} V{low}" fill="none" stroke="black"/>"#,
+                x1 = number(x1),
+                x2 = number(x2),
+                low = number(bracket),
+                high = number(bracket - 4.),
+            ));
+            svg.push(format!(
+                r#"<text class="tuplet" x="{}" y="{}" font-size="9" text-anchor="middle">{n}:{d}</text>"#,
+                number((x1 + x2) / 2.),
+                number(bracket - 6.),
+            ));
+        }
+
+        if self.show_labels {
+            for (index, layer) in layers.iter().enumerate() {
+                let row = MARGIN + index as f64 * LABEL_HEIGHT;
+                let colour = COLOURS[index % COLOURS.len()];
+                svg.push(format!(
+                    r#"<rect class="label" x="{}" y="{}" width="10" height="10" fill="{colour}"/>"#,
+                    number(MARGIN),
+                    number(row),
+                ));
+                svg.push(format!(
+                    r#"<text class="label" x="{}" y="{}" font-size="10">{}</text>"#,
+                    number(MARGIN + 14.),
+                    number(row + 9.),
+                    escape(layer.label.unwrap_or("")),
+                ));
+            }
+        }
+
+        svg.push("</svg>".to_string());
+        svg.join("\n") + "\n"
+    }
+}
+
+fn pitches(leaf: &ScoreObject) -> Vec<f64> {
+    match leaf {
+        ScoreObject::Note { written_pitch, .. } => vec![written_pitch.semitones() as f64],
+        ScoreObject::Chord {
+            written_pitches, ..
+        } => written_pitches
+            .iter()
+            .map(|p| p.semitones() as f64)
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// The innermost voice or staff around the leaf at `path`, labelled with the
+/// voice's name or else its staff's.
+fn layer<'a>(root: &'a ScoreObject, path: &[usize]) -> Layer<'a> {
+    let mut component = root;
+    let mut layer = Layer {
+        path: vec![],
+        label: root.name().as_deref(),
+    };
+    for (depth, index) in path.iter().enumerate() {
+        if matches!(
+            component,
+            ScoreObject::Staff { .. } | ScoreObject::Voice { .. }
+        ) {
+            layer = Layer {
+                path: path[..depth].to_vec(),
+                label: component.name().as_deref().or(layer.label),
+            };
+        }
+        match component.contents().and_then(|c| c.get(*index)) {
+            Some(child) => component = child,
+            None => break,
+        }
+    }
+    layer
+}
+
+/// The start and stop offsets of every measure, without repeats.
+fn measure_lines(component: &ScoreObject) -> Vec<Duration> {
+    fn collect(component: &ScoreObject, offset: Duration, lines: &mut Vec<Duration>) {
+        if let ScoreObject::Measure { .. } = component {
+            lines.push(offset);
+            lines.push(offset + component.duration());
+            return;
+        }
+        let mut child_offset = offset;
+        for child in component.contents().into_iter().flatten() {
+            collect(child, child_offset, lines);
+            if !component.is_simultaneous() {
+                child_offset = child_offset + child.duration();
+            }
+        }
+    }
+    let mut lines = vec![];
+    collect(component, Duration::new(0, 1), &mut lines);
+    lines.sort();
+    lines.dedup();
+    lines
+}
+
+fn tuplets(component: &ScoreObject) -> Vec<(Vec<usize>, &ScoreObject)> {
+    fn collect<'a>(
+        component: &'a ScoreObject,
+        path: &mut Vec<usize>,
+        tuplets: &mut Vec<(Vec<usize>, &'a ScoreObject)>,
+    ) {
+        if let ScoreObject::Tuplet { .. } = component {
+            tuplets.push((path.clone(), component));
+        }
+        for (index, child) in component.contents().into_iter().flatten().enumerate() {
+            path.push(index);
+            collect(child, path, tuplets);
+            path.pop();
+        }
+    }
+    let mut tuplets = vec![];
+    collect(component, &mut vec![], &mut tuplets);
+    tuplets
+}
+
+/// Formats a coordinate with at most two decimal places.
+fn number(value: f64) -> String {
+    let text = format!("{value:.2}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PianoRoll;
+    use crate::duration::Duration;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{chord, measure, note, rest, tuplet, ScoreBuilder, ScoreObject};
+    use crate::time_signature::TimeSignature;
+
+    fn pitch(
+        dpc: crate::pitch::DiatonicPitchClass,
+        accidental: crate::pitch::Accidental,
+        octave: i32,
+    ) -> Pitch {
+        Pitch::new(PitchClass::new(dpc, accidental), octave)
+    }
+
+    fn example() -> ScoreObject {
+        ScoreBuilder::new()
+            .staff("Piano", |s| {
+                s.voice("Right & Left", |v| {
+                    v.try_push(measure(
+                        TimeSignature::new(2, 4),
+                        vec![
+                            tuplet(
+                                Multiplier::new(2, 3),
+                                vec![
+                                    note(pitch(C, Natural, 5), Duration::new(1, 8)).unwrap(),
+                                    note(pitch(E, Natural, 5), Duration::new(1, 8)).unwrap(),
+                                    rest(Duration::new(1, 8)).unwrap(),
+                                ],
+                            )
+                            .unwrap(),
+                            note(pitch(G, Natural, 5), Duration::new(1, 4)).unwrap(),
+                        ],
+                    ))
+                    .try_push(measure(
+                        TimeSignature::new(2, 4),
+                        vec![note(pitch(C, Natural, 6), Duration::new(1, 2)).unwrap()],
+                    ))
+                })
+                .voice("Lower", |v| {
+                    v.try_push(chord(
+                        vec![pitch(C, Natural, 4), pitch(E, Natural, 4)],
+                        Duration::new(1, 1),
+                    ))
+                })
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn draws_notes_measures_and_tuplets() {
+        let svg = PianoRoll::new().render(&example());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"220\""));
+        assert_eq!(svg.matches("<rect class=\"note\"").count(), 6);
+        assert_eq!(svg.matches("<line class=\"measure\"").count(), 3);
+        assert_eq!(svg.matches("<path class=\"tuplet\"").count(), 1);
+        assert!(svg.contains(">3:2</text>"));
+        assert!(!svg.contains("class=\"label\""));
+        assert!(svg.contains("fill=\"#4e79a7\""));
+        assert!(svg.contains("fill=\"#f28e2b\""));
+    }
+
+    #[test]
+    fn positions() {
+        let svg = PianoRoll::new()
+            .with_pixels_per_whole_note(100.)
+            .with_pixels_per_semitone(4.)
+            .render(&example());
+        // C6 is the highest pitch, so it sits one semitone below the top.
+        assert!(svg.contains(
+            "<rect class=\"note\" x=\"60\" y=\"28\" width=\"50\" height=\"4\" fill=\"#4e79a7\"/>"
+        ));
+        assert!(svg.contains(
+            "<rect class=\"note\" x=\"18.33\" y=\"60\" width=\"8.33\" height=\"4\" fill=\"#4e79a7\"/>"
+        ));
+    }
+
+    #[test]
+    fn labels() {
+        let svg = PianoRoll::new().with_labels(true).render(&example());
+        assert!(svg.contains(">Right &amp; Left</text>"));
+        assert!(svg.contains(">Lower</text>"));
+    }
+}
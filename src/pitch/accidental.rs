@@ -1,6 +1,7 @@
 use crate::has_semitones::HasSemitones;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Accidental {
     DoubleFlat,
     ThreeQuarterFlat,
@@ -13,18 +14,36 @@ pub enum Accidental {
     DoubleSharp,
 }
 
+impl Accidental {
+    /// The accidental raising a note by `quarter_tones`, if there is one.
+    pub fn from_quarter_tones(quarter_tones: i32) -> Option<Self> {
+        match quarter_tones {
+            -4 => Some(Self::DoubleFlat),
+            -3 => Some(Self::ThreeQuarterFlat),
+            -2 => Some(Self::Flat),
+            -1 => Some(Self::QuarterFlat),
+            0 => Some(Self::Natural),
+            1 => Some(Self::QuarterSharp),
+            2 => Some(Self::Sharp),
+            3 => Some(Self::ThreeQuarterSharp),
+            4 => Some(Self::DoubleSharp),
+            _ => None,
+        }
+    }
+}
+
 impl HasSemitones for Accidental {
-    fn semitones(&self) -> f32 {
+    fn quarter_tones(&self) -> i32 {
         match self {
-            Self::DoubleFlat => -2.,
-            Self::ThreeQuarterFlat => -1.5,
-            Self::Flat => -1.,
-            Self::QuarterFlat => -0.5,
-            Self::Natural => 0.,
-            Self::QuarterSharp => 0.5,
-            Self::Sharp => 1.,
-            Self::ThreeQuarterSharp => 1.5,
-            Self::DoubleSharp => 2.,
+            Self::DoubleFlat => -4,
+            Self::ThreeQuarterFlat => -3,
+            Self::Flat => -2,
+            Self::QuarterFlat => -1,
+            Self::Natural => 0,
+            Self::QuarterSharp => 1,
+            Self::Sharp => 2,
+            Self::ThreeQuarterSharp => 3,
+            Self::DoubleSharp => 4,
         }
     }
 }
@@ -1,6 +1,7 @@
 use crate::has_semitones::HasSemitones;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DiatonicPitchClass {
     C = 0,
     D = 2,
@@ -11,8 +12,36 @@ pub enum DiatonicPitchClass {
     B = 11,
 }
 
+impl DiatonicPitchClass {
+    /// The letter's position in the scale from C, `0` to `6`.
+    pub fn index(&self) -> i32 {
+        match self {
+            Self::C => 0,
+            Self::D => 1,
+            Self::E => 2,
+            Self::F => 3,
+            Self::G => 4,
+            Self::A => 5,
+            Self::B => 6,
+        }
+    }
+
+    /// The letter at `index` steps above C, wrapping every seven steps.
+    pub fn from_index(index: i32) -> Self {
+        match index.rem_euclid(7) {
+            0 => Self::C,
+            1 => Self::D,
+            2 => Self::E,
+            3 => Self::F,
+            4 => Self::G,
+            5 => Self::A,
+            _ => Self::B,
+        }
+    }
+}
+
 impl HasSemitones for DiatonicPitchClass {
-    fn semitones(&self) -> f32 {
-        *self as i32 as f32
+    fn quarter_tones(&self) -> i32 {
+        *self as i32 * 2
     }
 }
@@ -0,0 +1,237 @@
+use std::{fmt, str::FromStr};
+
+use super::{Accidental, DiatonicPitchClass, Pitch, PitchClass};
+use crate::{error::Error, has_semitones::HasSemitones};
+
+/// A spelled interval: the distance in staff spaces (letter names) and in
+/// quarter tones, both signed so that descending intervals are negative.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Interval {
+    staff_spaces: i32,
+    quarter_tones: i32,
+}
+
+impl Interval {
+    pub fn new(staff_spaces: i32, quarter_tones: i32) -> Self {
+        Self {
+            staff_spaces,
+            quarter_tones,
+        }
+    }
+
+    /// The interval from `from` up (or down) to `to`.
+    pub fn between(from: &Pitch, to: &Pitch) -> Self {
+        Self::new(
+            to.diatonic_number() - from.diatonic_number(),
+            to.quarter_tones() - from.quarter_tones(),
+        )
+    }
+
+    pub fn staff_spaces(&self) -> i32 {
+        self.staff_spaces
+    }
+
+    /// The generic size: 1 for a unison, 5 for a fifth, 9 for a ninth.
+    pub fn number(&self) -> i32 {
+        self.staff_spaces.abs() + 1
+    }
+
+    pub fn is_descending(&self) -> bool {
+        self.staff_spaces < 0 || (self.staff_spaces == 0 && self.quarter_tones < 0)
+    }
+
+    /// The same interval reduced to less than an octave, keeping its
+    /// direction. Octaves reduce to unisons.
+    pub fn simple(&self) -> Self {
+        let octaves = self.staff_spaces / 7;
+        Self::new(
+            self.staff_spaces - octaves * 7,
+            self.quarter_tones - octaves * 24,
+        )
+    }
+
+    /// The interval in the opposite direction.
+    pub fn reversed(&self) -> Self {
+        Self::new(-self.staff_spaces, -self.quarter_tones)
+    }
+
+    pub fn is_perfect_fifth(&self) -> bool {
+        let simple = self.simple();
+        simple.staff_spaces.abs() == 4 && simple.quarter_tones.abs() == 14
+    }
+
+    /// True for perfect unisons, octaves and compound octaves.
+    pub fn is_perfect_octave(&self) -> bool {
+        self.staff_spaces % 7 == 0 && self.quarter_tones == self.staff_spaces / 7 * 24
+    }
+
+    /// Quarter tones above or below the major or perfect interval of the same
+    /// generic size, measured upwards.
+    fn deviation(&self) -> i32 {
+        let simple = if self.is_descending() {
+            self.reversed().simple()
+        } else {
+            self.simple()
+        };
+        let natural = DiatonicPitchClass::from_index(simple.staff_spaces).quarter_tones();
+        simple.quarter_tones - natural
+    }
+
+    fn is_perfect_type(&self) -> bool {
+        matches!(self.staff_spaces.abs() % 7, 0 | 3 | 4)
+    }
+}
+
+impl HasSemitones for Interval {
+    fn quarter_tones(&self) -> i32 {
+        self.quarter_tones
+    }
+}
+
+/// Written as quality and number, such as `M3`, `-P5` or `A4`. Quarter-tone
+/// intervals add `+` to the chromatic interval a quarter tone below.
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let deviation = self.deviation();
+        let quarter = if deviation.rem_euclid(2) == 1 {
+            "+"
+        } else {
+            ""
+        };
+        let steps = deviation.div_euclid(2);
+        let quality = match (self.is_perfect_type(), steps) {
+            (true, 0) => "P".to_string(),
+            (false, 0) => "M".to_string(),
+            (false, -1) => "m".to_string(),
+            (_, s) if s > 0 => "A".repeat(s as usize),
+            (true, s) => "d".repeat(s.unsigned_abs() as usize),
+            (false, s) => "d".repeat(s.unsigned_abs() as usize - 1),
+        };
+        let direction = if self.is_descending() { "-" } else { "" };
+        write!(f, "{direction}{quality}{}{quarter}", self.number())
+    }
+}
+
+impl FromStr for Interval {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::IntervalParse(s.to_string());
+        let (descending, rest) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (rest, quarter) = match rest.strip_suffix('+') {
+            Some(rest) => (rest, 1),
+            None => (rest, 0),
+        };
+        let split = rest
+            .find(|c: char| c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let (quality, number) = rest.split_at(split);
+        let number: i32 = number.parse().map_err(|_| invalid())?;
+        if number < 1 || quality.is_empty() {
+            return Err(invalid());
+        }
+        let staff_spaces = number - 1;
+        let is_perfect_type = matches!(staff_spaces % 7, 0 | 3 | 4);
+        let repeated = |c: char| quality.chars().all(|q| q == c) as i32 * quality.len() as i32;
+        let steps = match (quality, is_perfect_type) {
+            ("P", true) | ("M", false) => 0,
+            ("m", false) => -1,
+            _ if repeated('A') > 0 => repeated('A'),
+            _ if repeated('d') > 0 && is_perfect_type => -repeated('d'),
+            _ if repeated('d') > 0 => -repeated('d') - 1,
+            _ => return Err(invalid()),
+        };
+        let natural =
+            DiatonicPitchClass::from_index(staff_spaces).quarter_tones() + staff_spaces / 7 * 24;
+        let interval = Self::new(staff_spaces, natural + steps * 2 + quarter);
+        Ok(if descending {
+            interval.reversed()
+        } else {
+            interval
+        })
+    }
+}
+
+impl Pitch {
+    /// Moves the pitch by `interval`, keeping the spelling the interval
+    /// implies: C4 up a minor third is E-flat, not D-sharp. Falls back to a
+    /// sharp spelling when that would need more than a double accidental.
+    pub fn transpose(&self, interval: &Interval) -> Self {
+        let diatonic_number = self.diatonic_number() + interval.staff_spaces;
+        let letter = DiatonicPitchClass::from_index(diatonic_number);
+        let octave = diatonic_number.div_euclid(7);
+        let quarter_tones = self.quarter_tones() + interval.quarter_tones;
+        let natural = Pitch::new(PitchClass::new(letter, Accidental::Natural), octave);
+        let alteration = quarter_tones - natural.quarter_tones();
+        match Accidental::from_quarter_tones(alteration) {
+            Some(accidental) => Pitch::new(PitchClass::new(letter, accidental), octave),
+            None => Pitch::from_quarter_tones(quarter_tones),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Interval;
+    use crate::has_semitones::HasSemitones;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+
+    #[test]
+    fn between() {
+        let c4 = Pitch::new(PitchClass::new(C, Natural), 4);
+        let ef4 = Pitch::new(PitchClass::new(E, Flat), 4);
+        let g5 = Pitch::new(PitchClass::new(G, Natural), 5);
+
+        let minor_third = Interval::between(&c4, &ef4);
+        assert_eq!(minor_third, Interval::new(2, 6));
+        assert_eq!(minor_third.semitones(), 3.);
+        assert_eq!(Interval::between(&ef4, &c4).to_string(), "-m3");
+
+        let twelfth = Interval::between(&c4, &g5);
+        assert_eq!(twelfth.number(), 12);
+        assert_eq!(twelfth.simple(), Interval::new(4, 14));
+        assert!(twelfth.is_perfect_fifth());
+        assert!(Interval::new(7, 24).is_perfect_octave());
+        assert!(!Interval::new(7, 22).is_perfect_octave());
+    }
+
+    #[test]
+    fn display_and_parse() {
+        for name in [
+            "P1", "m2", "M3", "P4", "A4", "d5", "P5", "m6", "M7", "d7", "P8", "M9", "-M2", "AA4",
+            "dd5", "m3+",
+        ] {
+            let interval: Interval = name.parse().unwrap();
+            assert_eq!(interval.to_string(), name);
+        }
+        assert_eq!("d7".parse::<Interval>().unwrap(), Interval::new(6, 18));
+        assert_eq!("m3+".parse::<Interval>().unwrap(), Interval::new(2, 7));
+        assert!("P3".parse::<Interval>().is_err());
+        assert!("M5".parse::<Interval>().is_err());
+        assert!("X2".parse::<Interval>().is_err());
+    }
+
+    #[test]
+    fn transpose() {
+        let c4 = Pitch::new(PitchClass::new(C, Natural), 4);
+        assert_eq!(
+            c4.transpose(&"m3".parse().unwrap()),
+            Pitch::new(PitchClass::new(E, Flat), 4)
+        );
+        assert_eq!(
+            c4.transpose(&"-M2".parse().unwrap()),
+            Pitch::new(PitchClass::new(B, Flat), 3)
+        );
+        assert_eq!(
+            Pitch::new(PitchClass::new(F, Sharp), 4).transpose(&"A4".parse().unwrap()),
+            Pitch::new(PitchClass::new(B, Sharp), 4)
+        );
+        assert_eq!(
+            Pitch::new(PitchClass::new(B, Natural), 3).transpose(&"M9".parse().unwrap()),
+            Pitch::new(PitchClass::new(C, Sharp), 5)
+        );
+    }
+}
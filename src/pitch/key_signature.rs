@@ -0,0 +1,157 @@
+use super::{Accidental, DiatonicPitchClass, Pitch, PitchClass};
+use crate::{error::Error, has_semitones::HasSemitones};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    Major,
+    Minor,
+}
+
+impl Mode {
+    /// The scale degrees in quarter tones above the tonic.
+    fn intervals(&self) -> [i32; 7] {
+        match self {
+            Self::Major => [0, 4, 8, 10, 14, 18, 22],
+            Self::Minor => [0, 4, 6, 10, 14, 16, 20],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeySignature {
+    tonic: PitchClass,
+    mode: Mode,
+    scale: [PitchClass; 7],
+}
+
+impl KeySignature {
+    /// Fails for keys whose scale would need more than a double accidental,
+    /// such as G-double-sharp major.
+    pub fn new(tonic: PitchClass, mode: Mode) -> Result<Self, Error> {
+        let (tonic_letter, _) = tonic.to_pair();
+        let mut scale = [tonic; 7];
+        for (degree, interval) in mode.intervals().iter().enumerate() {
+            let letter = DiatonicPitchClass::from_index(tonic_letter.index() + degree as i32);
+            let alteration = (tonic.quarter_tones() + interval - letter.quarter_tones() + 12)
+                .rem_euclid(24)
+                - 12;
+            let accidental = Accidental::from_quarter_tones(alteration)
+                .ok_or(Error::UnspellableKey(tonic, mode))?;
+            scale[degree] = PitchClass::new(letter, accidental);
+        }
+        Ok(Self { tonic, mode, scale })
+    }
+
+    pub fn tonic(&self) -> PitchClass {
+        self.tonic
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// The seven diatonic degrees, spelled from the tonic on consecutive
+    /// letters. Minor keys use the natural minor scale.
+    pub fn scale(&self) -> Vec<PitchClass> {
+        self.scale.to_vec()
+    }
+
+    /// Sharps in the signature as a positive count, flats as a negative one.
+    pub fn accidental_count(&self) -> i32 {
+        self.scale()
+            .iter()
+            .map(|pitch_class| pitch_class.to_pair().1.quarter_tones() / 2)
+            .sum()
+    }
+
+    /// Spells a MIDI note number in this key: diatonic notes (and the raised
+    /// leading tone in minor) take their scale spelling, and chromatic notes
+    /// are spelled with sharps in sharp keys and flats in flat keys.
+    pub fn spell(&self, midi_number: i32) -> Pitch {
+        let quarter_tones = (midi_number - 60) * 2;
+        let pitch_class = quarter_tones.rem_euclid(24);
+        let scale = self.scale();
+
+        let diatonic = scale
+            .iter()
+            .find(|degree| degree.quarter_tones() == pitch_class)
+            .copied();
+        let leading_tone = match self.mode {
+            Mode::Minor => {
+                let (letter, accidental) = scale[6].to_pair();
+                Accidental::from_quarter_tones(accidental.quarter_tones() + 2)
+                    .map(|raised| PitchClass::new(letter, raised))
+                    .filter(|raised| raised.quarter_tones() == pitch_class)
+            }
+            Mode::Major => None,
+        };
+        let sharp = PitchClass::from_quarter_tones(pitch_class);
+        let chromatic = match self.accidental_count() < 0 {
+            true => sharp.respell_with_flats(),
+            false => sharp,
+        };
+
+        Pitch::with_spelling(
+            quarter_tones,
+            diatonic.or(leading_tone).unwrap_or(chromatic),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeySignature, Mode};
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+
+    #[test]
+    fn scale() {
+        let ef_major = KeySignature::new(PitchClass::new(E, Flat), Mode::Major).unwrap();
+        assert_eq!(
+            ef_major.scale(),
+            vec![
+                PitchClass::new(E, Flat),
+                PitchClass::new(F, Natural),
+                PitchClass::new(G, Natural),
+                PitchClass::new(A, Flat),
+                PitchClass::new(B, Flat),
+                PitchClass::new(C, Natural),
+                PitchClass::new(D, Natural),
+            ]
+        );
+        assert_eq!(ef_major.accidental_count(), -3);
+
+        let fs_minor = KeySignature::new(PitchClass::new(F, Sharp), Mode::Minor).unwrap();
+        assert_eq!(fs_minor.accidental_count(), 3);
+
+        assert!(KeySignature::new(PitchClass::new(G, DoubleSharp), Mode::Major).is_err());
+        assert!(KeySignature::new(PitchClass::new(F, DoubleFlat), Mode::Major).is_err());
+    }
+
+    #[test]
+    fn spell() {
+        let a_major = KeySignature::new(PitchClass::new(A, Natural), Mode::Major).unwrap();
+        assert_eq!(a_major.spell(61), Pitch::new(PitchClass::new(C, Sharp), 4));
+        assert_eq!(a_major.spell(63), Pitch::new(PitchClass::new(D, Sharp), 4));
+
+        let f_major = KeySignature::new(PitchClass::new(F, Natural), Mode::Major).unwrap();
+        assert_eq!(f_major.spell(58), Pitch::new(PitchClass::new(B, Flat), 3));
+        assert_eq!(f_major.spell(61), Pitch::new(PitchClass::new(D, Flat), 4));
+
+        let c_minor = KeySignature::new(PitchClass::new(C, Natural), Mode::Minor).unwrap();
+        assert_eq!(
+            c_minor.spell(71),
+            Pitch::new(PitchClass::new(B, Natural), 4)
+        );
+        assert_eq!(c_minor.spell(68), Pitch::new(PitchClass::new(A, Flat), 4));
+
+        let gs_minor = KeySignature::new(PitchClass::new(G, Sharp), Mode::Minor).unwrap();
+        assert_eq!(
+            gs_minor.spell(67),
+            Pitch::new(PitchClass::new(F, DoubleSharp), 4)
+        );
+        assert_eq!(
+            gs_minor.spell(72),
+            Pitch::new(PitchClass::new(C, Natural), 5)
+        );
+    }
+}
@@ -1,22 +1,30 @@
 mod accidental;
 mod diatonic_pitch_class;
+mod interval;
+mod key_signature;
+mod notation;
 mod pitch_class;
+mod spelling;
 
 pub use accidental::Accidental;
 pub use diatonic_pitch_class::DiatonicPitchClass;
+pub use interval::Interval;
+pub use key_signature::{KeySignature, Mode};
 pub use pitch_class::PitchClass;
 
+use std::cmp::Ordering;
+
 use crate::has_semitones::HasSemitones;
 
 type Octave = i32;
 
 impl HasSemitones for Octave {
-    fn semitones(&self) -> f32 {
-        (self - 4) as f32 * 12.
+    fn quarter_tones(&self) -> i32 {
+        (self - 4) * 24
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Pitch {
     pitch_class: PitchClass,
     octave: Octave,
@@ -29,11 +37,49 @@ impl Pitch {
             octave,
         }
     }
+
+    pub fn pitch_class(&self) -> PitchClass {
+        self.pitch_class
+    }
+
+    pub fn octave(&self) -> Octave {
+        self.octave
+    }
+
+    /// Diatonic steps above C0, counting letters and octaves but ignoring
+    /// accidentals.
+    pub fn diatonic_number(&self) -> i32 {
+        self.octave * 7 + self.pitch_class.to_pair().0.index()
+    }
+
+    /// Diatonic steps above middle C: the line or space the pitch occupies
+    /// relative to middle C on any staff.
+    pub fn staff_position(&self) -> i32 {
+        self.diatonic_number() - 28
+    }
 }
 
 impl HasSemitones for Pitch {
-    fn semitones(&self) -> f32 {
-        self.pitch_class.semitones() + self.octave.semitones()
+    fn quarter_tones(&self) -> i32 {
+        let (diatonic_pitch_class, accidental) = self.pitch_class.to_pair();
+        diatonic_pitch_class.quarter_tones()
+            + accidental.quarter_tones()
+            + self.octave.quarter_tones()
+    }
+}
+
+/// Orders by height, then by staff position, so `Bs3` sorts just before `C4`.
+impl Ord for Pitch {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.quarter_tones()
+            .cmp(&other.quarter_tones())
+            .then_with(|| self.staff_position().cmp(&other.staff_position()))
+    }
+}
+
+impl PartialOrd for Pitch {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -51,5 +97,50 @@ mod tests {
 
         let fqs6 = Pitch::new(PitchClass::new(F, QuarterSharp), 6);
         assert_eq!(fqs6.semitones(), 29.5);
+
+        let bs3 = Pitch::new(PitchClass::new(B, Sharp), 3);
+        assert_eq!(bs3.semitones(), 0.);
+
+        let cf4 = Pitch::new(PitchClass::new(C, Flat), 4);
+        assert_eq!(cf4.semitones(), -1.);
+        assert_eq!(cf4.quarter_tones(), -2);
+    }
+
+    #[test]
+    fn staff_position() {
+        let c4 = Pitch::new(PitchClass::new(C, Natural), 4);
+        assert_eq!(c4.staff_position(), 0);
+        assert_eq!(c4.diatonic_number(), 28);
+
+        let bs3 = Pitch::new(PitchClass::new(B, Sharp), 3);
+        assert_eq!(bs3.staff_position(), -1);
+
+        let gf5 = Pitch::new(PitchClass::new(G, Flat), 5);
+        assert_eq!(gf5.staff_position(), 11);
+    }
+
+    #[test]
+    fn ordering() {
+        let c4 = Pitch::new(PitchClass::new(C, Natural), 4);
+        let bs3 = Pitch::new(PitchClass::new(B, Sharp), 3);
+        let dff4 = Pitch::new(PitchClass::new(D, DoubleFlat), 4);
+        let cs4 = Pitch::new(PitchClass::new(C, Sharp), 4);
+
+        let mut pitches = vec![cs4, dff4, c4, bs3];
+        pitches.sort();
+        assert_eq!(pitches, vec![bs3, c4, dff4, cs4]);
+        assert_ne!(c4, bs3);
+    }
+
+    #[test]
+    fn hash() {
+        use std::collections::HashMap;
+
+        let c4 = Pitch::new(PitchClass::new(C, Natural), 4);
+        let bs3 = Pitch::new(PitchClass::new(B, Sharp), 3);
+        let counts: HashMap<Pitch, i32> = [(c4, 1), (bs3, 2)].into_iter().collect();
+
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&c4], 1);
     }
 }
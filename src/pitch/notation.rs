@@ -0,0 +1,163 @@
+use std::{fmt, str::FromStr};
+
+use super::{Accidental, DiatonicPitchClass, Pitch, PitchClass};
+use crate::error::Error;
+
+const ACCIDENTAL_SUFFIXES: [(Accidental, &str); 9] = [
+    (Accidental::DoubleFlat, "ff"),
+    (Accidental::ThreeQuarterFlat, "tqf"),
+    (Accidental::Flat, "f"),
+    (Accidental::QuarterFlat, "qf"),
+    (Accidental::Natural, ""),
+    (Accidental::QuarterSharp, "qs"),
+    (Accidental::Sharp, "s"),
+    (Accidental::ThreeQuarterSharp, "tqs"),
+    (Accidental::DoubleSharp, "ss"),
+];
+
+/// LilyPond English accidental suffixes: `f`, `s`, `qs`, `tqf`, `ss` and so
+/// on, with an empty string for natural.
+impl fmt::Display for Accidental {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, suffix) = ACCIDENTAL_SUFFIXES
+            .iter()
+            .find(|(accidental, _)| accidental == self)
+            .unwrap();
+        write!(f, "{suffix}")
+    }
+}
+
+impl FromStr for Accidental {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ACCIDENTAL_SUFFIXES
+            .iter()
+            .find(|(_, suffix)| *suffix == s)
+            .map(|(accidental, _)| *accidental)
+            .ok_or_else(|| Error::PitchParse(s.to_string()))
+    }
+}
+
+impl fmt::Display for DiatonicPitchClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letter = match self {
+            Self::C => "c",
+            Self::D => "d",
+            Self::E => "e",
+            Self::F => "f",
+            Self::G => "g",
+            Self::A => "a",
+            Self::B => "b",
+        };
+        write!(f, "{letter}")
+    }
+}
+
+impl FromStr for DiatonicPitchClass {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "c" => Ok(Self::C),
+            "d" => Ok(Self::D),
+            "e" => Ok(Self::E),
+            "f" => Ok(Self::F),
+            "g" => Ok(Self::G),
+            "a" => Ok(Self::A),
+            "b" => Ok(Self::B),
+            _ => Err(Error::PitchParse(s.to_string())),
+        }
+    }
+}
+
+/// Written in LilyPond English, such as `cs` or `bf`.
+impl fmt::Display for PitchClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (diatonic_pitch_class, accidental) = self.to_pair();
+        write!(f, "{diatonic_pitch_class}{accidental}")
+    }
+}
+
+impl FromStr for PitchClass {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::PitchParse(s.to_string());
+        if !s.is_char_boundary(1) || s.is_empty() {
+            return Err(invalid());
+        }
+        let (letter, suffix) = s.split_at(1);
+        Ok(Self::new(
+            letter.parse().map_err(|_| invalid())?,
+            suffix.parse().map_err(|_| invalid())?,
+        ))
+    }
+}
+
+/// Written in LilyPond English with absolute octave marks: `c` is C3, `c'`
+/// middle C, `cs''` C-sharp 5 and `bf,,` B-flat 1.
+impl fmt::Display for Pitch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let marks = match self.octave - 3 {
+            n if n < 0 => ",".repeat(n.unsigned_abs() as usize),
+            n => "'".repeat(n as usize),
+        };
+        write!(f, "{}{marks}", self.pitch_class)
+    }
+}
+
+impl FromStr for Pitch {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim_end_matches(['\'', ',']);
+        let marks = &s[name.len()..];
+        let raised = marks.chars().filter(|c| *c == '\'').count() as i32;
+        let lowered = marks.chars().filter(|c| *c == ',').count() as i32;
+        if raised > 0 && lowered > 0 {
+            return Err(Error::PitchParse(s.to_string()));
+        }
+        let pitch_class = name.parse().map_err(|_| Error::PitchParse(s.to_string()))?;
+        Ok(Self::new(pitch_class, 3 + raised - lowered))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+
+    #[test]
+    fn display() {
+        assert_eq!(PitchClass::new(C, Sharp).to_string(), "cs");
+        assert_eq!(PitchClass::new(B, ThreeQuarterFlat).to_string(), "btqf");
+        assert_eq!(Pitch::new(PitchClass::new(C, Natural), 4).to_string(), "c'");
+        assert_eq!(Pitch::new(PitchClass::new(C, Sharp), 5).to_string(), "cs''");
+        assert_eq!(Pitch::new(PitchClass::new(A, Natural), 3).to_string(), "a");
+        assert_eq!(Pitch::new(PitchClass::new(B, Flat), 1).to_string(), "bf,,");
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "cs''".parse::<Pitch>().unwrap(),
+            Pitch::new(PitchClass::new(C, Sharp), 5)
+        );
+        assert_eq!(
+            "eqf,".parse::<Pitch>().unwrap(),
+            Pitch::new(PitchClass::new(E, QuarterFlat), 2)
+        );
+        assert_eq!(
+            "fss".parse::<PitchClass>().unwrap(),
+            PitchClass::new(F, DoubleSharp)
+        );
+        for bad in ["", "h", "cx", "c',", "'"] {
+            assert!(bad.parse::<Pitch>().is_err(), "{bad}");
+        }
+
+        for octave in 0..8 {
+            let pitch = Pitch::new(PitchClass::new(G, ThreeQuarterSharp), octave);
+            assert_eq!(pitch.to_string().parse::<Pitch>().unwrap(), pitch);
+        }
+    }
+}
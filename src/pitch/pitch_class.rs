@@ -1,7 +1,9 @@
+use std::cmp::Ordering;
+
 use super::{Accidental, DiatonicPitchClass};
 use crate::has_semitones::HasSemitones;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct PitchClass {
     diatonic_pitch_class: DiatonicPitchClass,
     accidental: Accidental,
@@ -18,19 +20,33 @@ impl PitchClass {
     pub fn to_pair(&self) -> (DiatonicPitchClass, Accidental) {
         (self.diatonic_pitch_class, self.accidental)
     }
+}
 
-    pub fn semitones(&self) -> f32 {
-        match self.diatonic_pitch_class.semitones() + self.accidental.semitones() {
-            s if s < 0. => s + 12.,
-            s if s >= 12. => s - 12.,
-            s => s,
-        }
+impl HasSemitones for PitchClass {
+    fn quarter_tones(&self) -> i32 {
+        (self.diatonic_pitch_class.quarter_tones() + self.accidental.quarter_tones()).rem_euclid(24)
+    }
+}
+
+/// Orders by height above C, then by letter from C to B, so enharmonic
+/// spellings sort next to each other (`C`, then `Dff`, then `Bs`).
+impl Ord for PitchClass {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.quarter_tones()
+            .cmp(&other.quarter_tones())
+            .then_with(|| self.diatonic_pitch_class.cmp(&other.diatonic_pitch_class))
+    }
+}
+
+impl PartialOrd for PitchClass {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Accidental::*, DiatonicPitchClass::*, PitchClass};
+    use super::{Accidental::*, DiatonicPitchClass::*, HasSemitones, PitchClass};
 
     #[test]
     fn new() {
@@ -45,5 +61,29 @@ mod tests {
         assert_eq!(PitchClass::new(E, Flat).semitones(), 3.);
         assert_eq!(PitchClass::new(C, DoubleFlat).semitones(), 10.);
         assert_eq!(PitchClass::new(B, ThreeQuarterSharp).semitones(), 0.5);
+        assert_eq!(PitchClass::new(B, ThreeQuarterSharp).quarter_tones(), 1);
+    }
+
+    #[test]
+    fn ordering() {
+        let mut pitch_classes = vec![
+            PitchClass::new(D, DoubleFlat),
+            PitchClass::new(E, Flat),
+            PitchClass::new(C, Natural),
+            PitchClass::new(B, Sharp),
+            PitchClass::new(D, Flat),
+        ];
+        pitch_classes.sort();
+
+        assert_eq!(
+            pitch_classes,
+            vec![
+                PitchClass::new(C, Natural),
+                PitchClass::new(D, DoubleFlat),
+                PitchClass::new(B, Sharp),
+                PitchClass::new(D, Flat),
+                PitchClass::new(E, Flat),
+            ]
+        );
     }
 }
@@ -0,0 +1,213 @@
+use super::{Accidental, DiatonicPitchClass, Pitch, PitchClass};
+use crate::has_semitones::HasSemitones;
+
+const SHARP_SPELLINGS: [(DiatonicPitchClass, Accidental); 12] = [
+    (DiatonicPitchClass::C, Accidental::Natural),
+    (DiatonicPitchClass::C, Accidental::Sharp),
+    (DiatonicPitchClass::D, Accidental::Natural),
+    (DiatonicPitchClass::D, Accidental::Sharp),
+    (DiatonicPitchClass::E, Accidental::Natural),
+    (DiatonicPitchClass::F, Accidental::Natural),
+    (DiatonicPitchClass::F, Accidental::Sharp),
+    (DiatonicPitchClass::G, Accidental::Natural),
+    (DiatonicPitchClass::G, Accidental::Sharp),
+    (DiatonicPitchClass::A, Accidental::Natural),
+    (DiatonicPitchClass::A, Accidental::Sharp),
+    (DiatonicPitchClass::B, Accidental::Natural),
+];
+
+const FLAT_SPELLINGS: [(DiatonicPitchClass, Accidental); 12] = [
+    (DiatonicPitchClass::C, Accidental::Natural),
+    (DiatonicPitchClass::D, Accidental::Flat),
+    (DiatonicPitchClass::D, Accidental::Natural),
+    (DiatonicPitchClass::E, Accidental::Flat),
+    (DiatonicPitchClass::E, Accidental::Natural),
+    (DiatonicPitchClass::F, Accidental::Natural),
+    (DiatonicPitchClass::G, Accidental::Flat),
+    (DiatonicPitchClass::G, Accidental::Natural),
+    (DiatonicPitchClass::A, Accidental::Flat),
+    (DiatonicPitchClass::A, Accidental::Natural),
+    (DiatonicPitchClass::B, Accidental::Flat),
+    (DiatonicPitchClass::B, Accidental::Natural),
+];
+
+/// Odd quarter tones raise the semitone below by a quarter tone.
+fn spell_with_sharps(quarter_tones: i32) -> PitchClass {
+    let quarter_tones = quarter_tones.rem_euclid(24);
+    let (diatonic_pitch_class, accidental) = SHARP_SPELLINGS[(quarter_tones / 2) as usize];
+    let accidental = match (accidental, quarter_tones % 2) {
+        (_, 0) => accidental,
+        (Accidental::Natural, _) => Accidental::QuarterSharp,
+        _ => Accidental::ThreeQuarterSharp,
+    };
+    PitchClass::new(diatonic_pitch_class, accidental)
+}
+
+/// Odd quarter tones lower the semitone above by a quarter tone.
+fn spell_with_flats(quarter_tones: i32) -> PitchClass {
+    let quarter_tones = quarter_tones.rem_euclid(24);
+    let (diatonic_pitch_class, accidental) =
+        FLAT_SPELLINGS[((quarter_tones + 1) / 2 % 12) as usize];
+    let accidental = match (accidental, quarter_tones % 2) {
+        (_, 0) => accidental,
+        (Accidental::Natural, _) => Accidental::QuarterFlat,
+        _ => Accidental::ThreeQuarterFlat,
+    };
+    PitchClass::new(diatonic_pitch_class, accidental)
+}
+
+impl PitchClass {
+    /// Spells a pitch-class number in quarter tones above C (`0` for C, `3`
+    /// for C three-quarter sharp, ...) with sharps.
+    pub fn from_quarter_tones(quarter_tones: i32) -> Self {
+        spell_with_sharps(quarter_tones)
+    }
+
+    pub fn respell_with_sharps(&self) -> Self {
+        spell_with_sharps(self.quarter_tones())
+    }
+
+    pub fn respell_with_flats(&self) -> Self {
+        spell_with_flats(self.quarter_tones())
+    }
+
+    /// Respells double accidentals and `Es`/`Fb`/`Bs`/`Cf` with the simplest
+    /// accidental in the same direction.
+    pub fn simplify(&self) -> Self {
+        match self.to_pair().1.quarter_tones() {
+            q if q > 0 => self.respell_with_sharps(),
+            q if q < 0 => self.respell_with_flats(),
+            _ => *self,
+        }
+    }
+
+    pub fn is_enharmonic_with(&self, other: &PitchClass) -> bool {
+        self.quarter_tones() == other.quarter_tones()
+    }
+}
+
+impl Pitch {
+    /// Spells the pitch `quarter_tones` above middle C with sharps.
+    pub fn from_quarter_tones(quarter_tones: i32) -> Self {
+        Self::with_spelling(quarter_tones, spell_with_sharps(quarter_tones))
+    }
+
+    pub fn respell_with_sharps(&self) -> Self {
+        Self::from_quarter_tones(self.quarter_tones())
+    }
+
+    pub fn respell_with_flats(&self) -> Self {
+        let quarter_tones = self.quarter_tones();
+        Self::with_spelling(quarter_tones, spell_with_flats(quarter_tones))
+    }
+
+    pub fn simplify(&self) -> Self {
+        Self::with_spelling(self.quarter_tones(), self.pitch_class().simplify())
+    }
+
+    pub fn is_enharmonic_with(&self, other: &Pitch) -> bool {
+        self.quarter_tones() == other.quarter_tones()
+    }
+
+    /// The pitch `quarter_tones` above middle C written as `pitch_class`,
+    /// which must be enharmonically equivalent to it; the octave is chosen to
+    /// match.
+    pub(crate) fn with_spelling(quarter_tones: i32, pitch_class: PitchClass) -> Self {
+        let (diatonic_pitch_class, accidental) = pitch_class.to_pair();
+        let natural =
+            quarter_tones - accidental.quarter_tones() - diatonic_pitch_class.quarter_tones();
+        Self::new(pitch_class, (natural + 12).div_euclid(24) + 4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+
+    #[test]
+    fn pitch_class_respelling() {
+        let gs = PitchClass::new(G, Sharp);
+        assert_eq!(gs.respell_with_flats(), PitchClass::new(A, Flat));
+        assert_eq!(gs.respell_with_flats().respell_with_sharps(), gs);
+
+        let cqs = PitchClass::new(C, QuarterSharp);
+        assert_eq!(
+            cqs.respell_with_flats(),
+            PitchClass::new(D, ThreeQuarterFlat)
+        );
+        assert_eq!(
+            PitchClass::new(D, ThreeQuarterFlat).respell_with_sharps(),
+            cqs
+        );
+        assert_eq!(
+            PitchClass::new(B, QuarterFlat).respell_with_sharps(),
+            PitchClass::new(A, ThreeQuarterSharp)
+        );
+    }
+
+    #[test]
+    fn pitch_class_simplify() {
+        assert_eq!(
+            PitchClass::new(C, DoubleSharp).simplify(),
+            PitchClass::new(D, Natural)
+        );
+        assert_eq!(
+            PitchClass::new(F, Flat).simplify(),
+            PitchClass::new(E, Natural)
+        );
+        assert_eq!(
+            PitchClass::new(B, DoubleFlat).simplify(),
+            PitchClass::new(A, Natural)
+        );
+        assert_eq!(
+            PitchClass::new(E, Flat).simplify(),
+            PitchClass::new(E, Flat)
+        );
+    }
+
+    #[test]
+    fn pitch_respelling_crosses_octaves() {
+        let bs3 = Pitch::new(PitchClass::new(B, Sharp), 3);
+        assert_eq!(bs3.simplify(), Pitch::new(PitchClass::new(C, Natural), 4));
+
+        let c4 = Pitch::new(PitchClass::new(C, Natural), 4);
+        assert_eq!(
+            Pitch::new(PitchClass::new(C, Flat), 4).simplify(),
+            Pitch::new(PitchClass::new(B, Natural), 3)
+        );
+        assert_eq!(c4.respell_with_flats(), c4);
+
+        let cqf4 = Pitch::new(PitchClass::new(C, QuarterFlat), 4);
+        assert_eq!(
+            cqf4.respell_with_sharps(),
+            Pitch::new(PitchClass::new(B, QuarterSharp), 3)
+        );
+    }
+
+    #[test]
+    fn enharmonic_equivalence() {
+        let gs4 = Pitch::new(PitchClass::new(G, Sharp), 4);
+        let af4 = Pitch::new(PitchClass::new(A, Flat), 4);
+        let af5 = Pitch::new(PitchClass::new(A, Flat), 5);
+
+        assert!(gs4.is_enharmonic_with(&af4));
+        assert!(!gs4.is_enharmonic_with(&af5));
+        assert!(gs4.pitch_class().is_enharmonic_with(&af5.pitch_class()));
+    }
+
+    #[test]
+    fn from_quarter_tones() {
+        assert_eq!(
+            Pitch::from_quarter_tones(-22),
+            Pitch::new(PitchClass::new(C, Sharp), 3)
+        );
+        assert_eq!(
+            PitchClass::from_quarter_tones(27),
+            PitchClass::new(C, ThreeQuarterSharp)
+        );
+        assert_eq!(
+            Pitch::from_quarter_tones(-1).respell_with_flats(),
+            Pitch::new(PitchClass::new(C, QuarterFlat), 4)
+        );
+    }
+}
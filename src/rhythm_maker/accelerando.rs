@@ -0,0 +1,180 @@
+use num::Integer;
+
+use super::RhythmMaker;
+use crate::{
+    duration::Duration,
+    error::Error,
+    pitch::Pitch,
+    score_object::{container, FeatheredBeam, ScoreObject},
+};
+
+/// Fills each division with notes whose durations move exponentially from
+/// `start_duration` to `stop_duration`, rounded to a grid of `quantum` (or
+/// finer, when a division is not a multiple of it). Each division's container
+/// carries the matching feathered beam.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccelerandoRhythmMaker {
+    start_duration: Duration,
+    stop_duration: Duration,
+    quantum: Duration,
+    pitch: Pitch,
+}
+
+impl AccelerandoRhythmMaker {
+    pub fn new(
+        start_duration: Duration,
+        stop_duration: Duration,
+        pitch: Pitch,
+    ) -> Result<Self, Error> {
+        for duration in [start_duration, stop_duration] {
+            if duration <= Duration::new(0, 1) {
+                return Err(Error::NonPositiveDuration(duration));
+            }
+        }
+        Ok(Self {
+            start_duration,
+            stop_duration,
+            quantum: Duration::new(1, 32),
+            pitch,
+        })
+    }
+
+    pub fn with_quantum(mut self, quantum: Duration) -> Result<Self, Error> {
+        if quantum <= Duration::new(0, 1) {
+            return Err(Error::NonPositiveDuration(quantum));
+        }
+        self.quantum = quantum;
+        Ok(self)
+    }
+
+    /// The feathered beam set on each division's container.
+    pub fn feathered_beam(&self) -> Option<FeatheredBeam> {
+        match self.stop_duration.cmp(&self.start_duration) {
+            std::cmp::Ordering::Less => Some(FeatheredBeam::Accelerando),
+            std::cmp::Ordering::Greater => Some(FeatheredBeam::Ritardando),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// The quantised durations of the notes filling `division`.
+    pub fn durations(&self, division: Duration) -> Result<Vec<Duration>, Error> {
+        let grid = match (division / self.quantum).to_pair() {
+            (_, 1) => self.quantum,
+            _ => Duration::new(1, self.quantum.to_pair().1.lcm(&division.to_pair().1)),
+        };
+        let total_units = (division / grid).to_pair().0;
+        if total_units <= 0 {
+            return Err(Error::UnprintableDuration(division));
+        }
+
+        let (start, stop) = (
+            self.start_duration.to_float(),
+            self.stop_duration.to_float(),
+        );
+        let average = (start + stop) / 2.;
+        let count = ((division.to_float() / average).round() as i32).clamp(1, total_units);
+
+        let raw: Vec<f32> = (0..count)
+            .map(|i| match count {
+                1 => start,
+                _ => start * (stop / start).powf(i as f32 / (count - 1) as f32),
+            })
+            .collect();
+        let scale = total_units as f32 / raw.iter().sum::<f32>();
+
+        let mut offsets = vec![0];
+        let mut cumulative = 0.;
+        for (i, duration) in raw.iter().enumerate() {
+            cumulative += duration * scale;
+            let previous = *offsets.last().unwrap();
+            let remaining_notes = count - 1 - i as i32;
+            let offset =
+                (cumulative.round() as i32).clamp(previous + 1, total_units - remaining_notes);
+            offsets.push(offset);
+        }
+        *offsets.last_mut().unwrap() = total_units;
+
+        Ok(offsets
+            .windows(2)
+            .map(|pair| grid * (pair[1] - pair[0]))
+            .collect())
+    }
+}
+
+impl RhythmMaker for AccelerandoRhythmMaker {
+    fn make(&self, divisions: &[Duration]) -> Result<Vec<ScoreObject>, Error> {
+        divisions
+            .iter()
+            .map(|division| {
+                let mut contents = vec![];
+                for written_duration in self.durations(*division)? {
+                    let note = ScoreObject::Note {
+                        written_pitch: self.pitch,
+                        written_duration,
+                        is_tied: false,
+                    };
+                    contents.extend(note.split_leaf(&written_duration.to_printable_parts()?));
+                }
+                let mut division = container(contents)?;
+                division.set_feathered_beam(self.feathered_beam());
+                Ok(division)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AccelerandoRhythmMaker;
+    use crate::duration::Duration;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::rhythm_maker::{FeatheredBeam, RhythmMaker};
+
+    fn maker(start: Duration, stop: Duration) -> AccelerandoRhythmMaker {
+        AccelerandoRhythmMaker::new(start, stop, Pitch::new(PitchClass::new(C, Natural), 4))
+            .unwrap()
+    }
+
+    #[test]
+    fn accelerando_durations() {
+        let m = maker(Duration::new(1, 8), Duration::new(1, 32));
+        let durations = m.durations(Duration::new(1, 2)).unwrap();
+
+        assert_eq!(durations.len(), 6);
+        assert!(durations.windows(2).all(|pair| pair[0] >= pair[1]));
+        assert!(durations.first() > durations.last());
+        assert_eq!(
+            durations
+                .iter()
+                .fold(Duration::new(0, 1), |acc, d| acc + *d),
+            Duration::new(1, 2)
+        );
+        assert_eq!(m.feathered_beam(), Some(FeatheredBeam::Accelerando));
+    }
+
+    #[test]
+    fn ritardando_make() {
+        let m = maker(Duration::new(1, 32), Duration::new(1, 8));
+        let made = m.make(&[Duration::new(1, 2), Duration::new(3, 8)]).unwrap();
+
+        assert_eq!(m.feathered_beam(), Some(FeatheredBeam::Ritardando));
+        assert_eq!(made[0].feathered_beam(), Some(FeatheredBeam::Ritardando));
+        assert_eq!(made[0].duration(), Duration::new(1, 2));
+        assert_eq!(made[1].duration(), Duration::new(3, 8));
+        assert!(made
+            .iter()
+            .flat_map(|c| c.contents().unwrap().iter())
+            .all(|leaf| leaf.written_duration().unwrap().is_printable()));
+    }
+
+    #[test]
+    fn rejects_non_positive_durations() {
+        let c4 = Pitch::new(PitchClass::new(C, Natural), 4);
+        let eighth = Duration::new(1, 8);
+        assert!(AccelerandoRhythmMaker::new(Duration::new(0, 1), eighth, c4).is_err());
+        assert!(AccelerandoRhythmMaker::new(eighth, Duration::new(-1, 8), c4).is_err());
+        let m = maker(eighth, Duration::new(1, 32));
+        assert!(m.clone().with_quantum(Duration::new(0, 1)).is_err());
+        assert!(m.with_quantum(Duration::new(1, 64)).is_ok());
+    }
+}
@@ -0,0 +1,71 @@
+use super::RhythmMaker;
+use crate::{
+    duration::Duration, error::Error, pitch::Pitch, rhythm_tree::RhythmTree,
+    score_object::ScoreObject,
+};
+
+/// Divides every division into `parts` equal notes, wrapping them in a tuplet
+/// when the parts cannot be written with printable durations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvenDivisionRhythmMaker {
+    parts: i32,
+    pitch: Pitch,
+}
+
+impl EvenDivisionRhythmMaker {
+    /// `parts` must be positive.
+    pub fn new(parts: i32, pitch: Pitch) -> Result<Self, Error> {
+        if parts <= 0 {
+            return Err(Error::InvalidPartCount(parts));
+        }
+        Ok(Self { parts, pitch })
+    }
+}
+
+impl RhythmMaker for EvenDivisionRhythmMaker {
+    fn make(&self, divisions: &[Duration]) -> Result<Vec<ScoreObject>, Error> {
+        let tree = RhythmTree::Container(1, vec![RhythmTree::Leaf(1); self.parts as usize]);
+        divisions
+            .iter()
+            .map(|division| tree.to_score_object(*division, self.pitch))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EvenDivisionRhythmMaker;
+    use crate::duration::Duration;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::rhythm_maker::RhythmMaker;
+    use crate::score_object::ScoreObject;
+
+    #[test]
+    fn even_division() {
+        let maker =
+            EvenDivisionRhythmMaker::new(3, Pitch::new(PitchClass::new(C, Natural), 4)).unwrap();
+        let made = maker
+            .make(&[Duration::new(3, 8), Duration::new(1, 4)])
+            .unwrap();
+
+        assert!(matches!(made[0], ScoreObject::Container { .. }));
+        assert_eq!(made[0][0].written_duration(), Some(Duration::new(1, 8)));
+
+        match &made[1] {
+            ScoreObject::Tuplet { multiplier, .. } => {
+                assert_eq!(*multiplier, Multiplier::new(2, 3))
+            }
+            other => panic!("expected a tuplet, found {other:?}"),
+        }
+        assert_eq!(made[1][0].written_duration(), Some(Duration::new(1, 8)));
+        assert_eq!(made[1].duration(), Duration::new(1, 4));
+    }
+
+    #[test]
+    fn rejects_non_positive_parts() {
+        let c4 = Pitch::new(PitchClass::new(C, Natural), 4);
+        assert!(EvenDivisionRhythmMaker::new(0, c4).is_err());
+        assert!(EvenDivisionRhythmMaker::new(-2, c4).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{duration::Duration, error::Error, score_object::ScoreObject};
+
+mod accelerando;
+pub use crate::score_object::FeatheredBeam;
+pub use accelerando::AccelerandoRhythmMaker;
+
+mod even_division;
+pub use even_division::EvenDivisionRhythmMaker;
+
+mod talea;
+pub use talea::{Talea, TaleaRhythmMaker};
+
+pub trait RhythmMaker {
+    /// Fills each division with rhythm, returning one container or tuplet per
+    /// division.
+    fn make(&self, divisions: &[Duration]) -> Result<Vec<ScoreObject>, Error>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LeafClass {
+    Note,
+    Rest,
+}
+
+/// Selects indices, optionally repeating every `period` items.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    indices: Vec<usize>,
+    period: Option<usize>,
+}
+
+impl Pattern {
+    pub fn new(indices: Vec<usize>, period: Option<usize>) -> Self {
+        Self { indices, period }
+    }
+
+    pub fn matches(&self, index: usize) -> bool {
+        let index = match self.period {
+            Some(period) if period > 0 => index % period,
+            _ => index,
+        };
+        self.indices.contains(&index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pattern;
+
+    #[test]
+    fn pattern() {
+        let p = Pattern::new(vec![0, 2], Some(3));
+        let matched: Vec<usize> = (0..7).filter(|i| p.matches(*i)).collect();
+        assert_eq!(matched, vec![0, 2, 3, 5, 6]);
+
+        let p = Pattern::new(vec![1], None);
+        assert!(p.matches(1));
+        assert!(!p.matches(4));
+    }
+}
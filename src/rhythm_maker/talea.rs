@@ -0,0 +1,342 @@
+use num::Integer;
+
+use super::{LeafClass, Pattern, RhythmMaker};
+use crate::{
+    duration::Duration,
+    error::Error,
+    multiplier::Multiplier,
+    pitch::Pitch,
+    score_object::{container, tuplet, ScoreObject},
+};
+
+/// A cyclic sequence of counts in units of `1 / denominator`. Negative counts
+/// are rests; zero counts are skipped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Talea {
+    counts: Vec<i32>,
+    denominator: i32,
+}
+
+impl Talea {
+    /// The denominator must be positive and at least one count nonzero.
+    pub fn new(counts: Vec<i32>, denominator: i32) -> Result<Self, Error> {
+        if denominator <= 0 {
+            return Err(Error::InvalidTalea(format!(
+                "denominator {denominator} must be positive"
+            )));
+        }
+        if counts.iter().all(|count| *count == 0) {
+            return Err(Error::InvalidTalea(
+                "at least one count must be nonzero".to_string(),
+            ));
+        }
+        Ok(Self {
+            counts,
+            denominator,
+        })
+    }
+
+    pub fn counts(&self) -> &Vec<i32> {
+        &self.counts
+    }
+
+    pub fn denominator(&self) -> i32 {
+        self.denominator
+    }
+}
+
+/// Reads a talea continuously across divisions: a count left unfinished at
+/// the end of one division carries over into the next.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaleaRhythmMaker {
+    talea: Talea,
+    pitch: Pitch,
+    extra_counts: Vec<i32>,
+    rest_mask: Option<Pattern>,
+    left_burnish: Vec<LeafClass>,
+    right_burnish: Vec<LeafClass>,
+    tie_across_divisions: bool,
+}
+
+impl TaleaRhythmMaker {
+    pub fn new(talea: Talea, pitch: Pitch) -> Self {
+        Self {
+            talea,
+            pitch,
+            extra_counts: vec![],
+            rest_mask: None,
+            left_burnish: vec![],
+            right_burnish: vec![],
+            tie_across_divisions: false,
+        }
+    }
+
+    /// Counts added to each division in turn, cycling; a nonzero extra count
+    /// turns the division into a tuplet.
+    pub fn with_extra_counts(mut self, extra_counts: Vec<i32>) -> Self {
+        self.extra_counts = extra_counts;
+        self
+    }
+
+    /// Divisions selected by `rest_mask` are filled with rests.
+    pub fn with_rest_mask(mut self, rest_mask: Pattern) -> Self {
+        self.rest_mask = Some(rest_mask);
+        self
+    }
+
+    /// Forces the first and last leaves of every division to the given
+    /// classes.
+    pub fn with_burnish(mut self, left: Vec<LeafClass>, right: Vec<LeafClass>) -> Self {
+        self.left_burnish = left;
+        self.right_burnish = right;
+        self
+    }
+
+    pub fn with_tie_across_divisions(mut self, tie_across_divisions: bool) -> Self {
+        self.tie_across_divisions = tie_across_divisions;
+        self
+    }
+
+    fn division_counts(&self, divisions: &[Duration]) -> Result<(i32, Vec<Vec<i32>>), Error> {
+        let denominator = divisions
+            .iter()
+            .fold(self.talea.denominator, |acc, division| {
+                acc.lcm(&division.to_pair().1)
+            });
+        let scale = denominator / self.talea.denominator;
+
+        let mut talea = self
+            .talea
+            .counts
+            .iter()
+            .filter(|count| **count != 0)
+            .cycle()
+            .map(|count| count * scale);
+        let mut carried = 0;
+        let mut division_counts = vec![];
+
+        for (index, division) in divisions.iter().enumerate() {
+            let (n, d) = division.to_pair();
+            let extra = match self.extra_counts.is_empty() {
+                true => 0,
+                false => self.extra_counts[index % self.extra_counts.len()] * scale,
+            };
+            let mut needed = n * (denominator / d) + extra;
+            if needed <= 0 {
+                return Err(Error::NonPositiveDivision(index, *division));
+            }
+
+            let mut counts = vec![];
+            if self
+                .rest_mask
+                .as_ref()
+                .is_some_and(|mask| mask.matches(index))
+            {
+                counts.push(-needed);
+                needed = 0;
+            }
+            while needed > 0 {
+                if carried == 0 {
+                    carried = talea.next().expect("a talea has a nonzero count");
+                }
+                let count = carried.abs().min(needed) * carried.signum();
+                counts.push(count);
+                carried -= count;
+                needed -= count.abs();
+            }
+            self.burnish(&mut counts);
+            division_counts.push(counts);
+        }
+
+        Ok((denominator, division_counts))
+    }
+
+    fn burnish(&self, counts: &mut [i32]) {
+        let right_start = counts.len().saturating_sub(self.right_burnish.len());
+        let left = self.left_burnish.iter().enumerate();
+        let right = (right_start..).zip(self.right_burnish.iter());
+        for (index, class) in left.chain(right) {
+            if let Some(count) = counts.get_mut(index) {
+                *count = match class {
+                    LeafClass::Note => count.abs(),
+                    LeafClass::Rest => -count.abs(),
+                };
+            }
+        }
+    }
+}
+
+impl RhythmMaker for TaleaRhythmMaker {
+    fn make(&self, divisions: &[Duration]) -> Result<Vec<ScoreObject>, Error> {
+        let (denominator, division_counts) = self.division_counts(divisions)?;
+
+        let mut components = vec![];
+        for (division, counts) in divisions.iter().zip(division_counts) {
+            let mut contents = vec![];
+            for count in counts.iter() {
+                let written_duration = Duration::new(count.abs(), denominator);
+                let leaf = match *count > 0 {
+                    true => ScoreObject::Note {
+                        written_pitch: self.pitch,
+                        written_duration,
+                        is_tied: false,
+                    },
+                    false => ScoreObject::Rest { written_duration },
+                };
+                contents.extend(leaf.split_leaf(&written_duration.to_printable_parts()?));
+            }
+
+            let prolated: i32 = counts.iter().map(|count| count.abs()).sum();
+            let (n, d) = (*division / Duration::new(prolated, denominator)).to_pair();
+            components.push(match n == d {
+                true => container(contents)?,
+                false => tuplet(Multiplier::new(n, d), contents)?,
+            });
+        }
+
+        if self.tie_across_divisions {
+            for index in 1..components.len() {
+                let next_is_pitched = matches!(
+                    components[index][0],
+                    ScoreObject::Note { .. } | ScoreObject::Chord { .. }
+                );
+                if let Some(last) = components[index - 1]
+                    .contents_mut()
+                    .and_then(|contents| contents.last_mut())
+                {
+                    if next_is_pitched {
+                        last.set_is_tied(true);
+                    }
+                }
+            }
+        }
+
+        Ok(components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Talea, TaleaRhythmMaker};
+    use crate::duration::Duration;
+    use crate::error::Error;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::rhythm_maker::{LeafClass, Pattern, RhythmMaker};
+    use crate::score_object::ScoreObject;
+
+    fn maker(counts: Vec<i32>) -> TaleaRhythmMaker {
+        TaleaRhythmMaker::new(
+            Talea::new(counts, 16).unwrap(),
+            Pitch::new(PitchClass::new(C, Natural), 4),
+        )
+    }
+
+    fn leaf_durations(component: &ScoreObject) -> Vec<(i32, i32)> {
+        component
+            .contents()
+            .unwrap()
+            .iter()
+            .map(|leaf| match leaf {
+                ScoreObject::Rest { written_duration } => {
+                    let (n, d) = written_duration.to_pair();
+                    (-n, d)
+                }
+                _ => leaf.written_duration().unwrap().to_pair(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn invalid_taleas() {
+        assert!(Talea::new(vec![1, 2], 0).is_err());
+        assert!(Talea::new(vec![1, 2], -8).is_err());
+        assert!(Talea::new(vec![], 8).is_err());
+        assert!(Talea::new(vec![0, 0], 8).is_err());
+    }
+
+    #[test]
+    fn non_positive_divisions() {
+        let made = maker(vec![1])
+            .with_extra_counts(vec![-4])
+            .make(&[Duration::new(1, 4)]);
+        assert!(matches!(made, Err(Error::NonPositiveDivision(0, _))));
+        assert!(maker(vec![1]).make(&[Duration::new(-1, 4)]).is_err());
+    }
+
+    #[test]
+    fn talea_carries_across_divisions() {
+        let divisions = [Duration::new(3, 8), Duration::new(3, 8)];
+        let made = maker(vec![1, 2, 3]).make(&divisions).unwrap();
+
+        assert_eq!(made.len(), 2);
+        assert_eq!(leaf_durations(&made[0]), vec![(1, 16), (1, 8), (3, 16)]);
+        assert_eq!(leaf_durations(&made[1]), vec![(1, 16), (1, 8), (3, 16)]);
+        assert!(made.iter().all(|c| c.duration() == Duration::new(3, 8)));
+    }
+
+    #[test]
+    fn talea_splits_counts_at_division_boundaries() {
+        let divisions = [Duration::new(1, 4), Duration::new(1, 4)];
+        let made = maker(vec![3, -2]).make(&divisions).unwrap();
+
+        assert_eq!(leaf_durations(&made[0]), vec![(3, 16), (-1, 16)]);
+        assert_eq!(leaf_durations(&made[1]), vec![(-1, 16), (3, 16)]);
+    }
+
+    #[test]
+    fn extra_counts_make_tuplets() {
+        let divisions = [Duration::new(1, 4), Duration::new(1, 4)];
+        let made = maker(vec![1])
+            .with_extra_counts(vec![1, 0])
+            .make(&divisions)
+            .unwrap();
+
+        match &made[0] {
+            ScoreObject::Tuplet { multiplier, .. } => {
+                assert_eq!(*multiplier, Multiplier::new(4, 5))
+            }
+            other => panic!("expected a tuplet, found {other:?}"),
+        }
+        assert_eq!(made[0].contents().unwrap().len(), 5);
+        assert!(matches!(made[1], ScoreObject::Container { .. }));
+        assert_eq!(made[0].duration(), Duration::new(1, 4));
+    }
+
+    #[test]
+    fn rest_mask_and_burnish() {
+        let divisions = [Duration::new(1, 4); 3];
+        let made = maker(vec![1])
+            .with_rest_mask(Pattern::new(vec![1], None))
+            .with_burnish(vec![LeafClass::Rest], vec![])
+            .make(&divisions)
+            .unwrap();
+
+        assert_eq!(
+            leaf_durations(&made[0]),
+            vec![(-1, 16), (1, 16), (1, 16), (1, 16)]
+        );
+        assert_eq!(leaf_durations(&made[1]), vec![(-1, 4)]);
+    }
+
+    #[test]
+    fn long_counts_are_tied() {
+        let made = maker(vec![5]).make(&[Duration::new(5, 16)]).unwrap();
+
+        assert_eq!(leaf_durations(&made[0]), vec![(1, 4), (1, 16)]);
+        assert!(made[0][0].is_tied());
+        assert!(!made[0][1].is_tied());
+    }
+
+    #[test]
+    fn tie_across_divisions() {
+        let divisions = [Duration::new(1, 8), Duration::new(1, 8)];
+        let made = maker(vec![4])
+            .with_tie_across_divisions(true)
+            .make(&divisions)
+            .unwrap();
+
+        assert!(made[0][0].is_tied());
+        assert!(!made[1][0].is_tied());
+    }
+}
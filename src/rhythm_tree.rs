@@ -0,0 +1,223 @@
+use std::str::FromStr;
+
+use crate::{
+    duration::Duration,
+    error::Error,
+    multiplier::Multiplier,
+    pitch::Pitch,
+    score_object::{container, tuplet, ScoreObject},
+};
+
+/// A proportional rhythm in RTM notation. Leaves are weighted notes (or rests,
+/// when negative); containers divide their share of time among their children
+/// in proportion to the children's weights.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RhythmTree {
+    Leaf(i32),
+    Container(i32, Vec<RhythmTree>),
+}
+
+impl RhythmTree {
+    pub fn weight(&self) -> i32 {
+        match self {
+            Self::Leaf(weight) => weight.abs(),
+            Self::Container(weight, _) => *weight,
+        }
+    }
+
+    /// Realises the tree over `duration`, writing notes at `pitch`. Every
+    /// container becomes a `Container`, or a `Tuplet` whose multiplier lets its
+    /// leaves be written with printable durations. `duration` must be
+    /// positive.
+    pub fn to_score_object(&self, duration: Duration, pitch: Pitch) -> Result<ScoreObject, Error> {
+        if duration <= Duration::new(0, 1) {
+            return Err(Error::NonPositiveDuration(duration));
+        }
+        match self {
+            Self::Leaf(_) => {
+                Self::Container(1, vec![self.clone()]).to_score_object(duration, pitch)
+            }
+            Self::Container(_, children) => {
+                let total_weight: i32 = children.iter().map(|child| child.weight()).sum();
+                if total_weight <= 0 {
+                    return Err(parse_error("containers must have positive total weight"));
+                }
+                let written_unit = (duration / total_weight).equal_or_greater_power_of_two();
+                let (n, d) = (duration / (written_unit * total_weight)).to_pair();
+
+                let mut contents = vec![];
+                for child in children {
+                    let written_duration = written_unit * child.weight();
+                    match child {
+                        Self::Leaf(weight) => {
+                            let leaf = match *weight > 0 {
+                                true => ScoreObject::Note {
+                                    written_pitch: pitch,
+                                    written_duration,
+                                    is_tied: false,
+                                },
+                                false => ScoreObject::Rest { written_duration },
+                            };
+                            contents
+                                .extend(leaf.split_leaf(&written_duration.to_printable_parts()?));
+                        }
+                        Self::Container(..) => {
+                            contents.push(child.to_score_object(written_duration, pitch)?)
+                        }
+                    }
+                }
+
+                match n == d {
+                    true => container(contents),
+                    false => tuplet(Multiplier::new(n, d), contents),
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for RhythmTree {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spaced = s.replace('(', " ( ").replace(')', " ) ");
+        let mut tokens = spaced.split_whitespace().peekable();
+        let tree = parse_node(&mut tokens)?;
+        match tokens.next() {
+            None => Ok(tree),
+            Some(token) => Err(parse_error(format!("unexpected trailing token `{token}`"))),
+        }
+    }
+}
+
+fn parse_node<'a, I>(tokens: &mut std::iter::Peekable<I>) -> Result<RhythmTree, Error>
+where
+    I: Iterator<Item = &'a str>,
+{
+    match tokens.next() {
+        Some("(") => {
+            let weight = parse_weight(tokens.next())?;
+            if weight < 0 {
+                return Err(parse_error(format!(
+                    "container weight {weight} must be positive"
+                )));
+            }
+            if tokens.next() != Some("(") {
+                return Err(parse_error("expected `(` before container children"));
+            }
+
+            let mut children = vec![];
+            while tokens.peek().is_some_and(|token| *token != ")") {
+                children.push(parse_node(tokens)?);
+            }
+            if children.is_empty() {
+                return Err(parse_error("containers must have at least one child"));
+            }
+
+            for _ in 0..2 {
+                if tokens.next() != Some(")") {
+                    return Err(parse_error("unbalanced parentheses"));
+                }
+            }
+            Ok(RhythmTree::Container(weight, children))
+        }
+        token => Ok(RhythmTree::Leaf(parse_weight(token)?)),
+    }
+}
+
+fn parse_weight(token: Option<&str>) -> Result<i32, Error> {
+    match token.map(|token| token.parse::<i32>()) {
+        Some(Ok(0)) => Err(parse_error("weights must be nonzero")),
+        Some(Ok(weight)) => Ok(weight),
+        Some(Err(_)) => Err(parse_error(format!(
+            "expected a weight, found `{}`",
+            token.unwrap()
+        ))),
+        None => Err(parse_error("unexpected end of input")),
+    }
+}
+
+fn parse_error(message: impl Into<String>) -> Error {
+    Error::RhythmTreeParse(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RhythmTree::{self, *};
+    use crate::duration::Duration;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::ScoreObject;
+
+    fn c4() -> Pitch {
+        Pitch::new(PitchClass::new(C, Natural), 4)
+    }
+
+    #[test]
+    fn parse() {
+        let tree: RhythmTree = "(1 (1 (2 (1 1 1)) -1))".parse().unwrap();
+        assert_eq!(
+            tree,
+            Container(
+                1,
+                vec![
+                    Leaf(1),
+                    Container(2, vec![Leaf(1), Leaf(1), Leaf(1)]),
+                    Leaf(-1)
+                ]
+            )
+        );
+        assert_eq!("3".parse::<RhythmTree>().unwrap(), Leaf(3));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!("(1 (1 1)".parse::<RhythmTree>().is_err());
+        assert!("(1 (1 0))".parse::<RhythmTree>().is_err());
+        assert!("(1 ())".parse::<RhythmTree>().is_err());
+        assert!("(1 (1 x))".parse::<RhythmTree>().is_err());
+        assert!("(1 (1 1)) 1".parse::<RhythmTree>().is_err());
+    }
+
+    #[test]
+    fn to_score_object() {
+        let tree: RhythmTree = "(1 (1 (2 (1 1 1)) -1))".parse().unwrap();
+        let c = tree.to_score_object(Duration::new(1, 1), c4()).unwrap();
+
+        assert!(matches!(c, ScoreObject::Container { .. }));
+        assert_eq!(c.duration(), Duration::new(1, 1));
+        assert_eq!(c[0].written_duration(), Some(Duration::new(1, 4)));
+        assert!(matches!(c[2], ScoreObject::Rest { .. }));
+
+        match &c[1] {
+            ScoreObject::Tuplet { multiplier, .. } => {
+                assert_eq!(*multiplier, Multiplier::new(2, 3))
+            }
+            other => panic!("expected a tuplet, found {other:?}"),
+        }
+        assert_eq!(c[1][0].written_duration(), Some(Duration::new(1, 4)));
+    }
+
+    #[test]
+    fn to_score_object_rejects_non_positive_durations() {
+        let tree: RhythmTree = "(1 (1 1))".parse().unwrap();
+        assert!(tree.to_score_object(Duration::new(0, 1), c4()).is_err());
+        assert!(tree.to_score_object(Duration::new(-1, 4), c4()).is_err());
+        assert!(Container(1, vec![])
+            .to_score_object(Duration::new(1, 4), c4())
+            .is_err());
+    }
+
+    #[test]
+    fn to_score_object_ties_unprintable_weights() {
+        let tree: RhythmTree = "(1 (5 3))".parse().unwrap();
+        let c = tree.to_score_object(Duration::new(1, 2), c4()).unwrap();
+
+        assert_eq!(c.contents().unwrap().len(), 3);
+        assert!(c[0].is_tied());
+        assert_eq!(c[0].written_duration(), Some(Duration::new(1, 4)));
+        assert_eq!(c[1].written_duration(), Some(Duration::new(1, 16)));
+        assert_eq!(c[2].written_duration(), Some(Duration::new(3, 16)));
+        assert_eq!(c.duration(), Duration::new(1, 2));
+    }
+}
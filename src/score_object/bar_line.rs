@@ -0,0 +1,10 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BarLine {
+    Single,
+    Double,
+    Final,
+    StartRepeat,
+    EndRepeat,
+    DoubleRepeat,
+}
@@ -0,0 +1,298 @@
+use super::{
+    score, staff, staff_group, voice, ScoreObject, StaffContext, StaffGroupContext, VoiceContext,
+};
+use crate::{error::Error, instrument::Instrument};
+
+/// Assembles a simultaneous `Score` of named staves and staff groups.
+///
+/// ```
+/// # use copland::duration::Duration;
+/// # use copland::score_object::{rest, ScoreBuilder};
+/// let score = ScoreBuilder::new()
+///     .name("Quartet")
+///     .staff("Violin", |s| {
+///         s.voice("Violin Voice", |v| v.try_push(rest(Duration::new(1, 1))))
+///     })
+///     .build();
+/// assert!(score.is_ok());
+/// ```
+#[derive(Debug, Default)]
+pub struct ScoreBuilder {
+    name: Option<String>,
+    contents: Vec<Result<ScoreObject, Error>>,
+}
+
+impl ScoreBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn staff_group<F>(mut self, name: impl Into<String>, f: F) -> Self
+    where
+        F: FnOnce(StaffGroupBuilder) -> StaffGroupBuilder,
+    {
+        self.contents.push(f(StaffGroupBuilder::new(name)).build());
+        self
+    }
+
+    pub fn staff<F>(mut self, name: impl Into<String>, f: F) -> Self
+    where
+        F: FnOnce(StaffBuilder) -> StaffBuilder,
+    {
+        self.contents.push(f(StaffBuilder::new(name)).build());
+        self
+    }
+
+    pub fn build(self) -> Result<ScoreObject, Error> {
+        let mut score = score(self.contents.into_iter().collect::<Result<_, _>>()?)?;
+        score.set_name(self.name);
+        score.set_is_simultaneous(true);
+        Ok(score)
+    }
+}
+
+#[derive(Debug)]
+pub struct StaffGroupBuilder {
+    name: String,
+    context: StaffGroupContext,
+    contents: Vec<Result<ScoreObject, Error>>,
+}
+
+impl StaffGroupBuilder {
+    fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            context: StaffGroupContext::StaffGroup,
+            contents: vec![],
+        }
+    }
+
+    pub fn context(mut self, context: StaffGroupContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    pub fn staff_group<F>(mut self, name: impl Into<String>, f: F) -> Self
+    where
+        F: FnOnce(StaffGroupBuilder) -> StaffGroupBuilder,
+    {
+        self.contents.push(f(StaffGroupBuilder::new(name)).build());
+        self
+    }
+
+    pub fn staff<F>(mut self, name: impl Into<String>, f: F) -> Self
+    where
+        F: FnOnce(StaffBuilder) -> StaffBuilder,
+    {
+        self.contents.push(f(StaffBuilder::new(name)).build());
+        self
+    }
+
+    pub fn build(self) -> Result<ScoreObject, Error> {
+        let mut group = staff_group(self.contents.into_iter().collect::<Result<_, _>>()?)?;
+        group.set_name(Some(self.name));
+        group.set_staff_group_context(self.context);
+        group.set_is_simultaneous(true);
+        Ok(group)
+    }
+}
+
+/// Builds a staff from named voices, or from voice-level content pushed
+/// directly. A staff holding several voices is simultaneous.
+#[derive(Debug)]
+pub struct StaffBuilder {
+    name: String,
+    context: StaffContext,
+    instrument: Option<Instrument>,
+    contents: Vec<Result<ScoreObject, Error>>,
+}
+
+impl StaffBuilder {
+    fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            context: StaffContext::Staff,
+            instrument: None,
+            contents: vec![],
+        }
+    }
+
+    pub fn context(mut self, context: StaffContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Attaches an instrument and adopts its staff context.
+    pub fn instrument(mut self, instrument: Instrument) -> Self {
+        self.context = instrument.staff_context().clone();
+        self.instrument = Some(instrument);
+        self
+    }
+
+    pub fn voice<F>(mut self, name: impl Into<String>, f: F) -> Self
+    where
+        F: FnOnce(VoiceBuilder) -> VoiceBuilder,
+    {
+        self.contents.push(f(VoiceBuilder::new(name)).build());
+        self
+    }
+
+    pub fn push(self, component: ScoreObject) -> Self {
+        self.try_push(Ok(component))
+    }
+
+    pub fn try_push(mut self, component: Result<ScoreObject, Error>) -> Self {
+        self.contents.push(component);
+        self
+    }
+
+    pub fn build(self) -> Result<ScoreObject, Error> {
+        let contents: Vec<ScoreObject> = self.contents.into_iter().collect::<Result<_, _>>()?;
+        let voice_count = contents
+            .iter()
+            .filter(|c| matches!(c, ScoreObject::Voice { .. }))
+            .count();
+
+        let mut staff = staff(contents)?;
+        staff.set_name(Some(self.name));
+        staff.set_staff_context(self.context);
+        staff.set_instrument(self.instrument);
+        staff.set_is_simultaneous(voice_count > 1);
+        Ok(staff)
+    }
+}
+
+#[derive(Debug)]
+pub struct VoiceBuilder {
+    name: String,
+    context: VoiceContext,
+    instrument: Option<Instrument>,
+    contents: Vec<Result<ScoreObject, Error>>,
+}
+
+impl VoiceBuilder {
+    fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            context: VoiceContext::Voice,
+            instrument: None,
+            contents: vec![],
+        }
+    }
+
+    pub fn context(mut self, context: VoiceContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    pub fn instrument(mut self, instrument: Instrument) -> Self {
+        self.instrument = Some(instrument);
+        self
+    }
+
+    pub fn push(self, component: ScoreObject) -> Self {
+        self.try_push(Ok(component))
+    }
+
+    pub fn try_push(mut self, component: Result<ScoreObject, Error>) -> Self {
+        self.contents.push(component);
+        self
+    }
+
+    pub fn extend(mut self, components: impl IntoIterator<Item = ScoreObject>) -> Self {
+        self.contents.extend(components.into_iter().map(Ok));
+        self
+    }
+
+    pub fn build(self) -> Result<ScoreObject, Error> {
+        let mut voice = voice(self.contents.into_iter().collect::<Result<_, _>>()?)?;
+        voice.set_name(Some(self.name));
+        voice.set_voice_context(self.context);
+        voice.set_instrument(self.instrument);
+        Ok(voice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScoreBuilder;
+    use crate::duration::Duration;
+    use crate::error::Error;
+    use crate::instrument::Instrument;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{note, rest, score, StaffContext, StaffGroupContext};
+
+    #[test]
+    fn build_score() {
+        let s = ScoreBuilder::new()
+            .name("Score")
+            .staff_group("Piano", |g| {
+                g.context(StaffGroupContext::PianoStaff)
+                    .staff("RH", |s| {
+                        s.voice("RH Voice 1", |v| {
+                            v.try_push(note(
+                                Pitch::new(PitchClass::new(E, Natural), 5),
+                                Duration::new(1, 1),
+                            ))
+                        })
+                        .voice("RH Voice 2", |v| v.try_push(rest(Duration::new(1, 1))))
+                    })
+                    .staff("LH", |s| s.push(rest(Duration::new(1, 1)).unwrap()))
+            })
+            .staff("Percussion", |s| s.context(StaffContext::RhythmicStaff))
+            .build()
+            .unwrap();
+
+        assert_eq!(s.name(), &Some("Score".to_string()));
+        assert!(s.is_simultaneous());
+        assert_eq!(
+            s[0].staff_group_context(),
+            Some(&StaffGroupContext::PianoStaff)
+        );
+        assert!(s[0][0].is_simultaneous());
+        assert!(!s[0][1].is_simultaneous());
+        assert_eq!(s[0][0][1].name(), &Some("RH Voice 2".to_string()));
+        assert_eq!(s[1].staff_context(), Some(&StaffContext::RhythmicStaff));
+    }
+
+    #[test]
+    fn build_propagates_errors() {
+        let s = ScoreBuilder::new()
+            .staff("Violin", |s| {
+                s.voice("Violin Voice", |v| v.try_push(rest(Duration::new(1, 3))))
+            })
+            .build();
+        assert!(matches!(s, Err(Error::UnprintableDuration(_))));
+
+        let s = ScoreBuilder::new()
+            .staff("Violin", |s| s.push(score(vec![]).unwrap()))
+            .build();
+        assert!(matches!(s, Err(Error::IllegalNesting("Score", "Staff"))));
+    }
+
+    #[test]
+    fn build_with_instruments() {
+        let s = ScoreBuilder::new()
+            .staff("Percussion", |s| s.instrument(Instrument::percussion()))
+            .staff("Clarinet", |s| {
+                s.voice("Clarinet Voice", |v| {
+                    v.instrument(Instrument::clarinet_in_b_flat())
+                })
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(s[0].staff_context(), Some(&StaffContext::DrumStaff));
+        assert_eq!(s[0].instrument(), Some(&Instrument::percussion()));
+        assert!(s[1].instrument().is_none());
+        assert_eq!(
+            s[1][0].instrument().map(|i| i.name()),
+            Some("Clarinet in B-flat")
+        );
+    }
+}
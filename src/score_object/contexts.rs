@@ -1,4 +1,5 @@
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StaffGroupContext {
     StaffGroup,
     ChoirStaff,
@@ -6,7 +7,8 @@ pub enum StaffGroupContext {
     PianoStaff,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StaffContext {
     Staff,
     RhythmicStaff,
@@ -16,7 +18,8 @@ pub enum StaffContext {
     MensuralStaff,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VoiceContext {
     Voice,
     VaticanaVoice,
@@ -0,0 +1,26 @@
+use super::ScoreObject;
+
+/// The direction of a feathered beam: beams fan out towards the faster end.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FeatheredBeam {
+    Accelerando,
+    Ritardando,
+}
+
+impl ScoreObject {
+    /// The feathered beam drawn across a container's contents, if any.
+    pub fn feathered_beam(&self) -> Option<FeatheredBeam> {
+        match self {
+            Self::Container { feathered_beam, .. } => *feathered_beam,
+            _ => None,
+        }
+    }
+
+    /// Sets the feathered beam of a container; other objects are unchanged.
+    pub fn set_feathered_beam(&mut self, new_feathered_beam: Option<FeatheredBeam>) {
+        if let Self::Container { feathered_beam, .. } = self {
+            *feathered_beam = new_feathered_beam
+        }
+    }
+}
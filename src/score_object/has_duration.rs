@@ -0,0 +1,108 @@
+use super::ScoreObject;
+use crate::duration::Duration;
+
+impl ScoreObject {
+    pub fn written_duration(&self) -> Option<Duration> {
+        match self {
+            Self::Note {
+                written_duration, ..
+            } => Some(*written_duration),
+            Self::Rest { written_duration } => Some(*written_duration),
+            Self::Spacer { written_duration } => Some(*written_duration),
+            Self::Lyric {
+                written_duration, ..
+            } => Some(*written_duration),
+            Self::Chord {
+                written_duration, ..
+            } => Some(*written_duration),
+            _ => None,
+        }
+    }
+
+    pub fn set_written_duration(&mut self, new_written_duration: Duration) {
+        match self {
+            Self::Note {
+                written_duration, ..
+            } => *written_duration = new_written_duration,
+            Self::Rest { written_duration } => *written_duration = new_written_duration,
+            Self::Spacer { written_duration } => *written_duration = new_written_duration,
+            Self::Lyric {
+                written_duration, ..
+            } => *written_duration = new_written_duration,
+            Self::Chord {
+                written_duration, ..
+            } => *written_duration = new_written_duration,
+            _ => (),
+        }
+    }
+
+    /// The time this object occupies in its parent: the written duration of a
+    /// leaf, or the summed (or, when simultaneous, longest) duration of its
+    /// contents scaled by any tuplet multiplier.
+    pub fn duration(&self) -> Duration {
+        if let Some(written_duration) = self.written_duration() {
+            return written_duration;
+        }
+
+        let contents = self.contents().map(|c| c.as_slice()).unwrap_or(&[]);
+        let contents_duration = if self.is_simultaneous() {
+            contents
+                .iter()
+                .map(|c| c.duration())
+                .max()
+                .unwrap_or(Duration::new(0, 1))
+        } else {
+            contents
+                .iter()
+                .fold(Duration::new(0, 1), |acc, c| acc + c.duration())
+        };
+
+        match self {
+            Self::Tuplet { multiplier, .. } => contents_duration * *multiplier,
+            _ => contents_duration,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::duration::Duration;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{note, rest, staff, tuplet, voice};
+
+    #[test]
+    fn leaf_duration() {
+        let mut r = rest(Duration::new(1, 4)).unwrap();
+        assert_eq!(r.duration(), Duration::new(1, 4));
+
+        r.set_written_duration(Duration::new(1, 2));
+        assert_eq!(r.written_duration(), Some(Duration::new(1, 2)));
+    }
+
+    #[test]
+    fn container_duration() {
+        let notes = (0..3)
+            .map(|_| {
+                note(
+                    Pitch::new(PitchClass::new(C, Natural), 4),
+                    Duration::new(1, 8),
+                )
+                .unwrap()
+            })
+            .collect();
+        let t = tuplet(Multiplier::new(2, 3), notes).unwrap();
+        assert_eq!(t.duration(), Duration::new(1, 4));
+        assert!(t.written_duration().is_none());
+
+        let v = voice(vec![t, rest(Duration::new(3, 4)).unwrap()]).unwrap();
+        assert_eq!(v.duration(), Duration::new(1, 1));
+
+        let short = voice(vec![rest(Duration::new(1, 2)).unwrap()]).unwrap();
+        let mut s = staff(vec![v, short]).unwrap();
+        assert_eq!(s.duration(), Duration::new(3, 2));
+
+        s.set_is_simultaneous(true);
+        assert_eq!(s.duration(), Duration::new(1, 1));
+    }
+}
@@ -0,0 +1,46 @@
+use super::ScoreObject;
+use crate::instrument::Instrument;
+
+impl ScoreObject {
+    pub fn instrument(&self) -> Option<&Instrument> {
+        match self {
+            Self::Voice { instrument, .. } => instrument.as_ref(),
+            Self::Staff { instrument, .. } => instrument.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn set_instrument(&mut self, new_instrument: Option<Instrument>) {
+        match self {
+            Self::Voice { instrument, .. } => *instrument = new_instrument,
+            Self::Staff { instrument, .. } => *instrument = new_instrument,
+            _ => (),
+        }
+    }
+
+    /// True for a voice or staff that `to_concert_pitch` has rewritten at
+    /// sounding pitch.
+    pub fn is_concert_pitch(&self) -> bool {
+        match self {
+            Self::Voice {
+                is_concert_pitch, ..
+            }
+            | Self::Staff {
+                is_concert_pitch, ..
+            } => *is_concert_pitch,
+            _ => false,
+        }
+    }
+
+    pub(crate) fn concert_pitch_mut(&mut self) -> Option<&mut bool> {
+        match self {
+            Self::Voice {
+                is_concert_pitch, ..
+            }
+            | Self::Staff {
+                is_concert_pitch, ..
+            } => Some(is_concert_pitch),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use super::ScoreObject;
+
+impl ScoreObject {
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Self::Lyric { text, .. } => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn set_text(&mut self, new_text: impl Into<String>) {
+        if let Self::Lyric { text, .. } = self {
+            *text = new_text.into()
+        }
+    }
+}
@@ -7,6 +7,7 @@ impl ScoreObject {
         match self {
             Self::Tuplet { contents, .. } => Some(contents),
             Self::Container { contents, .. } => Some(contents),
+            Self::Measure { contents, .. } => Some(contents),
             Self::Voice { contents, .. } => Some(contents),
             Self::Staff { contents, .. } => Some(contents),
             Self::StaffGroup { contents, .. } => Some(contents),
@@ -23,6 +24,9 @@ impl ScoreObject {
             Self::Container {
                 ref mut contents, ..
             } => Some(contents),
+            Self::Measure {
+                ref mut contents, ..
+            } => Some(contents),
             Self::Voice {
                 ref mut contents, ..
             } => Some(contents),
@@ -38,6 +42,14 @@ impl ScoreObject {
             _ => None,
         }
     }
+
+    /// Every leaf in this object, in depth-first order.
+    pub fn leaves(&self) -> Vec<&ScoreObject> {
+        match self.contents() {
+            Some(contents) => contents.iter().flat_map(|c| c.leaves()).collect(),
+            None => vec![self],
+        }
+    }
 }
 
 impl Index<usize> for ScoreObject {
@@ -94,6 +106,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn leaves() {
+        let t = tuplet_fixture();
+        assert_eq!(t.leaves().len(), 3);
+        assert_eq!(t[0].leaves(), vec![&t[0]]);
+    }
+
     #[test]
     #[should_panic]
     fn indexed_out_of_bounds() {
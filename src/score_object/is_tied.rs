@@ -0,0 +1,87 @@
+use super::ScoreObject;
+use crate::duration::Duration;
+
+impl ScoreObject {
+    pub fn is_tied(&self) -> bool {
+        match self {
+            Self::Note { is_tied, .. } => *is_tied,
+            Self::Chord { is_tied, .. } => *is_tied,
+            _ => false,
+        }
+    }
+
+    pub fn set_is_tied(&mut self, new_is_tied: bool) {
+        match self {
+            Self::Note { is_tied, .. } => *is_tied = new_is_tied,
+            Self::Chord { is_tied, .. } => *is_tied = new_is_tied,
+            _ => (),
+        }
+    }
+
+    /// Copies a leaf once per written duration, tying each copy to the next.
+    /// The final copy keeps the original leaf's tie.
+    pub fn split_leaf(&self, written_durations: &[Duration]) -> Vec<ScoreObject> {
+        if !self.is_leaf() {
+            return vec![self.clone()];
+        }
+
+        let mut leaves: Vec<ScoreObject> = written_durations
+            .iter()
+            .map(|written_duration| {
+                let mut leaf = self.clone();
+                leaf.set_written_duration(*written_duration);
+                leaf.set_is_tied(true);
+                leaf
+            })
+            .collect();
+        if let Some(last) = leaves.last_mut() {
+            last.set_is_tied(self.is_tied());
+        }
+        leaves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::duration::Duration;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{note, rest};
+
+    #[test]
+    fn set_tied() {
+        let mut n = note(
+            Pitch::new(PitchClass::new(C, Natural), 4),
+            Duration::new(1, 4),
+        )
+        .unwrap();
+        assert!(!n.is_tied());
+
+        n.set_is_tied(true);
+        assert!(n.is_tied());
+
+        let mut r = rest(Duration::new(1, 4)).unwrap();
+        r.set_is_tied(true);
+        assert!(!r.is_tied());
+    }
+
+    #[test]
+    fn split_leaf() {
+        let n = note(
+            Pitch::new(PitchClass::new(C, Natural), 4),
+            Duration::new(5, 8),
+        );
+        assert!(n.is_err());
+
+        let n = note(
+            Pitch::new(PitchClass::new(C, Natural), 4),
+            Duration::new(1, 2),
+        )
+        .unwrap();
+        let leaves = n.split_leaf(&[Duration::new(3, 8), Duration::new(1, 8)]);
+
+        assert_eq!(leaves.len(), 2);
+        assert!(leaves[0].is_tied());
+        assert!(!leaves[1].is_tied());
+        assert_eq!(leaves[1].written_duration(), Some(Duration::new(1, 8)));
+    }
+}
@@ -0,0 +1,217 @@
+use super::{measure, BarLine, ScoreObject};
+use crate::{duration::Duration, error::Error, time_signature::TimeSignature};
+
+impl ScoreObject {
+    pub fn time_signature(&self) -> Option<&TimeSignature> {
+        match self {
+            Self::Measure { time_signature, .. } => Some(time_signature),
+            _ => None,
+        }
+    }
+
+    pub fn bar_line(&self) -> Option<&BarLine> {
+        match self {
+            Self::Measure { bar_line, .. } => Some(bar_line),
+            _ => None,
+        }
+    }
+
+    pub fn set_bar_line(&mut self, new_bar_line: BarLine) {
+        if let Self::Measure { bar_line, .. } = self {
+            *bar_line = new_bar_line
+        }
+    }
+
+    /// Every measure in this object, in depth-first order.
+    pub fn measures(&self) -> Vec<&ScoreObject> {
+        let mut measures = vec![];
+        if let Self::Measure { .. } = self {
+            measures.push(self);
+        }
+        if let Some(contents) = self.contents() {
+            for component in contents.iter() {
+                measures.extend(component.measures());
+            }
+        }
+        measures
+    }
+}
+
+/// Groups `contents` into measures using `time_signatures` in order, repeating
+/// the last time signature once the list is exhausted. Leaves that cross a
+/// barline are split into tied, printable pieces; tuplets and containers must
+/// fit inside a single measure.
+///
+/// Every measure, including the last, must be filled exactly: an incomplete
+/// final measure is a `MeasureDurationMismatch`. A pickup is written by
+/// starting `time_signatures` with one as long as the anacrusis, e.g. `1/4`
+/// followed by `3/4`.
+pub fn partition_into_measures(
+    contents: Vec<ScoreObject>,
+    time_signatures: &[TimeSignature],
+) -> Result<Vec<ScoreObject>, Error> {
+    let Some(last_time_signature) = time_signatures.last() else {
+        return Ok(contents);
+    };
+    let mut time_signatures = time_signatures
+        .iter()
+        .chain(std::iter::repeat(last_time_signature));
+
+    let zero = Duration::new(0, 1);
+    let mut measures = vec![];
+    let mut time_signature = *time_signatures.next().unwrap();
+    let mut capacity = time_signature.duration();
+    let mut current = vec![];
+    let mut offset = zero;
+
+    for component in contents {
+        let duration = component.duration();
+        if duration <= capacity {
+            current.push(component);
+            capacity = capacity - duration;
+            offset = offset + duration;
+        } else if component.is_leaf() {
+            let mut remaining = duration;
+            while remaining > zero {
+                let chunk = remaining.min(capacity);
+                remaining = remaining - chunk;
+
+                let mut leaves = component.split_leaf(&chunk.to_printable_parts()?);
+                if remaining > zero {
+                    if let Some(last) = leaves.last_mut() {
+                        last.set_is_tied(true);
+                    }
+                }
+                current.append(&mut leaves);
+                capacity = capacity - chunk;
+                offset = offset + chunk;
+
+                if capacity == zero && remaining > zero {
+                    measures.push(measure(time_signature, std::mem::take(&mut current))?);
+                    time_signature = *time_signatures.next().unwrap();
+                    capacity = time_signature.duration();
+                }
+            }
+        } else {
+            return Err(Error::UnsplittableComponent(offset));
+        }
+
+        if capacity == zero {
+            measures.push(measure(time_signature, std::mem::take(&mut current))?);
+            time_signature = *time_signatures.next().unwrap();
+            capacity = time_signature.duration();
+        }
+    }
+
+    if !current.is_empty() {
+        measures.push(measure(time_signature, current)?);
+    }
+
+    Ok(measures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::partition_into_measures;
+    use crate::duration::Duration;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{measure, note, rest, tuplet, voice, BarLine, ScoreObject};
+    use crate::time_signature::TimeSignature;
+
+    fn c4(n: i32, d: i32) -> ScoreObject {
+        note(
+            Pitch::new(PitchClass::new(C, Natural), 4),
+            Duration::new(n, d),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn new_measure() {
+        let m = measure(TimeSignature::new(3, 4), vec![c4(1, 2), c4(1, 4)]).unwrap();
+        assert_eq!(m.time_signature(), Some(&TimeSignature::new(3, 4)));
+        assert_eq!(m.bar_line(), Some(&BarLine::Single));
+
+        let m = measure(TimeSignature::new(3, 4), vec![c4(1, 2)]);
+        assert!(m.is_err());
+    }
+
+    #[test]
+    fn set_bar_line() {
+        let mut m = measure(TimeSignature::new(2, 4), vec![c4(1, 2)]).unwrap();
+        m.set_bar_line(BarLine::Final);
+        assert_eq!(m.bar_line(), Some(&BarLine::Final));
+    }
+
+    #[test]
+    fn measures() {
+        let m1 = measure(TimeSignature::new(2, 4), vec![c4(1, 2)]).unwrap();
+        let m2 = measure(TimeSignature::new(2, 4), vec![c4(1, 4), c4(1, 4)]).unwrap();
+        let v = voice(vec![m1, m2]).unwrap();
+
+        assert_eq!(v.measures().len(), 2);
+        assert_eq!(v.measures()[1][0], c4(1, 4));
+    }
+
+    #[test]
+    fn partition_splits_leaves_across_barlines() {
+        let contents = vec![c4(1, 2), c4(1, 2), rest(Duration::new(1, 2)).unwrap()];
+        let measures = partition_into_measures(contents, &[TimeSignature::new(3, 4)]).unwrap();
+
+        assert_eq!(measures.len(), 2);
+        assert_eq!(measures[0].contents().unwrap().len(), 2);
+        assert!(measures[0][1].is_tied());
+        assert_eq!(measures[0][1].written_duration(), Some(Duration::new(1, 4)));
+        assert_eq!(measures[1][0].written_duration(), Some(Duration::new(1, 4)));
+        assert!(!measures[1][0].is_tied());
+        assert_eq!(measures[1][1], rest(Duration::new(1, 2)).unwrap());
+    }
+
+    #[test]
+    fn partition_splits_into_printable_pieces() {
+        let contents = vec![c4(1, 8), c4(1, 1), c4(3, 8)];
+        let measures = partition_into_measures(contents, &[TimeSignature::new(3, 4)]).unwrap();
+
+        assert_eq!(measures.len(), 2);
+        let first: Vec<Option<Duration>> = measures[0]
+            .contents()
+            .unwrap()
+            .iter()
+            .map(|leaf| leaf.written_duration())
+            .collect();
+        assert_eq!(
+            first,
+            vec![
+                Some(Duration::new(1, 8)),
+                Some(Duration::new(1, 2)),
+                Some(Duration::new(1, 8))
+            ]
+        );
+        assert!(measures[0][1].is_tied());
+        assert!(measures[0][2].is_tied());
+        assert_eq!(measures[1][0].written_duration(), Some(Duration::new(3, 8)));
+    }
+
+    #[test]
+    fn partition_requires_a_full_final_measure() {
+        let contents = vec![c4(1, 4), c4(3, 4), c4(3, 4)];
+        assert!(partition_into_measures(contents.clone(), &[TimeSignature::new(3, 4)]).is_err());
+
+        let pickup = [TimeSignature::new(1, 4), TimeSignature::new(3, 4)];
+        let measures = partition_into_measures(contents, &pickup).unwrap();
+        assert_eq!(measures.len(), 3);
+        assert_eq!(
+            measures[0].time_signature(),
+            Some(&TimeSignature::new(1, 4))
+        );
+    }
+
+    #[test]
+    fn partition_rejects_tuplets_across_barlines() {
+        let t = tuplet(Multiplier::new(2, 3), vec![c4(1, 4), c4(1, 4), c4(1, 4)]).unwrap();
+        let contents = vec![c4(1, 4), t];
+
+        assert!(partition_into_measures(contents, &[TimeSignature::new(2, 4)]).is_err());
+    }
+}
@@ -1,18 +1,47 @@
-use crate::{duration::Duration, error::Error, multiplier::Multiplier, pitch::Pitch};
+use crate::{
+    duration::Duration, error::Error, instrument::Instrument, multiplier::Multiplier, pitch::Pitch,
+    time_signature::TimeSignature,
+};
+
+mod bar_line;
+pub use bar_line::BarLine;
+
+mod builder;
+pub use builder::{ScoreBuilder, StaffBuilder, StaffGroupBuilder, VoiceBuilder};
 
 mod contexts;
 pub use contexts::{StaffContext, StaffGroupContext, VoiceContext};
 
+mod feathered_beam;
+pub use feathered_beam::FeatheredBeam;
+
 mod has_context;
+mod has_duration;
+mod has_instrument;
+mod has_text;
 mod indexed;
 mod is_simultaneous;
+mod is_tied;
+mod measured;
+pub use measured::partition_into_measures;
 mod named;
+mod nesting;
+mod prolated;
+mod sounding;
+mod timespans;
+pub use timespans::{Timespan, VerticalMoment};
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
 pub enum ScoreObject {
     Note {
         written_pitch: Pitch,
         written_duration: Duration,
+        is_tied: bool,
     },
     Rest {
         written_duration: Duration,
@@ -20,29 +49,57 @@ pub enum ScoreObject {
     Spacer {
         written_duration: Duration,
     },
+    Lyric {
+        text: String,
+        written_duration: Duration,
+    },
     Chord {
         written_pitches: Box<Vec<Pitch>>,
         written_duration: Duration,
+        is_tied: bool,
     },
     Tuplet {
         multiplier: Multiplier,
+        display_ratio: Option<(i32, i32)>,
         contents: Box<Vec<ScoreObject>>,
     },
     Container {
         contents: Box<Vec<ScoreObject>>,
         is_simultaneous: bool,
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        feathered_beam: Option<FeatheredBeam>,
+    },
+    Measure {
+        time_signature: TimeSignature,
+        bar_line: BarLine,
+        contents: Box<Vec<ScoreObject>>,
     },
     Voice {
         contents: Box<Vec<ScoreObject>>,
         is_simultaneous: bool,
         name: Option<String>,
         context: VoiceContext,
+        instrument: Option<Instrument>,
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "std::ops::Not::not")
+        )]
+        is_concert_pitch: bool,
     },
     Staff {
         contents: Box<Vec<ScoreObject>>,
         is_simultaneous: bool,
         name: Option<String>,
         context: StaffContext,
+        instrument: Option<Instrument>,
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "std::ops::Not::not")
+        )]
+        is_concert_pitch: bool,
     },
     StaffGroup {
         contents: Box<Vec<ScoreObject>>,
@@ -61,7 +118,11 @@ impl ScoreObject {
     pub fn is_leaf(&self) -> bool {
         matches!(
             self,
-            Self::Note { .. } | Self::Chord { .. } | Self::Rest { .. } | Self::Spacer { .. }
+            Self::Note { .. }
+                | Self::Chord { .. }
+                | Self::Rest { .. }
+                | Self::Spacer { .. }
+                | Self::Lyric { .. }
         )
     }
 
@@ -75,6 +136,7 @@ pub fn note(written_pitch: Pitch, written_duration: Duration) -> Result<ScoreObj
         Ok(ScoreObject::Note {
             written_pitch,
             written_duration,
+            is_tied: false,
         })
     } else {
         Err(Error::UnprintableDuration(written_duration))
@@ -89,12 +151,25 @@ pub fn rest(written_duration: Duration) -> Result<ScoreObject, Error> {
     }
 }
 
+/// A syllable or text label, as used in `Lyrics` and `FiguredBass` voices.
+pub fn lyric(text: impl Into<String>, written_duration: Duration) -> Result<ScoreObject, Error> {
+    if written_duration.is_printable() {
+        Ok(ScoreObject::Lyric {
+            text: text.into(),
+            written_duration,
+        })
+    } else {
+        Err(Error::UnprintableDuration(written_duration))
+    }
+}
+
 pub fn chord(pitches: Vec<Pitch>, written_duration: Duration) -> Result<ScoreObject, Error> {
     if written_duration.is_printable() {
         let written_pitches = Box::new(pitches);
         Ok(ScoreObject::Chord {
             written_pitches,
             written_duration,
+            is_tied: false,
         })
     } else {
         Err(Error::UnprintableDuration(written_duration))
@@ -103,57 +178,95 @@ pub fn chord(pitches: Vec<Pitch>, written_duration: Duration) -> Result<ScoreObj
 
 pub fn tuplet(multiplier: Multiplier, contents: Vec<ScoreObject>) -> Result<ScoreObject, Error> {
     let contents = Box::new(contents);
-    Ok(ScoreObject::Tuplet {
+    ScoreObject::Tuplet {
         multiplier,
+        display_ratio: None,
         contents,
-    })
+    }
+    .validated()
+}
+
+/// Builds a tuplet of `numerator` notes in the time of `denominator`, so that
+/// `3, 2` gives a multiplier of `2/3`.
+pub fn tuplet_from_ratio(
+    numerator: i32,
+    denominator: i32,
+    contents: Vec<ScoreObject>,
+) -> Result<ScoreObject, Error> {
+    tuplet(Multiplier::new(denominator, numerator), contents)
 }
 
 pub fn container(contents: Vec<ScoreObject>) -> Result<ScoreObject, Error> {
     let contents = Box::new(contents);
-    Ok(ScoreObject::Container {
+    ScoreObject::Container {
         contents,
         is_simultaneous: false,
-    })
+        feathered_beam: None,
+    }
+    .validated()
+}
+
+pub fn measure(
+    time_signature: TimeSignature,
+    contents: Vec<ScoreObject>,
+) -> Result<ScoreObject, Error> {
+    let contents = Box::new(contents);
+    let measure = ScoreObject::Measure {
+        time_signature,
+        bar_line: BarLine::Single,
+        contents,
+    };
+    match measure.duration() {
+        duration if duration == time_signature.duration() => measure.validated(),
+        duration => Err(Error::MeasureDurationMismatch(time_signature, duration)),
+    }
 }
 
 pub fn voice(contents: Vec<ScoreObject>) -> Result<ScoreObject, Error> {
     let contents = Box::new(contents);
-    Ok(ScoreObject::Voice {
+    ScoreObject::Voice {
         contents,
         is_simultaneous: false,
         name: None,
         context: VoiceContext::Voice,
-    })
+        instrument: None,
+        is_concert_pitch: false,
+    }
+    .validated()
 }
 
 pub fn staff(contents: Vec<ScoreObject>) -> Result<ScoreObject, Error> {
     let contents = Box::new(contents);
-    Ok(ScoreObject::Staff {
+    ScoreObject::Staff {
         contents,
         is_simultaneous: false,
         name: None,
         context: StaffContext::Staff,
-    })
+        instrument: None,
+        is_concert_pitch: false,
+    }
+    .validated()
 }
 
 pub fn staff_group(contents: Vec<ScoreObject>) -> Result<ScoreObject, Error> {
     let contents = Box::new(contents);
-    Ok(ScoreObject::StaffGroup {
+    ScoreObject::StaffGroup {
         contents,
         is_simultaneous: false,
         name: None,
         context: StaffGroupContext::StaffGroup,
-    })
+    }
+    .validated()
 }
 
 pub fn score(contents: Vec<ScoreObject>) -> Result<ScoreObject, Error> {
     let contents = Box::new(contents);
-    Ok(ScoreObject::Score {
+    ScoreObject::Score {
         contents,
         is_simultaneous: false,
         name: None,
-    })
+    }
+    .validated()
 }
 
 #[cfg(test)]
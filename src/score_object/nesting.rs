@@ -0,0 +1,97 @@
+use super::ScoreObject;
+use crate::error::Error;
+
+impl ScoreObject {
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Self::Note { .. } => "Note",
+            Self::Rest { .. } => "Rest",
+            Self::Spacer { .. } => "Spacer",
+            Self::Lyric { .. } => "Lyric",
+            Self::Chord { .. } => "Chord",
+            Self::Tuplet { .. } => "Tuplet",
+            Self::Container { .. } => "Container",
+            Self::Measure { .. } => "Measure",
+            Self::Voice { .. } => "Voice",
+            Self::Staff { .. } => "Staff",
+            Self::StaffGroup { .. } => "StaffGroup",
+            Self::Score { .. } => "Score",
+        }
+    }
+
+    /// Whether `child` may appear directly inside this object, following
+    /// Score > StaffGroup > Staff > Voice > Measure/Container/Tuplet > leaves.
+    /// Staff groups may nest, and a staff may hold voice-level content
+    /// directly.
+    pub fn can_contain(&self, child: &ScoreObject) -> bool {
+        let is_voice_content = child.is_leaf()
+            || matches!(
+                child,
+                Self::Tuplet { .. } | Self::Container { .. } | Self::Measure { .. }
+            );
+        let is_measure_content =
+            child.is_leaf() || matches!(child, Self::Tuplet { .. } | Self::Container { .. });
+
+        match self {
+            Self::Tuplet { .. } | Self::Container { .. } | Self::Measure { .. } => {
+                is_measure_content
+            }
+            Self::Voice { .. } => is_voice_content,
+            Self::Staff { .. } => is_voice_content || matches!(child, Self::Voice { .. }),
+            Self::StaffGroup { .. } | Self::Score { .. } => {
+                matches!(child, Self::Staff { .. } | Self::StaffGroup { .. })
+            }
+            _ => false,
+        }
+    }
+
+    pub(crate) fn validated(self) -> Result<Self, Error> {
+        if let Some(contents) = self.contents() {
+            if let Some(child) = contents.iter().find(|child| !self.can_contain(child)) {
+                return Err(Error::IllegalNesting(child.kind_name(), self.kind_name()));
+            }
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::duration::Duration;
+    use crate::error::Error;
+    use crate::multiplier::Multiplier;
+    use crate::score_object::{container, rest, score, staff, staff_group, tuplet, voice};
+
+    #[test]
+    fn legal_nesting() {
+        let t = tuplet(
+            Multiplier::new(2, 3),
+            vec![rest(Duration::new(1, 8)).unwrap()],
+        )
+        .unwrap();
+        let c = container(vec![t]).unwrap();
+        let v = voice(vec![c]).unwrap();
+        let s = staff(vec![v]).unwrap();
+        let g = staff_group(vec![s]).unwrap();
+        let g = staff_group(vec![g]).unwrap();
+
+        assert!(score(vec![g]).is_ok());
+        assert!(staff(vec![rest(Duration::new(1, 4)).unwrap()]).is_ok());
+    }
+
+    #[test]
+    fn illegal_nesting() {
+        let s = score(vec![]).unwrap();
+        let t = tuplet(Multiplier::new(2, 3), vec![s]);
+        assert!(matches!(t, Err(Error::IllegalNesting("Score", "Tuplet"))));
+
+        let v = voice(vec![]).unwrap();
+        assert!(container(vec![v]).is_err());
+
+        let v = voice(vec![]).unwrap();
+        assert!(score(vec![v]).is_err());
+
+        let r = rest(Duration::new(1, 4)).unwrap();
+        assert!(staff_group(vec![r]).is_err());
+    }
+}
@@ -0,0 +1,335 @@
+use super::ScoreObject;
+use crate::{duration::Duration, error::Error, multiplier::Multiplier};
+
+impl ScoreObject {
+    pub fn multiplier(&self) -> Option<&Multiplier> {
+        match self {
+            Self::Tuplet { multiplier, .. } => Some(multiplier),
+            _ => None,
+        }
+    }
+
+    pub fn set_multiplier(&mut self, new_multiplier: Multiplier) {
+        if let Self::Tuplet { multiplier, .. } = self {
+            *multiplier = new_multiplier
+        }
+    }
+
+    /// True for tuplets whose multiplier is `1`.
+    pub fn is_trivial(&self) -> bool {
+        match self {
+            Self::Tuplet { multiplier, .. } => {
+                let (n, d) = multiplier.to_pair();
+                n == d
+            }
+            _ => false,
+        }
+    }
+
+    /// The ratio printed over the tuplet bracket: the override, if one is
+    /// set, otherwise the reduced inverse of the multiplier (`3:2` for `2/3`).
+    pub fn display_ratio(&self) -> Option<(i32, i32)> {
+        match self {
+            Self::Tuplet {
+                display_ratio: Some(ratio),
+                ..
+            } => Some(*ratio),
+            Self::Tuplet { multiplier, .. } => {
+                let (n, d) = multiplier.to_pair();
+                let (n, d) = Duration::new(n, d).to_pair();
+                Some((d, n))
+            }
+            _ => None,
+        }
+    }
+
+    /// Overrides the printed ratio, e.g. `10:6` rather than `5:3`. The ratio
+    /// must describe the same proportion as the multiplier.
+    pub fn set_display_ratio(
+        &mut self,
+        new_display_ratio: Option<(i32, i32)>,
+    ) -> Result<(), Error> {
+        if let Self::Tuplet {
+            multiplier,
+            display_ratio,
+            ..
+        } = self
+        {
+            if let Some((a, b)) = new_display_ratio {
+                let (n, d) = multiplier.to_pair();
+                if a <= 0 || b <= 0 || Duration::new(b, a) != Duration::new(n, d) {
+                    return Err(Error::InvalidDisplayRatio((a, b), *multiplier));
+                }
+            }
+            *display_ratio = new_display_ratio;
+        }
+        Ok(())
+    }
+
+    /// Removes a shared dot count from a tuplet's leaves, folding the dots
+    /// into the multiplier: `1/1` of dotted eighths becomes `3/2` of eighths.
+    pub fn rewrite_dots(&mut self) {
+        let Some(contents) = self.contents() else {
+            return;
+        };
+        let dot_counts: Vec<u32> = contents
+            .iter()
+            .flat_map(|component| component.leaves())
+            .filter_map(|leaf| leaf.written_duration())
+            .map(|duration| duration.dot_count())
+            .collect();
+
+        let Some(dots) = dot_counts.first().copied() else {
+            return;
+        };
+        if dots == 0 || dot_counts.iter().any(|count| *count != dots) {
+            return;
+        }
+
+        let dotted = (1 << (dots + 1)) - 1;
+        let undotted = 1 << dots;
+        if let Self::Tuplet { multiplier, .. } = self {
+            *multiplier = *multiplier * Multiplier::new(dotted, undotted);
+            self.scale_leaves(Duration::new(undotted, dotted));
+        }
+    }
+
+    /// Keeps the multiplier between `1/2` and `2`, inclusive, by doubling or
+    /// halving the written durations of the tuplet's leaves. Empty tuplets
+    /// are left alone; a non-positive multiplier is an error.
+    pub fn normalize_multiplier(&mut self) -> Result<(), Error> {
+        if !self.has_rescalable_multiplier()? {
+            return Ok(());
+        }
+        while let Some(multiplier) = self.multiplier().copied() {
+            let factor = match ratio(multiplier) {
+                m if m < Duration::new(1, 2) => Duration::new(1, 2),
+                m if m > Duration::new(2, 1) => Duration::new(2, 1),
+                _ => break,
+            };
+            self.try_scale_leaves(factor)?;
+        }
+        Ok(())
+    }
+
+    /// Turns a diminution (multiplier below `1`) into the equivalent
+    /// augmentation, and vice versa. Empty tuplets are left alone; a
+    /// non-positive multiplier is an error.
+    pub fn toggle_prolation(&mut self) -> Result<(), Error> {
+        if !self.has_rescalable_multiplier()? {
+            return Ok(());
+        }
+        let one = Duration::new(1, 1);
+        let Some(multiplier) = self.multiplier().copied() else {
+            return Ok(());
+        };
+        let factor = match ratio(multiplier) {
+            m if m < one => Duration::new(1, 2),
+            m if m > one => Duration::new(2, 1),
+            _ => return Ok(()),
+        };
+        let is_diminution = factor < one;
+        while let Some(multiplier) = self.multiplier().copied() {
+            match (is_diminution, ratio(multiplier)) {
+                (true, m) if m < one => self.try_scale_leaves(factor)?,
+                (false, m) if m > one => self.try_scale_leaves(factor)?,
+                _ => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// The product of the multipliers of every tuplet from this object down
+    /// to (but not including) the component at `path`.
+    pub fn implied_prolation(&self, path: &[usize]) -> Option<Multiplier> {
+        let mut prolation = Multiplier::new(1, 1);
+        let mut component = self;
+        for index in path {
+            if let Some(multiplier) = component.multiplier() {
+                prolation = prolation * *multiplier;
+            }
+            component = component.contents()?.get(*index)?;
+        }
+        Some(prolation)
+    }
+
+    /// Whether this is a non-empty tuplet, failing if its multiplier is not
+    /// positive.
+    fn has_rescalable_multiplier(&self) -> Result<bool, Error> {
+        let Some(multiplier) = self.multiplier() else {
+            return Ok(false);
+        };
+        if ratio(*multiplier) <= Duration::new(0, 1) {
+            return Err(Error::NonPositiveMultiplier(*multiplier));
+        }
+        Ok(self.contents().is_some_and(|contents| !contents.is_empty()))
+    }
+
+    /// Scales the leaves by `factor` and the multiplier by its inverse,
+    /// failing without changes if a leaf would become unprintable.
+    fn try_scale_leaves(&mut self, factor: Duration) -> Result<(), Error> {
+        for leaf in self.leaves() {
+            if let Some(written_duration) = leaf.written_duration() {
+                let scaled = written_duration * factor;
+                if !scaled.is_printable() {
+                    return Err(Error::UnprintableDuration(scaled));
+                }
+            }
+        }
+
+        let (n, d) = factor.to_pair();
+        if let Self::Tuplet { multiplier, .. } = self {
+            *multiplier = *multiplier * Multiplier::new(d, n);
+        }
+        self.scale_leaves(factor);
+        Ok(())
+    }
+
+    fn scale_leaves(&mut self, factor: Duration) {
+        match self.contents_mut() {
+            Some(contents) => contents
+                .iter_mut()
+                .for_each(|component| component.scale_leaves(factor)),
+            None => {
+                if let Some(written_duration) = self.written_duration() {
+                    self.set_written_duration(written_duration * factor);
+                }
+            }
+        }
+    }
+}
+
+fn ratio(multiplier: Multiplier) -> Duration {
+    let (n, d) = multiplier.to_pair();
+    Duration::new(n, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::duration::Duration;
+    use crate::error::Error;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{container, note, tuplet, tuplet_from_ratio, ScoreObject};
+
+    fn notes(count: usize, n: i32, d: i32) -> Vec<ScoreObject> {
+        (0..count)
+            .map(|_| {
+                note(
+                    Pitch::new(PitchClass::new(C, Natural), 4),
+                    Duration::new(n, d),
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn from_ratio() {
+        let t = tuplet_from_ratio(3, 2, notes(3, 1, 8)).unwrap();
+        assert_eq!(t.multiplier(), Some(&Multiplier::new(2, 3)));
+        assert_eq!(t.duration(), Duration::new(1, 4));
+    }
+
+    #[test]
+    fn is_trivial() {
+        assert!(tuplet(Multiplier::new(3, 3), notes(3, 1, 8))
+            .unwrap()
+            .is_trivial());
+        assert!(!tuplet(Multiplier::new(2, 3), notes(3, 1, 8))
+            .unwrap()
+            .is_trivial());
+        assert!(!container(notes(3, 1, 8)).unwrap().is_trivial());
+    }
+
+    #[test]
+    fn display_ratio() {
+        let mut t = tuplet(Multiplier::new(6, 10), notes(5, 1, 8)).unwrap();
+        assert_eq!(t.display_ratio(), Some((5, 3)));
+
+        assert!(t.set_display_ratio(Some((10, 6))).is_ok());
+        assert_eq!(t.display_ratio(), Some((10, 6)));
+
+        assert!(t.set_display_ratio(Some((4, 3))).is_err());
+        assert_eq!(t.display_ratio(), Some((10, 6)));
+    }
+
+    #[test]
+    fn rewrite_dots() {
+        let mut t = tuplet(Multiplier::new(1, 1), notes(2, 3, 16)).unwrap();
+        t.rewrite_dots();
+
+        assert_eq!(t.multiplier(), Some(&Multiplier::new(3, 2)));
+        assert_eq!(t[0].written_duration(), Some(Duration::new(1, 8)));
+        assert_eq!(t.duration(), Duration::new(3, 8));
+    }
+
+    #[test]
+    fn normalize_multiplier() {
+        let mut t = tuplet(Multiplier::new(1, 3), notes(3, 1, 4)).unwrap();
+        t.normalize_multiplier().unwrap();
+
+        assert_eq!(t.multiplier().unwrap().to_float(), 2. / 3.);
+        assert_eq!(t[0].written_duration(), Some(Duration::new(1, 8)));
+        assert_eq!(t.duration(), Duration::new(1, 4));
+    }
+
+    #[test]
+    fn normalize_multiplier_keeps_the_bounds() {
+        let mut t = tuplet(Multiplier::new(1, 2), notes(2, 1, 4)).unwrap();
+        t.normalize_multiplier().unwrap();
+        assert_eq!(t.multiplier(), Some(&Multiplier::new(1, 2)));
+        assert_eq!(t[0].written_duration(), Some(Duration::new(1, 4)));
+    }
+
+    #[test]
+    fn rescaling_rejects_non_positive_multipliers() {
+        for contents in [vec![], notes(2, 1, 4)] {
+            let mut t = tuplet(Multiplier::new(0, 1), contents).unwrap();
+            assert!(matches!(
+                t.normalize_multiplier(),
+                Err(Error::NonPositiveMultiplier(_))
+            ));
+            assert!(matches!(
+                t.toggle_prolation(),
+                Err(Error::NonPositiveMultiplier(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rescaling_leaves_empty_tuplets_alone() {
+        let mut t = tuplet(Multiplier::new(1, 3), vec![]).unwrap();
+        t.normalize_multiplier().unwrap();
+        t.toggle_prolation().unwrap();
+        assert_eq!(t.multiplier(), Some(&Multiplier::new(1, 3)));
+    }
+
+    #[test]
+    fn toggle_prolation() {
+        let mut t = tuplet(Multiplier::new(2, 3), notes(3, 1, 8)).unwrap();
+        t.toggle_prolation().unwrap();
+
+        assert_eq!(t.multiplier().unwrap().to_float(), 4. / 3.);
+        assert_eq!(t[0].written_duration(), Some(Duration::new(1, 16)));
+
+        t.toggle_prolation().unwrap();
+        assert_eq!(t.multiplier().unwrap().to_float(), 2. / 3.);
+        assert_eq!(t.duration(), Duration::new(1, 4));
+    }
+
+    #[test]
+    fn implied_prolation() {
+        let inner = tuplet(Multiplier::new(4, 5), notes(5, 1, 16)).unwrap();
+        let mut contents = notes(2, 1, 8);
+        contents.push(inner);
+        let outer = tuplet(Multiplier::new(2, 3), contents).unwrap();
+
+        assert_eq!(
+            outer.implied_prolation(&[2, 0]).unwrap().to_float(),
+            8. / 15.
+        );
+        assert_eq!(outer.implied_prolation(&[0]).unwrap().to_float(), 2. / 3.);
+        assert!(outer.implied_prolation(&[5]).is_none());
+    }
+}
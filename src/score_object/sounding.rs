@@ -0,0 +1,174 @@
+use super::ScoreObject;
+use crate::{pitch::Interval, pitch::Pitch};
+
+impl ScoreObject {
+    /// The pitches the note or chord at `path` sounds, applying the
+    /// transposition of the nearest enclosing voice or staff instrument.
+    /// Written pitches are taken to be in transposed (part) notation unless
+    /// an enclosing voice or staff is at concert pitch.
+    pub fn sounding_pitches(&self, path: &[usize]) -> Option<Vec<Pitch>> {
+        let mut component = self;
+        let mut instrument = self.instrument();
+        let mut is_concert_pitch = self.is_concert_pitch();
+        for index in path {
+            component = component.contents()?.get(*index)?;
+            instrument = component.instrument().or(instrument);
+            is_concert_pitch |= component.is_concert_pitch();
+        }
+        let transposition = match instrument {
+            Some(instrument) if !is_concert_pitch => instrument.transposition(),
+            _ => Interval::new(0, 0),
+        };
+        let pitches = match component {
+            Self::Note { written_pitch, .. } => vec![*written_pitch],
+            Self::Chord {
+                written_pitches, ..
+            } => written_pitches.to_vec(),
+            _ => return None,
+        };
+        Some(
+            pitches
+                .iter()
+                .map(|p| p.transpose(&transposition))
+                .collect(),
+        )
+    }
+
+    /// The pitch the note at `path` sounds. `None` for anything but a note.
+    pub fn sounding_pitch(&self, path: &[usize]) -> Option<Pitch> {
+        let mut component = self;
+        for index in path {
+            component = component.contents()?.get(*index)?;
+        }
+        match component {
+            Self::Note { .. } => self.sounding_pitches(path)?.first().copied(),
+            _ => None,
+        }
+    }
+
+    /// Rewrites transposed parts at sounding pitch, as for a score in C, and
+    /// marks every voice and staff as being at concert pitch. Voices and
+    /// staves already at concert pitch are left alone.
+    pub fn to_concert_pitch(&mut self) {
+        self.transpose_parts(None, false);
+    }
+
+    /// Rewrites a concert-pitch score so that transposing instruments read
+    /// their written parts. Undoes `to_concert_pitch`, and leaves voices and
+    /// staves that are not at concert pitch alone.
+    pub fn to_written_pitch(&mut self) {
+        self.transpose_parts(None, true);
+    }
+
+    fn transpose_parts(&mut self, transposition: Option<Interval>, reverse: bool) {
+        if let Some(is_concert_pitch) = self.concert_pitch_mut() {
+            if *is_concert_pitch != reverse {
+                return;
+            }
+            *is_concert_pitch = !reverse;
+        }
+        let transposition = match self.instrument() {
+            Some(instrument) if reverse => Some(instrument.transposition().reversed()),
+            Some(instrument) => Some(instrument.transposition()),
+            None => transposition,
+        };
+        let Some(interval) = transposition else {
+            for child in self.contents_mut().into_iter().flatten() {
+                child.transpose_parts(None, reverse);
+            }
+            return;
+        };
+        match self {
+            Self::Note { written_pitch, .. } => *written_pitch = written_pitch.transpose(&interval),
+            Self::Chord {
+                written_pitches, ..
+            } => {
+                for pitch in written_pitches.iter_mut() {
+                    *pitch = pitch.transpose(&interval);
+                }
+            }
+            _ => {
+                for child in self.contents_mut().into_iter().flatten() {
+                    child.transpose_parts(Some(interval), reverse);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::duration::Duration;
+    use crate::instrument::Instrument;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{chord, note, rest, ScoreBuilder, ScoreObject};
+
+    fn fixture() -> ScoreObject {
+        let quarter = Duration::new(1, 4);
+        let d4 = Pitch::new(PitchClass::new(D, Natural), 4);
+        let fs4 = Pitch::new(PitchClass::new(F, Sharp), 4);
+        ScoreBuilder::new()
+            .staff("Clarinet", |s| {
+                s.instrument(Instrument::clarinet_in_b_flat())
+                    .voice("Clarinet Voice", |v| {
+                        v.try_push(note(d4, quarter))
+                            .try_push(chord(vec![d4, fs4], quarter))
+                            .try_push(rest(quarter))
+                    })
+            })
+            .staff("Flute", |s| {
+                s.voice("Flute Voice", |v| v.try_push(note(d4, quarter)))
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn sounding_pitch() {
+        let s = fixture();
+        let c4 = Pitch::new(PitchClass::new(C, Natural), 4);
+        let e4 = Pitch::new(PitchClass::new(E, Natural), 4);
+        assert_eq!(s.sounding_pitch(&[0, 0, 0]), Some(c4));
+        assert_eq!(s.sounding_pitches(&[0, 0, 1]), Some(vec![c4, e4]));
+        assert_eq!(s.sounding_pitch(&[0, 0, 1]), None);
+        assert_eq!(s.sounding_pitches(&[0, 0, 2]), None);
+        assert_eq!(
+            s.sounding_pitch(&[1, 0, 0]),
+            Some(Pitch::new(PitchClass::new(D, Natural), 4))
+        );
+        assert_eq!(s.sounding_pitch(&[0, 0, 9]), None);
+    }
+
+    #[test]
+    fn concert_and_written_pitch() {
+        let original = fixture();
+        let mut s = original.clone();
+        s.to_concert_pitch();
+        assert_eq!(
+            s[0][0][1],
+            chord(
+                vec![
+                    Pitch::new(PitchClass::new(C, Natural), 4),
+                    Pitch::new(PitchClass::new(E, Natural), 4)
+                ],
+                Duration::new(1, 4)
+            )
+            .unwrap()
+        );
+        assert_eq!(s[1][0][0], original[1][0][0]);
+        assert!(s[0].is_concert_pitch() && s[1][0].is_concert_pitch());
+        assert_eq!(
+            s.sounding_pitch(&[0, 0, 0]),
+            Some(Pitch::new(PitchClass::new(C, Natural), 4))
+        );
+
+        let concert = s.clone();
+        s.to_concert_pitch();
+        assert_eq!(s, concert);
+
+        s.to_written_pitch();
+        assert_eq!(s, original);
+        s.to_written_pitch();
+        assert_eq!(s, original);
+    }
+}
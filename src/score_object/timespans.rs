@@ -0,0 +1,146 @@
+use super::ScoreObject;
+use crate::{duration::Duration, multiplier::Multiplier};
+
+/// Where a leaf sits in time, in whole notes from the start of the object it
+/// was collected from, after any tuplet scaling.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timespan<'a> {
+    pub path: Vec<usize>,
+    pub start_offset: Duration,
+    pub stop_offset: Duration,
+    pub leaf: &'a ScoreObject,
+}
+
+/// The leaves sounding at one instant where at least one leaf begins.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerticalMoment<'a> {
+    pub offset: Duration,
+    pub timespans: Vec<Timespan<'a>>,
+}
+
+impl VerticalMoment<'_> {
+    /// The timespans that begin at this moment rather than sustaining into it.
+    pub fn starting(&self) -> impl Iterator<Item = &Timespan<'_>> {
+        self.timespans
+            .iter()
+            .filter(move |timespan| timespan.start_offset == self.offset)
+    }
+}
+
+impl ScoreObject {
+    /// Every leaf with its path and offsets, in depth-first order.
+    pub fn leaf_timespans(&self) -> Vec<Timespan<'_>> {
+        let mut timespans = vec![];
+        collect(
+            self,
+            &mut vec![],
+            Duration::new(0, 1),
+            Multiplier::new(1, 1),
+            &mut timespans,
+        );
+        timespans
+    }
+
+    /// The vertical moments at each distinct leaf start offset, in time order.
+    /// Each moment holds every leaf sounding at that offset.
+    pub fn vertical_moments(&self) -> Vec<VerticalMoment<'_>> {
+        let timespans = self.leaf_timespans();
+        let mut offsets: Vec<Duration> = timespans.iter().map(|t| t.start_offset).collect();
+        offsets.sort();
+        offsets.dedup();
+        offsets
+            .into_iter()
+            .map(|offset| VerticalMoment {
+                offset,
+                timespans: timespans
+                    .iter()
+                    .filter(|t| t.start_offset <= offset && offset < t.stop_offset)
+                    .cloned()
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+fn collect<'a>(
+    component: &'a ScoreObject,
+    path: &mut Vec<usize>,
+    offset: Duration,
+    prolation: Multiplier,
+    timespans: &mut Vec<Timespan<'a>>,
+) -> Duration {
+    if let Some(written_duration) = component.written_duration() {
+        let stop_offset = offset + written_duration * prolation;
+        timespans.push(Timespan {
+            path: path.clone(),
+            start_offset: offset,
+            stop_offset,
+            leaf: component,
+        });
+        return stop_offset;
+    }
+
+    let prolation = match component.multiplier() {
+        Some(multiplier) => prolation * *multiplier,
+        None => prolation,
+    };
+    let mut stop_offset = offset;
+    for (i, child) in component.contents().into_iter().flatten().enumerate() {
+        path.push(i);
+        let start = if component.is_simultaneous() {
+            offset
+        } else {
+            stop_offset
+        };
+        stop_offset = stop_offset.max(collect(child, path, start, prolation, timespans));
+        path.pop();
+    }
+    stop_offset
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::duration::Duration;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{note, staff, tuplet_from_ratio, voice};
+
+    #[test]
+    fn leaf_timespans() {
+        let c4 = Pitch::new(PitchClass::new(C, Natural), 4);
+        let eighths = (0..3)
+            .map(|_| note(c4, Duration::new(1, 8)).unwrap())
+            .collect();
+        let v = voice(vec![
+            note(c4, Duration::new(1, 4)).unwrap(),
+            tuplet_from_ratio(3, 2, eighths).unwrap(),
+        ])
+        .unwrap();
+
+        let timespans = v.leaf_timespans();
+        assert_eq!(timespans.len(), 4);
+        assert_eq!(timespans[2].path, vec![1, 1]);
+        assert_eq!(timespans[2].start_offset, Duration::new(1, 3));
+        assert_eq!(timespans[2].stop_offset, Duration::new(5, 12));
+        assert_eq!(timespans[3].stop_offset, Duration::new(1, 2));
+    }
+
+    #[test]
+    fn vertical_moments() {
+        let pitch = |dpc| Pitch::new(PitchClass::new(dpc, Natural), 4);
+        let upper = voice(vec![
+            note(pitch(E), Duration::new(1, 4)).unwrap(),
+            note(pitch(F), Duration::new(1, 4)).unwrap(),
+        ])
+        .unwrap();
+        let lower = voice(vec![note(pitch(C), Duration::new(1, 2)).unwrap()]).unwrap();
+        let mut s = staff(vec![upper, lower]).unwrap();
+        s.set_is_simultaneous(true);
+
+        let moments = s.vertical_moments();
+        assert_eq!(moments.len(), 2);
+        assert_eq!(moments[1].offset, Duration::new(1, 4));
+        assert_eq!(moments[1].timespans.len(), 2);
+        assert_eq!(moments[1].starting().count(), 1);
+        assert_eq!(moments[1].timespans[1].path, vec![1, 0]);
+    }
+}
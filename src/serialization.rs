@@ -0,0 +1,214 @@
+//! Persistence of the score model with serde, behind the `serde` feature.
+//!
+//! Scores serialize to readable JSON: pitches as LilyPond English names
+//! (`"cs''"`), durations, multipliers and time signatures as fractions
+//! (`"3/8"`), intervals by name (`"-M2"`), and each `ScoreObject` as an object
+//! tagged with its `"type"`. Wrap a score in a `ScoreDocument` to record the
+//! format version alongside it.
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    duration::Duration,
+    error::Error,
+    multiplier::Multiplier,
+    pitch::{Interval, Pitch, PitchClass},
+    score_object::ScoreObject,
+    time_signature::TimeSignature,
+    wellformedness::{check_wellformedness, ViolationKind},
+};
+
+/// The version written by this crate. Documents from later versions are
+/// refused rather than misread.
+pub const FORMAT_VERSION: u32 = 1;
+
+macro_rules! serialize_as_string {
+    ($($t:ty),*) => {
+        $(
+            impl Serialize for $t {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(self)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $t {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    String::deserialize(deserializer)?
+                        .parse()
+                        .map_err(de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+serialize_as_string!(
+    Duration,
+    Multiplier,
+    TimeSignature,
+    Pitch,
+    PitchClass,
+    Interval
+);
+
+/// A score tagged with the format version it was written in.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScoreDocument {
+    format_version: u32,
+    score: ScoreObject,
+}
+
+impl ScoreDocument {
+    pub fn new(score: ScoreObject) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            score,
+        }
+    }
+
+    pub fn format_version(&self) -> u32 {
+        self.format_version
+    }
+
+    /// The score, if this crate understands the document's version and the
+    /// score is well-formed. Notes outside an instrument's range are allowed,
+    /// as they are when building a score.
+    pub fn into_score(self) -> Result<ScoreObject, Error> {
+        if !(1..=FORMAT_VERSION).contains(&self.format_version) {
+            return Err(Error::UnsupportedFormatVersion(self.format_version));
+        }
+        match check_wellformedness(&self.score)
+            .into_iter()
+            .find(|violation| !matches!(violation.kind, ViolationKind::OutOfRange(_)))
+        {
+            Some(violation) => Err(Error::IllFormedScore(violation)),
+            None => Ok(self.score),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScoreDocument;
+    use crate::duration::Duration;
+    use crate::error::Error;
+    use crate::instrument::Instrument;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{
+        chord, lyric, measure, note, rest, tuplet, ScoreBuilder, ScoreObject, StaffContext,
+    };
+    use crate::time_signature::TimeSignature;
+    use crate::wellformedness::{Violation, ViolationKind};
+
+    fn fixture() -> ScoreObject {
+        let eighth = Duration::new(1, 8);
+        let cs5 = Pitch::new(PitchClass::new(C, Sharp), 5);
+        let eqf4 = Pitch::new(PitchClass::new(E, QuarterFlat), 4);
+        let triplet = tuplet(
+            Multiplier::new(2, 3),
+            vec![
+                note(cs5, eighth).unwrap(),
+                rest(eighth).unwrap(),
+                chord(vec![eqf4, cs5], eighth).unwrap(),
+            ],
+        )
+        .unwrap();
+        ScoreBuilder::new()
+            .name("Sketch")
+            .staff("Clarinet", |s| {
+                s.instrument(Instrument::clarinet_in_b_flat())
+                    .context(StaffContext::Staff)
+                    .voice("Clarinet Voice", |v| {
+                        v.try_push(measure(
+                            TimeSignature::new(2, 4),
+                            vec![triplet, note(cs5, Duration::new(1, 4)).unwrap()],
+                        ))
+                    })
+                    .voice("Words", |v| v.try_push(lyric("la", Duration::new(1, 2))))
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn readable_json() {
+        let json = serde_json::to_value(ScoreDocument::new(fixture())).unwrap();
+        assert_eq!(json["format_version"], 1);
+        assert_eq!(json["score"]["type"], "Score");
+
+        let staff = &json["score"]["contents"][0];
+        assert_eq!(staff["instrument"]["transposition"], "-M2");
+        let m = &staff["contents"][0]["contents"][0];
+        assert_eq!(m["time_signature"], "2/4");
+        assert_eq!(m["contents"][0]["multiplier"], "2/3");
+        assert_eq!(m["contents"][0]["contents"][0]["written_pitch"], "cs''");
+        assert_eq!(m["contents"][0]["contents"][0]["written_duration"], "1/8");
+        assert_eq!(
+            m["contents"][0]["contents"][2]["written_pitches"][0],
+            "eqf'"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let score = fixture();
+        let json = serde_json::to_string(&ScoreDocument::new(score.clone())).unwrap();
+        let document: ScoreDocument = serde_json::from_str(&json).unwrap();
+        assert_eq!(document.into_score().unwrap(), score);
+    }
+
+    #[test]
+    fn rejects_future_versions_and_bad_values() {
+        let json = serde_json::json!({
+            "format_version": 99,
+            "score": {"type": "Rest", "written_duration": "1/4"}
+        });
+        let document: ScoreDocument = serde_json::from_value(json).unwrap();
+        assert!(document.into_score().is_err());
+
+        let bad_pitch = serde_json::json!({
+            "type": "Note",
+            "written_pitch": "h'",
+            "written_duration": "1/4",
+            "is_tied": false
+        });
+        assert!(serde_json::from_value::<ScoreObject>(bad_pitch).is_err());
+    }
+
+    #[test]
+    fn rejects_ill_formed_scores() {
+        let json = serde_json::json!({
+            "format_version": 1,
+            "score": {
+                "type": "Measure",
+                "time_signature": "3/4",
+                "bar_line": "Single",
+                "contents": [{"type": "Rest", "written_duration": "1/4"}]
+            }
+        });
+        let document: ScoreDocument = serde_json::from_value(json).unwrap();
+        assert!(matches!(
+            document.into_score(),
+            Err(Error::IllFormedScore(Violation {
+                kind: ViolationKind::MeasureDurationMismatch(..),
+                ..
+            }))
+        ));
+
+        let json = serde_json::json!({
+            "format_version": 1,
+            "score": {
+                "type": "Voice",
+                "contents": [{"type": "Staff", "contents": [], "is_simultaneous": false,
+                    "name": null, "context": "Staff", "instrument": null}],
+                "is_simultaneous": false,
+                "name": null,
+                "context": "Voice",
+                "instrument": null
+            }
+        });
+        let document: ScoreDocument = serde_json::from_value(json).unwrap();
+        assert!(document.into_score().is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use std::{collections::HashMap, fmt, sync::OnceLock};
+
+use super::PitchClassSet;
+
+/// A set class's name in Forte's catalogue, such as `4-Z15`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ForteNumber {
+    cardinality: usize,
+    ordinal: usize,
+    is_z: bool,
+}
+
+impl ForteNumber {
+    pub fn cardinality(&self) -> usize {
+        self.cardinality
+    }
+
+    pub fn ordinal(&self) -> usize {
+        self.ordinal
+    }
+
+    pub fn is_z(&self) -> bool {
+        self.is_z
+    }
+}
+
+impl fmt::Display for ForteNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let z = if self.is_z { "Z" } else { "" };
+        write!(f, "{}-{}{}", self.cardinality, z, self.ordinal)
+    }
+}
+
+/// Forte's prime forms for trichords through hexachords, in catalogue order,
+/// with the ordinals of Z-related classes. Septachords through nonachords are
+/// the complements of pentachords through trichords with the same ordinal.
+const TRICHORDS: &[&[i32]] = &[
+    &[0, 1, 2],
+    &[0, 1, 3],
+    &[0, 1, 4],
+    &[0, 1, 5],
+    &[0, 1, 6],
+    &[0, 2, 4],
+    &[0, 2, 5],
+    &[0, 2, 6],
+    &[0, 2, 7],
+    &[0, 3, 6],
+    &[0, 3, 7],
+    &[0, 4, 8],
+];
+
+const TETRACHORDS: &[&[i32]] = &[
+    &[0, 1, 2, 3],
+    &[0, 1, 2, 4],
+    &[0, 1, 3, 4],
+    &[0, 1, 2, 5],
+    &[0, 1, 2, 6],
+    &[0, 1, 2, 7],
+    &[0, 1, 4, 5],
+    &[0, 1, 5, 6],
+    &[0, 1, 6, 7],
+    &[0, 2, 3, 5],
+    &[0, 1, 3, 5],
+    &[0, 2, 3, 6],
+    &[0, 1, 3, 6],
+    &[0, 2, 3, 7],
+    &[0, 1, 4, 6],
+    &[0, 1, 5, 7],
+    &[0, 3, 4, 7],
+    &[0, 1, 4, 7],
+    &[0, 1, 4, 8],
+    &[0, 1, 5, 8],
+    &[0, 2, 4, 6],
+    &[0, 2, 4, 7],
+    &[0, 2, 5, 7],
+    &[0, 2, 4, 8],
+    &[0, 2, 6, 8],
+    &[0, 3, 5, 8],
+    &[0, 2, 5, 8],
+    &[0, 3, 6, 9],
+    &[0, 1, 3, 7],
+];
+
+const PENTACHORDS: &[&[i32]] = &[
+    &[0, 1, 2, 3, 4],
+    &[0, 1, 2, 3, 5],
+    &[0, 1, 2, 4, 5],
+    &[0, 1, 2, 3, 6],
+    &[0, 1, 2, 3, 7],
+    &[0, 1, 2, 5, 6],
+    &[0, 1, 2, 6, 7],
+    &[0, 2, 3, 4, 6],
+    &[0, 1, 2, 4, 6],
+    &[0, 1, 3, 4, 6],
+    &[0, 2, 3, 4, 7],
+    &[0, 1, 3, 5, 6],
+    &[0, 1, 2, 4, 8],
+    &[0, 1, 2, 5, 7],
+    &[0, 1, 2, 6, 8],
+    &[0, 1, 3, 4, 7],
+    &[0, 1, 3, 4, 8],
+    &[0, 1, 4, 5, 7],
+    &[0, 1, 3, 6, 7],
+    &[0, 1, 5, 6, 8],
+    &[0, 1, 4, 5, 8],
+    &[0, 1, 4, 7, 8],
+    &[0, 2, 3, 5, 7],
+    &[0, 1, 3, 5, 7],
+    &[0, 2, 3, 5, 8],
+    &[0, 2, 4, 5, 8],
+    &[0, 1, 3, 5, 8],
+    &[0, 2, 3, 6, 8],
+    &[0, 1, 3, 6, 8],
+    &[0, 1, 4, 6, 8],
+    &[0, 1, 3, 6, 9],
+    &[0, 1, 4, 6, 9],
+    &[0, 2, 4, 6, 8],
+    &[0, 2, 4, 6, 9],
+    &[0, 2, 4, 7, 9],
+    &[0, 1, 2, 4, 7],
+    &[0, 3, 4, 5, 8],
+    &[0, 1, 2, 5, 8],
+];
+
+const HEXACHORDS: &[&[i32]] = &[
+    &[0, 1, 2, 3, 4, 5],
+    &[0, 1, 2, 3, 4, 6],
+    &[0, 1, 2, 3, 5, 6],
+    &[0, 1, 2, 4, 5, 6],
+    &[0, 1, 2, 3, 6, 7],
+    &[0, 1, 2, 5, 6, 7],
+    &[0, 1, 2, 6, 7, 8],
+    &[0, 2, 3, 4, 5, 7],
+    &[0, 1, 2, 3, 5, 7],
+    &[0, 1, 3, 4, 5, 7],
+    &[0, 1, 2, 4, 5, 7],
+    &[0, 1, 2, 4, 6, 7],
+    &[0, 1, 3, 4, 6, 7],
+    &[0, 1, 3, 4, 5, 8],
+    &[0, 1, 2, 4, 5, 8],
+    &[0, 1, 4, 5, 6, 8],
+    &[0, 1, 2, 4, 7, 8],
+    &[0, 1, 2, 5, 7, 8],
+    &[0, 1, 3, 4, 7, 8],
+    &[0, 1, 4, 5, 8, 9],
+    &[0, 2, 3, 4, 6, 8],
+    &[0, 1, 2, 4, 6, 8],
+    &[0, 2, 3, 5, 6, 8],
+    &[0, 1, 3, 4, 6, 8],
+    &[0, 1, 3, 5, 6, 8],
+    &[0, 1, 3, 5, 7, 8],
+    &[0, 1, 3, 4, 6, 9],
+    &[0, 1, 3, 5, 6, 9],
+    &[0, 1, 3, 6, 8, 9],
+    &[0, 1, 3, 6, 7, 9],
+    &[0, 1, 3, 5, 8, 9],
+    &[0, 2, 4, 5, 7, 9],
+    &[0, 2, 3, 5, 7, 9],
+    &[0, 1, 3, 5, 7, 9],
+    &[0, 2, 4, 6, 8, 10],
+    &[0, 1, 2, 3, 4, 7],
+    &[0, 1, 2, 3, 4, 8],
+    &[0, 1, 2, 3, 7, 8],
+    &[0, 2, 3, 4, 5, 8],
+    &[0, 1, 2, 3, 5, 8],
+    &[0, 1, 2, 3, 6, 8],
+    &[0, 1, 2, 3, 6, 9],
+    &[0, 1, 2, 5, 6, 8],
+    &[0, 1, 2, 5, 6, 9],
+    &[0, 2, 3, 4, 6, 9],
+    &[0, 1, 2, 4, 6, 9],
+    &[0, 1, 2, 4, 7, 9],
+    &[0, 1, 2, 5, 7, 9],
+    &[0, 1, 3, 4, 7, 9],
+    &[0, 1, 4, 6, 7, 9],
+];
+
+const Z_ORDINALS: &[(usize, &[usize])] = &[
+    (4, &[15, 29]),
+    (5, &[12, 17, 18, 36, 37, 38]),
+    (
+        6,
+        &[
+            3, 4, 6, 10, 11, 12, 13, 17, 19, 23, 24, 25, 26, 28, 29, 36, 37, 38, 39, 40, 41, 42,
+            43, 44, 45, 46, 47, 48, 49, 50,
+        ],
+    ),
+];
+
+fn is_z(cardinality: usize, ordinal: usize) -> bool {
+    let cardinality = cardinality.min(12 - cardinality);
+    Z_ORDINALS
+        .iter()
+        .any(|(c, ordinals)| *c == cardinality && ordinals.contains(&ordinal))
+}
+
+fn catalogue() -> &'static HashMap<Vec<i32>, ForteNumber> {
+    static CATALOGUE: OnceLock<HashMap<Vec<i32>, ForteNumber>> = OnceLock::new();
+    CATALOGUE.get_or_init(|| {
+        let mut catalogue = HashMap::new();
+        for table in [TRICHORDS, TETRACHORDS, PENTACHORDS, HEXACHORDS] {
+            for (index, form) in table.iter().enumerate() {
+                let set = PitchClassSet::from_numbers(form);
+                let mut entries = vec![set.clone()];
+                if set.len() < 6 {
+                    entries.push(set.complement());
+                }
+                for entry in entries {
+                    let number = ForteNumber {
+                        cardinality: entry.len(),
+                        ordinal: index + 1,
+                        is_z: is_z(entry.len(), index + 1),
+                    };
+                    catalogue.insert(entry.prime_form().unwrap(), number);
+                }
+            }
+        }
+        catalogue
+    })
+}
+
+pub(super) fn lookup(prime_form: &[i32]) -> Option<ForteNumber> {
+    catalogue().get(prime_form).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::catalogue;
+    use crate::set_theory::PitchClassSet;
+
+    #[test]
+    fn catalogue_covers_every_set_class() {
+        let mut counts = HashMap::new();
+        for bits in 0..(1 << 12) {
+            let numbers: Vec<i32> = (0..12).filter(|n| bits & (1 << n) != 0).collect();
+            let set = PitchClassSet::from_numbers(&numbers);
+            if (3..=9).contains(&set.len()) {
+                let number = set.forte_number().expect("every set class is catalogued");
+                counts.insert(set.prime_form().unwrap(), number);
+            }
+        }
+
+        assert_eq!(counts.len(), catalogue().len());
+        for cardinality in 3..=9 {
+            let expected = [12, 29, 38, 50, 38, 29, 12][cardinality - 3];
+            let found = catalogue()
+                .values()
+                .filter(|number| number.cardinality() == cardinality)
+                .count();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn z_marks_match_interval_class_vectors() {
+        for (prime_form, number) in catalogue().iter() {
+            let set = PitchClassSet::from_numbers(prime_form);
+            let partners = catalogue()
+                .keys()
+                .map(|other| PitchClassSet::from_numbers(other))
+                .filter(|other| set.is_z_related(other))
+                .count();
+            assert_eq!(partners > 0, number.is_z(), "{number}");
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Post-tonal pitch-class set theory.
+//!
+//! Pitch classes are handled in a 24-tone universe of quarter tones so that
+//! quarter-tone pitch classes are never silently rounded. Transposition,
+//! inversion, normal order, complements and subset relations work for any
+//! set. Prime forms, interval-class vectors, Z-relations and Forte numbers
+//! are only defined for the twelve equal-tempered pitch classes: for sets
+//! containing quarter tones they return `None` (or `false`). Complements are
+//! taken in the 12-tone universe for twelve-tone sets and in the 24-tone
+//! universe otherwise. Prime forms follow Rahn, so a few differ from the
+//! forms in Forte's table; the Forte numbers do not.
+
+mod forte;
+mod pitch_class_segment;
+mod pitch_class_set;
+mod twelve_tone_row;
+
+pub use forte::ForteNumber;
+pub use pitch_class_segment::PitchClassSegment;
+pub use pitch_class_set::PitchClassSet;
+pub use twelve_tone_row::{RowForm, TwelveToneRow};
+
+use crate::{has_semitones::HasSemitones, pitch::PitchClass};
+
+const QUARTER_TONES_PER_OCTAVE: i32 = 24;
+
+fn quarter_tones(pitch_class: &PitchClass) -> i32 {
+    pitch_class.quarter_tones()
+}
+
+fn transposed(quarter_tones: i32, semitones: i32) -> i32 {
+    (quarter_tones + semitones * 2).rem_euclid(QUARTER_TONES_PER_OCTAVE)
+}
+
+fn inverted(quarter_tones: i32, semitones: i32) -> i32 {
+    (semitones * 2 - quarter_tones).rem_euclid(QUARTER_TONES_PER_OCTAVE)
+}
+
+fn to_pitch_class(quarter_tones: i32) -> PitchClass {
+    PitchClass::from_quarter_tones(quarter_tones)
+}
@@ -0,0 +1,108 @@
+use super::{inverted, quarter_tones, to_pitch_class, transposed, PitchClassSet};
+use crate::pitch::PitchClass;
+
+/// An ordered sequence of pitch classes, which may repeat.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PitchClassSegment {
+    items: Vec<i32>,
+}
+
+impl PitchClassSegment {
+    pub fn new(pitch_classes: Vec<PitchClass>) -> Self {
+        Self {
+            items: pitch_classes.iter().map(quarter_tones).collect(),
+        }
+    }
+
+    pub(crate) fn from_quarter_tones(items: Vec<i32>) -> Self {
+        Self { items }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The pitch classes as semitone numbers (`1.5` for a three-quarter-tone
+    /// above C).
+    pub fn semitones(&self) -> Vec<f32> {
+        self.items.iter().map(|item| *item as f32 / 2.).collect()
+    }
+
+    /// The pitch classes, spelled with sharps.
+    pub fn pitch_classes(&self) -> Vec<PitchClass> {
+        self.items
+            .iter()
+            .map(|item| to_pitch_class(*item))
+            .collect()
+    }
+
+    pub fn transpose(&self, semitones: i32) -> Self {
+        Self::from_quarter_tones(
+            self.items
+                .iter()
+                .map(|i| transposed(*i, semitones))
+                .collect(),
+        )
+    }
+
+    /// Inverts about C, then transposes by `semitones` (TnI).
+    pub fn invert(&self, semitones: i32) -> Self {
+        Self::from_quarter_tones(self.items.iter().map(|i| inverted(*i, semitones)).collect())
+    }
+
+    pub fn retrograde(&self) -> Self {
+        Self::from_quarter_tones(self.items.iter().rev().copied().collect())
+    }
+
+    /// Rotates left by `n` places, so the item at `n` comes first.
+    pub fn rotate(&self, n: usize) -> Self {
+        let mut items = self.items.clone();
+        if !items.is_empty() {
+            let len = items.len();
+            items.rotate_left(n % len);
+        }
+        Self::from_quarter_tones(items)
+    }
+
+    pub fn to_set(&self) -> PitchClassSet {
+        PitchClassSet::from_quarter_tones(self.items.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PitchClassSegment;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, PitchClass};
+
+    fn segment() -> PitchClassSegment {
+        PitchClassSegment::new(vec![
+            PitchClass::new(E, Natural),
+            PitchClass::new(C, Natural),
+            PitchClass::new(G, Natural),
+            PitchClass::new(C, Natural),
+        ])
+    }
+
+    #[test]
+    fn operations() {
+        assert_eq!(segment().semitones(), vec![4., 0., 7., 0.]);
+        assert_eq!(segment().transpose(2).semitones(), vec![6., 2., 9., 2.]);
+        assert_eq!(segment().invert(0).semitones(), vec![8., 0., 5., 0.]);
+        assert_eq!(segment().retrograde().semitones(), vec![0., 7., 0., 4.]);
+        assert_eq!(segment().rotate(1).semitones(), vec![0., 7., 0., 4.]);
+        assert_eq!(segment().to_set().len(), 3);
+    }
+
+    #[test]
+    fn quarter_tones() {
+        let s = PitchClassSegment::new(vec![PitchClass::new(C, QuarterSharp)]);
+        assert_eq!(
+            s.invert(0).pitch_classes(),
+            vec![PitchClass::new(B, QuarterSharp)]
+        );
+    }
+}
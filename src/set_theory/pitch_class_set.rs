@@ -0,0 +1,285 @@
+use super::{
+    forte::{self, ForteNumber},
+    inverted, quarter_tones, to_pitch_class, transposed, PitchClassSegment,
+    QUARTER_TONES_PER_OCTAVE,
+};
+use crate::pitch::PitchClass;
+
+/// An unordered collection of distinct pitch classes. Enharmonic spellings
+/// are the same member.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PitchClassSet {
+    items: Vec<i32>,
+}
+
+impl PitchClassSet {
+    pub fn new(pitch_classes: Vec<PitchClass>) -> Self {
+        Self::from_quarter_tones(pitch_classes.iter().map(quarter_tones).collect())
+    }
+
+    /// Builds a twelve-tone set from pitch-class integers, reducing them
+    /// modulo 12.
+    pub fn from_numbers(numbers: &[i32]) -> Self {
+        Self::from_quarter_tones(numbers.iter().map(|n| n.rem_euclid(12) * 2).collect())
+    }
+
+    pub(crate) fn from_quarter_tones(mut items: Vec<i32>) -> Self {
+        items.sort();
+        items.dedup();
+        Self { items }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn contains(&self, pitch_class: &PitchClass) -> bool {
+        self.items.contains(&quarter_tones(pitch_class))
+    }
+
+    /// The members as ascending semitone numbers.
+    pub fn semitones(&self) -> Vec<f32> {
+        self.items.iter().map(|item| *item as f32 / 2.).collect()
+    }
+
+    /// The members in ascending order, spelled with sharps.
+    pub fn pitch_classes(&self) -> Vec<PitchClass> {
+        self.items
+            .iter()
+            .map(|item| to_pitch_class(*item))
+            .collect()
+    }
+
+    /// True when every member belongs to the twelve-tone equal-tempered
+    /// universe.
+    pub fn is_twelve_tone(&self) -> bool {
+        self.items.iter().all(|item| item % 2 == 0)
+    }
+
+    pub fn transpose(&self, semitones: i32) -> Self {
+        Self::from_quarter_tones(
+            self.items
+                .iter()
+                .map(|i| transposed(*i, semitones))
+                .collect(),
+        )
+    }
+
+    /// Inverts about C, then transposes by `semitones` (TnI).
+    pub fn invert(&self, semitones: i32) -> Self {
+        Self::from_quarter_tones(self.items.iter().map(|i| inverted(*i, semitones)).collect())
+    }
+
+    /// The most compact rotation of the set, packed to the left: the
+    /// rotation with the smallest span, ties broken by the smallest interval
+    /// from the first member to the last, then second-to-last, and so on.
+    pub fn normal_order(&self) -> PitchClassSegment {
+        PitchClassSegment::from_quarter_tones(normal_order(&self.items))
+    }
+
+    /// The normal order of the set or its inversion, whichever is more
+    /// compact, transposed to begin on 0, as twelve-tone integers. Ties are
+    /// broken as in `normal_order`, so these are Rahn's prime forms: they
+    /// differ from those in Forte's table for 6-Z29 (`[0, 2, 3, 6, 7, 9]`
+    /// rather than `[0, 1, 3, 6, 8, 9]`), 6-31 and some of the complements,
+    /// though the Forte numbers are the same.
+    pub fn prime_form(&self) -> Option<Vec<i32>> {
+        if !self.is_twelve_tone() {
+            return None;
+        }
+        let zeroed = |items: Vec<i32>| -> Vec<i32> {
+            let first = items.first().copied().unwrap_or(0);
+            items
+                .iter()
+                .map(|item| (item - first).rem_euclid(QUARTER_TONES_PER_OCTAVE) / 2)
+                .collect()
+        };
+        let original = zeroed(normal_order(&self.items));
+        let inversion = zeroed(normal_order(&self.invert(0).items));
+        Some(std::cmp::min_by_key(original, inversion, |form| {
+            form.iter().rev().copied().collect::<Vec<i32>>()
+        }))
+    }
+
+    /// Counts of each interval class 1-6 between pairs of members.
+    pub fn interval_class_vector(&self) -> Option<[i32; 6]> {
+        if !self.is_twelve_tone() {
+            return None;
+        }
+        let mut vector = [0; 6];
+        for (i, a) in self.items.iter().enumerate() {
+            for b in self.items[i + 1..].iter() {
+                let interval = ((b - a) / 2).rem_euclid(12);
+                let interval_class = interval.min(12 - interval);
+                if interval_class > 0 {
+                    vector[interval_class as usize - 1] += 1;
+                }
+            }
+        }
+        Some(vector)
+    }
+
+    pub fn forte_number(&self) -> Option<ForteNumber> {
+        forte::lookup(&self.prime_form()?)
+    }
+
+    /// True when the sets share an interval-class vector without being
+    /// related by transposition or inversion.
+    pub fn is_z_related(&self, other: &PitchClassSet) -> bool {
+        match (self.interval_class_vector(), other.interval_class_vector()) {
+            (Some(a), Some(b)) => a == b && self.prime_form() != other.prime_form(),
+            _ => false,
+        }
+    }
+
+    pub fn complement(&self) -> Self {
+        let step = if self.is_twelve_tone() { 2 } else { 1 };
+        Self::from_quarter_tones(
+            (0..QUARTER_TONES_PER_OCTAVE)
+                .step_by(step)
+                .filter(|item| !self.items.contains(item))
+                .collect(),
+        )
+    }
+
+    pub fn is_subset_of(&self, other: &PitchClassSet) -> bool {
+        self.items.iter().all(|item| other.items.contains(item))
+    }
+
+    pub fn is_superset_of(&self, other: &PitchClassSet) -> bool {
+        other.is_subset_of(self)
+    }
+}
+
+fn normal_order(items: &[i32]) -> Vec<i32> {
+    let span = |rotation: &Vec<i32>| -> Vec<i32> {
+        rotation
+            .iter()
+            .rev()
+            .map(|item| (item - rotation[0]).rem_euclid(QUARTER_TONES_PER_OCTAVE))
+            .collect()
+    };
+    (0..items.len())
+        .map(|n| {
+            let mut rotation = items.to_vec();
+            rotation.rotate_left(n);
+            rotation
+        })
+        .min_by_key(|rotation| (span(rotation), rotation.first().copied()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PitchClassSet;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, PitchClass};
+
+    #[test]
+    fn new() {
+        let set = PitchClassSet::new(vec![
+            PitchClass::new(G, Sharp),
+            PitchClass::new(A, Flat),
+            PitchClass::new(C, Natural),
+        ]);
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.semitones(), vec![0., 8.]);
+        assert!(set.contains(&PitchClass::new(G, Sharp)));
+    }
+
+    #[test]
+    fn transpose_and_invert() {
+        let set = PitchClassSet::from_numbers(&[0, 4, 7]);
+        assert_eq!(set.transpose(5), PitchClassSet::from_numbers(&[5, 9, 0]));
+        assert_eq!(set.invert(0), PitchClassSet::from_numbers(&[0, 8, 5]));
+        assert_eq!(set.invert(7), PitchClassSet::from_numbers(&[7, 3, 0]));
+    }
+
+    #[test]
+    fn normal_order() {
+        let set = PitchClassSet::from_numbers(&[0, 4, 7]);
+        assert_eq!(set.normal_order().semitones(), vec![0., 4., 7.]);
+
+        let set = PitchClassSet::from_numbers(&[11, 2, 7]);
+        assert_eq!(set.normal_order().semitones(), vec![7., 11., 2.]);
+
+        let set = PitchClassSet::from_numbers(&[1, 5, 9, 10]);
+        assert_eq!(set.normal_order().semitones(), vec![9., 10., 1., 5.]);
+    }
+
+    #[test]
+    fn prime_form() {
+        assert_eq!(
+            PitchClassSet::from_numbers(&[0, 4, 7]).prime_form(),
+            Some(vec![0, 3, 7])
+        );
+        assert_eq!(
+            PitchClassSet::from_numbers(&[2, 5, 6, 9]).prime_form(),
+            Some(vec![0, 3, 4, 7])
+        );
+        assert_eq!(
+            PitchClassSet::from_numbers(&[0, 1, 3, 6, 8, 9]).prime_form(),
+            Some(vec![0, 2, 3, 6, 7, 9])
+        );
+        assert_eq!(
+            PitchClassSet::new(vec![PitchClass::new(C, QuarterSharp)]).prime_form(),
+            None
+        );
+    }
+
+    #[test]
+    fn interval_class_vector() {
+        assert_eq!(
+            PitchClassSet::from_numbers(&[0, 4, 7]).interval_class_vector(),
+            Some([0, 0, 1, 1, 1, 0])
+        );
+        assert_eq!(
+            PitchClassSet::from_numbers(&[0, 2, 4, 5, 7, 9, 11]).interval_class_vector(),
+            Some([2, 5, 4, 3, 6, 1])
+        );
+    }
+
+    #[test]
+    fn forte_number() {
+        let major_triad = PitchClassSet::from_numbers(&[0, 4, 7]);
+        assert_eq!(major_triad.forte_number().unwrap().to_string(), "3-11");
+
+        let all_interval = PitchClassSet::from_numbers(&[0, 1, 4, 6]);
+        assert_eq!(all_interval.forte_number().unwrap().to_string(), "4-Z15");
+
+        let diatonic = PitchClassSet::from_numbers(&[0, 2, 4, 5, 7, 9, 11]);
+        assert_eq!(diatonic.forte_number().unwrap().to_string(), "7-35");
+
+        assert!(PitchClassSet::from_numbers(&[0, 1])
+            .forte_number()
+            .is_none());
+    }
+
+    #[test]
+    fn z_relation() {
+        let a = PitchClassSet::from_numbers(&[0, 1, 4, 6]);
+        let b = PitchClassSet::from_numbers(&[0, 1, 3, 7]);
+        assert!(a.is_z_related(&b));
+        assert!(!a.is_z_related(&a.transpose(3)));
+    }
+
+    #[test]
+    fn complement_and_subsets() {
+        let triad = PitchClassSet::from_numbers(&[0, 4, 7]);
+        let scale = PitchClassSet::from_numbers(&[0, 2, 4, 5, 7, 9, 11]);
+
+        assert_eq!(
+            scale.complement(),
+            PitchClassSet::from_numbers(&[1, 3, 6, 8, 10])
+        );
+        assert!(triad.is_subset_of(&scale));
+        assert!(scale.is_superset_of(&triad));
+        assert!(!scale.is_subset_of(&triad));
+
+        let quarter = PitchClassSet::new(vec![PitchClass::new(C, QuarterSharp)]);
+        assert_eq!(quarter.complement().len(), 23);
+    }
+}
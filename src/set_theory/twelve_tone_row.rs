@@ -0,0 +1,290 @@
+use super::{PitchClassSegment, PitchClassSet};
+use crate::{
+    duration::Duration,
+    error::Error,
+    has_semitones::HasSemitones,
+    pitch::{Pitch, PitchClass},
+    score_object::{note, ScoreObject},
+};
+
+/// One of the 48 forms of a row. Transposition levels are relative to the
+/// row as given, so `Prime(0)` is the row itself and `Inversion(0)` is its
+/// inversion beginning on the same pitch class.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RowForm {
+    Prime(i32),
+    Inversion(i32),
+    Retrograde(i32),
+    RetrogradeInversion(i32),
+}
+
+/// An ordering of all twelve equal-tempered pitch classes.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TwelveToneRow {
+    items: [i32; 12],
+}
+
+impl TwelveToneRow {
+    pub fn new(pitch_classes: Vec<PitchClass>) -> Result<Self, Error> {
+        if let Some(pc) = pitch_classes.iter().find(|pc| pc.quarter_tones() % 2 != 0) {
+            return Err(Error::QuarterToneInRow(*pc));
+        }
+        Self::from_numbers(
+            &pitch_classes
+                .iter()
+                .map(|pc| pc.quarter_tones() / 2)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Builds a row from integers 0-11, with C as 0.
+    pub fn from_numbers(numbers: &[i32]) -> Result<Self, Error> {
+        let items: Vec<i32> = numbers.iter().map(|n| n.rem_euclid(12)).collect();
+        let mut sorted = items.clone();
+        sorted.sort();
+        match items.try_into() {
+            Ok(items) if sorted == (0..12).collect::<Vec<_>>() => Ok(Self { items }),
+            _ => Err(Error::InvalidTwelveToneRow(numbers.to_vec())),
+        }
+    }
+
+    pub fn numbers(&self) -> [i32; 12] {
+        self.items
+    }
+
+    /// The pitch classes, spelled with sharps.
+    pub fn pitch_classes(&self) -> Vec<PitchClass> {
+        self.items
+            .iter()
+            .map(|n| PitchClass::from_quarter_tones(n * 2))
+            .collect()
+    }
+
+    pub fn to_segment(&self) -> PitchClassSegment {
+        PitchClassSegment::from_quarter_tones(self.items.iter().map(|n| n * 2).collect())
+    }
+
+    pub fn form(&self, form: RowForm) -> Self {
+        let first = self.items[0];
+        let map = |f: &dyn Fn(i32) -> i32, reverse: bool| {
+            let mut items = self.items.map(|n| f(n).rem_euclid(12));
+            if reverse {
+                items.reverse();
+            }
+            Self { items }
+        };
+        match form {
+            RowForm::Prime(n) => map(&|pc| pc + n, false),
+            RowForm::Inversion(n) => map(&|pc| 2 * first - pc + n, false),
+            RowForm::Retrograde(n) => map(&|pc| pc + n, true),
+            RowForm::RetrogradeInversion(n) => map(&|pc| 2 * first - pc + n, true),
+        }
+    }
+
+    /// The row matrix: prime forms read left to right along the rows,
+    /// inversions top to bottom down the columns, with the row as given
+    /// along the top.
+    pub fn matrix(&self) -> [[i32; 12]; 12] {
+        let mut matrix = [[0; 12]; 12];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (self.items[j] + self.items[0] - self.items[i]).rem_euclid(12);
+            }
+        }
+        matrix
+    }
+
+    /// Rotates left by `n` places, so the pitch class at `n` comes first.
+    pub fn rotate(&self, n: usize) -> Self {
+        let mut items = self.items;
+        items.rotate_left(n % 12);
+        Self { items }
+    }
+
+    /// Multiplies every pitch class by `factor`, as in the M5 and M7
+    /// operations. Only factors coprime to 12 yield a row.
+    pub fn multiply(&self, factor: i32) -> Result<Self, Error> {
+        Self::from_numbers(&self.items.map(|n| n * factor))
+    }
+
+    /// The forms whose first hexachord is the complement of this row's first
+    /// hexachord. `Retrograde(0)` always qualifies and is included.
+    pub fn combinatorial_forms(&self) -> Vec<RowForm> {
+        let complement = self.first_hexachord().complement();
+        (0..12)
+            .flat_map(|n| {
+                [
+                    RowForm::Prime(n),
+                    RowForm::Inversion(n),
+                    RowForm::Retrograde(n),
+                    RowForm::RetrogradeInversion(n),
+                ]
+            })
+            .filter(|form| self.form(*form).first_hexachord() == complement)
+            .collect()
+    }
+
+    /// True when the row is combinatorial under both transposition and
+    /// inversion, and therefore (with their retrogrades) under all four
+    /// operations.
+    pub fn is_all_combinatorial(&self) -> bool {
+        let forms = self.combinatorial_forms();
+        forms.iter().any(|f| matches!(f, RowForm::Prime(_)))
+            && forms.iter().any(|f| matches!(f, RowForm::Inversion(_)))
+    }
+
+    /// Writes out a form of the row as notes, cycling through `octaves` and
+    /// `durations` independently; both must be non-empty.
+    pub fn realize(
+        &self,
+        form: RowForm,
+        octaves: &[i32],
+        durations: &[Duration],
+    ) -> Result<Vec<ScoreObject>, Error> {
+        if octaves.is_empty() {
+            return Err(Error::EmptyCycle("octaves"));
+        }
+        if durations.is_empty() {
+            return Err(Error::EmptyCycle("durations"));
+        }
+        self.form(form)
+            .pitch_classes()
+            .into_iter()
+            .enumerate()
+            .map(|(i, pitch_class)| {
+                note(
+                    Pitch::new(pitch_class, octaves[i % octaves.len()]),
+                    durations[i % durations.len()],
+                )
+            })
+            .collect()
+    }
+
+    fn first_hexachord(&self) -> PitchClassSet {
+        PitchClassSet::from_quarter_tones(self.items[..6].iter().map(|n| n * 2).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RowForm::*, TwelveToneRow};
+    use crate::duration::Duration;
+    use crate::error::Error;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::ScoreObject;
+
+    // Schoenberg, Op. 25.
+    fn suite() -> TwelveToneRow {
+        TwelveToneRow::from_numbers(&[4, 5, 7, 1, 6, 3, 8, 2, 11, 0, 9, 10]).unwrap()
+    }
+
+    #[test]
+    fn new() {
+        let pcs = [C, D, E, F, G, A, B]
+            .into_iter()
+            .map(|d| PitchClass::new(d, Natural))
+            .chain(
+                [C, D, F, G, A]
+                    .into_iter()
+                    .map(|d| PitchClass::new(d, Sharp)),
+            )
+            .collect();
+        assert!(TwelveToneRow::new(pcs).is_ok());
+
+        assert!(TwelveToneRow::from_numbers(&[0, 1, 2]).is_err());
+        assert!(matches!(
+            TwelveToneRow::from_numbers(&[0; 12]),
+            Err(Error::InvalidTwelveToneRow(numbers)) if numbers == [0; 12]
+        ));
+        assert!(matches!(
+            TwelveToneRow::new(vec![PitchClass::new(C, QuarterSharp); 12]),
+            Err(Error::QuarterToneInRow(_))
+        ));
+    }
+
+    #[test]
+    fn forms() {
+        let row = suite();
+        assert_eq!(row.form(Prime(0)), row);
+        assert_eq!(
+            row.form(Prime(2)).numbers(),
+            [6, 7, 9, 3, 8, 5, 10, 4, 1, 2, 11, 0]
+        );
+        assert_eq!(
+            row.form(Inversion(0)).numbers(),
+            [4, 3, 1, 7, 2, 5, 0, 6, 9, 8, 11, 10]
+        );
+        assert_eq!(
+            row.form(Retrograde(0)).numbers(),
+            [10, 9, 0, 11, 2, 8, 3, 6, 1, 7, 5, 4]
+        );
+        assert_eq!(
+            row.form(RetrogradeInversion(0)).numbers(),
+            [10, 11, 8, 9, 6, 0, 5, 2, 7, 1, 3, 4]
+        );
+    }
+
+    #[test]
+    fn matrix() {
+        let row = suite();
+        let matrix = row.matrix();
+        assert_eq!(matrix[0], row.numbers());
+        let column: Vec<i32> = matrix.iter().map(|r| r[0]).collect();
+        assert_eq!(column, row.form(Inversion(0)).numbers().to_vec());
+        for r in matrix.iter() {
+            assert!(TwelveToneRow::from_numbers(r).is_ok());
+        }
+    }
+
+    #[test]
+    fn rotate_and_multiply() {
+        let chromatic = TwelveToneRow::from_numbers(&(0..12).collect::<Vec<_>>()).unwrap();
+        assert_eq!(chromatic.rotate(3).numbers()[0], 3);
+        assert_eq!(
+            chromatic.multiply(5).unwrap().numbers(),
+            [0, 5, 10, 3, 8, 1, 6, 11, 4, 9, 2, 7]
+        );
+        assert_eq!(
+            chromatic.multiply(7).unwrap().numbers(),
+            [0, 7, 2, 9, 4, 11, 6, 1, 8, 3, 10, 5]
+        );
+        assert!(chromatic.multiply(2).is_err());
+    }
+
+    #[test]
+    fn combinatoriality() {
+        let chromatic = TwelveToneRow::from_numbers(&(0..12).collect::<Vec<_>>()).unwrap();
+        let forms = chromatic.combinatorial_forms();
+        assert!(forms.contains(&Prime(6)));
+        assert!(forms.contains(&Inversion(11)));
+        assert!(forms.contains(&Retrograde(0)));
+        assert!(chromatic.is_all_combinatorial());
+
+        // Berg, Violin Concerto: not hexachordally combinatorial.
+        let berg = TwelveToneRow::from_numbers(&[7, 10, 2, 6, 9, 0, 4, 8, 11, 1, 3, 5]).unwrap();
+        assert_eq!(berg.combinatorial_forms(), vec![Retrograde(0)]);
+        assert!(!berg.is_all_combinatorial());
+    }
+
+    #[test]
+    fn realize() {
+        let row = suite();
+        let notes = row
+            .realize(
+                Prime(0),
+                &[4, 5],
+                &[Duration::new(1, 8), Duration::new(1, 4)],
+            )
+            .unwrap();
+        assert_eq!(notes.len(), 12);
+        assert!(matches!(
+            notes[1],
+            ScoreObject::Note { written_pitch, .. }
+                if written_pitch == Pitch::new(PitchClass::new(F, Natural), 5)
+        ));
+        assert_eq!(notes[1].written_duration(), Some(Duration::new(1, 4)));
+
+        assert!(row.realize(Prime(0), &[], &[Duration::new(1, 8)]).is_err());
+        assert!(row.realize(Prime(0), &[4], &[]).is_err());
+    }
+}
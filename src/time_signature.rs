@@ -0,0 +1,70 @@
+use std::{fmt, str::FromStr};
+
+use crate::{
+    duration::{parse_fraction, Duration},
+    error::Error,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeSignature {
+    numerator: i32,
+    denominator: i32,
+}
+
+impl TimeSignature {
+    pub fn new(numerator: i32, denominator: i32) -> Self {
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    pub fn to_pair(&self) -> (i32, i32) {
+        (self.numerator, self.denominator)
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::new(self.numerator, self.denominator)
+    }
+}
+
+impl fmt::Display for TimeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+impl FromStr for TimeSignature {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (numerator, denominator) = parse_fraction(s)?;
+        Ok(Self::new(numerator, denominator))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimeSignature;
+    use crate::duration::Duration;
+
+    #[test]
+    fn new() {
+        assert_eq!(TimeSignature::new(6, 8).to_pair(), (6, 8));
+    }
+
+    #[test]
+    fn duration() {
+        assert_eq!(TimeSignature::new(6, 8).duration(), Duration::new(3, 4));
+        assert_eq!(TimeSignature::new(3, 4).duration(), Duration::new(3, 4));
+    }
+
+    #[test]
+    fn display_and_parse() {
+        assert_eq!(TimeSignature::new(6, 8).to_string(), "6/8");
+        assert_eq!(
+            "6/8".parse::<TimeSignature>().unwrap(),
+            TimeSignature::new(6, 8)
+        );
+    }
+}
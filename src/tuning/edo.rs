@@ -0,0 +1,73 @@
+use super::TunedPitch;
+use crate::error::Error;
+
+/// An equal division of the octave, such as 72-EDO.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Edo {
+    divisions: u32,
+}
+
+impl Edo {
+    pub fn new(divisions: u32) -> Result<Self, Error> {
+        match divisions {
+            0 => Err(Error::InvalidEdo(divisions)),
+            _ => Ok(Self { divisions }),
+        }
+    }
+
+    pub fn divisions(&self) -> u32 {
+        self.divisions
+    }
+
+    pub fn step_cents(&self) -> f64 {
+        1200. / self.divisions as f64
+    }
+
+    /// The pitch `steps` above middle C.
+    pub fn pitch(&self, steps: i32) -> TunedPitch {
+        TunedPitch::from_cents(steps as f64 * self.step_cents())
+    }
+
+    /// The step nearest a height `cents` above middle C.
+    pub fn nearest_step(&self, cents: f64) -> i32 {
+        (cents / self.step_cents()).round() as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Edo;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+
+    #[test]
+    fn seventy_two() {
+        let edo = Edo::new(72).unwrap();
+        assert!((edo.step_cents() - 16.666_666).abs() < 1e-5);
+
+        let twelfth_tone_sharp_c = edo.pitch(1);
+        assert_eq!(
+            twelfth_tone_sharp_c.pitch(),
+            Pitch::new(PitchClass::new(C, Natural), 4)
+        );
+        assert!((twelfth_tone_sharp_c.cents() - 16.666_666).abs() < 1e-5);
+
+        let sixth_tone_sharp_d = edo.pitch(22);
+        assert_eq!(
+            sixth_tone_sharp_d.pitch(),
+            Pitch::new(PitchClass::new(D, ThreeQuarterSharp), 4)
+        );
+        assert!((sixth_tone_sharp_d.cents() - 16.666_666).abs() < 1e-5);
+
+        assert_eq!(edo.nearest_step(386.3), 23);
+        assert_eq!(
+            edo.pitch(-72).pitch(),
+            Pitch::new(PitchClass::new(C, Natural), 3)
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(Edo::new(0).is_err());
+        assert_eq!(Edo::new(12).unwrap().pitch(7).cents(), 0.);
+    }
+}
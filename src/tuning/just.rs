@@ -0,0 +1,202 @@
+use super::TunedPitch;
+use crate::{
+    error::Error,
+    has_semitones::HasSemitones,
+    pitch::{Interval, Pitch},
+};
+
+/// Helmholtz-Ellis comma inflections, counted in steps up (positive) or down
+/// (negative): syntonic commas (81/80) for the 5-limit, septimal commas
+/// (64/63) for the 7-limit and undecimal quarter tones (33/32) for the
+/// 11-limit.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct HejiInflection {
+    pub syntonic: i32,
+    pub septimal: i32,
+    pub undecimal: i32,
+}
+
+impl HejiInflection {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A just ratio above a reference pitch, written in Helmholtz-Ellis
+/// notation: a Pythagorean spelling, comma inflections, and the remaining
+/// deviation from equal temperament in cents.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JustPitch {
+    tuned_pitch: TunedPitch,
+    inflection: HejiInflection,
+}
+
+impl JustPitch {
+    /// The pitch `numerator/denominator` above `reference`, which is taken to
+    /// sound at its equal-tempered height. Ratios must be positive and
+    /// 11-limit.
+    pub fn new(reference: Pitch, numerator: u64, denominator: u64) -> Result<Self, Error> {
+        let unsupported = || Error::UnsupportedRatio(numerator, denominator);
+        let mut exponents = [0i32; 5];
+        for (value, sign) in [(numerator, 1), (denominator, -1)] {
+            if value == 0 {
+                return Err(unsupported());
+            }
+            let mut value = value;
+            for (exponent, prime) in exponents.iter_mut().zip([2, 3, 5, 7, 11]) {
+                while value % prime == 0 {
+                    value /= prime;
+                    *exponent += sign;
+                }
+            }
+            if value != 1 {
+                return Err(unsupported());
+            }
+        }
+        let [twos, threes, fives, sevens, elevens] = exponents;
+
+        // Each prime's notated interval: octave, fifth, Pythagorean major
+        // third, Pythagorean minor seventh and perfect fourth.
+        let nominal = [
+            (twos, Interval::new(7, 24)),
+            (threes, Interval::new(4, 14)),
+            (fives, Interval::new(2, 8)),
+            (sevens, Interval::new(6, 20)),
+            (elevens, Interval::new(3, 10)),
+        ]
+        .iter()
+        .fold(Interval::new(0, 0), |sum, (exponent, interval)| {
+            Interval::new(
+                sum.staff_spaces() + exponent * interval.staff_spaces(),
+                sum.quarter_tones() + exponent * interval.quarter_tones(),
+            )
+        });
+        // Those intervals are the primes reduced by octaves (3/2, 5/4, 7/4,
+        // 11/8), so put the octaves back.
+        let octaves = threes + fives * 2 + sevens * 2 + elevens * 3;
+        let correction = Interval::new(7 * octaves, 24 * octaves);
+        let nominal = Interval::new(
+            nominal.staff_spaces() + correction.staff_spaces(),
+            nominal.quarter_tones() + correction.quarter_tones(),
+        );
+
+        let pitch = reference.transpose(&nominal);
+        let ratio_cents = 1200. * (numerator as f64 / denominator as f64).log2();
+        let nominal_cents = (pitch.quarter_tones() - reference.quarter_tones()) as f64 * 50.;
+        Ok(Self {
+            tuned_pitch: TunedPitch::new(pitch, ratio_cents - nominal_cents),
+            inflection: HejiInflection {
+                syntonic: -fives,
+                septimal: -sevens,
+                undecimal: elevens,
+            },
+        })
+    }
+
+    pub fn tuned_pitch(&self) -> TunedPitch {
+        self.tuned_pitch
+    }
+
+    pub fn pitch(&self) -> Pitch {
+        self.tuned_pitch.pitch()
+    }
+
+    pub fn cents(&self) -> f64 {
+        self.tuned_pitch.cents()
+    }
+
+    pub fn inflection(&self) -> HejiInflection {
+        self.inflection
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HejiInflection, JustPitch};
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+
+    fn c4() -> Pitch {
+        Pitch::new(PitchClass::new(C, Natural), 4)
+    }
+
+    fn assert_just(ratio: (u64, u64), pitch: Pitch, cents: f64, inflection: HejiInflection) {
+        let just = JustPitch::new(c4(), ratio.0, ratio.1).unwrap();
+        assert_eq!(just.pitch(), pitch, "{ratio:?}");
+        assert!(
+            (just.cents() - cents).abs() < 0.05,
+            "{ratio:?}: {}",
+            just.cents()
+        );
+        assert_eq!(just.inflection(), inflection, "{ratio:?}");
+    }
+
+    #[test]
+    fn prime_limits() {
+        let none = HejiInflection::default();
+        assert_just(
+            (3, 2),
+            Pitch::new(PitchClass::new(G, Natural), 4),
+            1.96,
+            none,
+        );
+        assert_just((2, 1), Pitch::new(PitchClass::new(C, Natural), 5), 0., none);
+        assert_just(
+            (4, 3),
+            Pitch::new(PitchClass::new(F, Natural), 4),
+            -1.96,
+            none,
+        );
+        assert_just(
+            (5, 4),
+            Pitch::new(PitchClass::new(E, Natural), 4),
+            -13.69,
+            HejiInflection {
+                syntonic: -1,
+                ..none
+            },
+        );
+        assert_just(
+            (6, 5),
+            Pitch::new(PitchClass::new(E, Flat), 4),
+            15.64,
+            HejiInflection {
+                syntonic: 1,
+                ..none
+            },
+        );
+        assert_just(
+            (7, 4),
+            Pitch::new(PitchClass::new(B, Flat), 4),
+            -31.17,
+            HejiInflection {
+                septimal: -1,
+                ..none
+            },
+        );
+        assert_just(
+            (11, 8),
+            Pitch::new(PitchClass::new(F, Natural), 4),
+            51.32,
+            HejiInflection {
+                undecimal: 1,
+                ..none
+            },
+        );
+        assert_just(
+            (15, 8),
+            Pitch::new(PitchClass::new(B, Natural), 4),
+            -11.73,
+            HejiInflection {
+                syntonic: -1,
+                ..none
+            },
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        assert!(JustPitch::new(c4(), 13, 8).is_err());
+        assert!(JustPitch::new(c4(), 0, 1).is_err());
+        assert!(JustPitch::new(c4(), 1, 0).is_err());
+    }
+}
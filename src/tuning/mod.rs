@@ -0,0 +1,108 @@
+//! Tunings finer than quarter tones. Every tuned pitch is written as the
+//! nearest notatable `Pitch` plus a deviation in cents, which playback can
+//! render as MIDI pitch bend.
+
+mod edo;
+mod just;
+
+pub use edo::Edo;
+pub use just::{HejiInflection, JustPitch};
+
+use crate::{has_semitones::HasSemitones, pitch::Pitch};
+
+/// The centre of the 14-bit MIDI pitch-bend range.
+const BEND_CENTER: f64 = 8192.;
+
+/// A written pitch and how far it sounds above (or below) its equal-tempered
+/// height, in cents.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TunedPitch {
+    pitch: Pitch,
+    cents: f64,
+}
+
+impl TunedPitch {
+    pub fn new(pitch: Pitch, cents: f64) -> Self {
+        Self { pitch, cents }
+    }
+
+    /// The pitch `cents` above middle C, written as the nearest quarter tone
+    /// spelled with sharps.
+    pub fn from_cents(cents: f64) -> Self {
+        let quarter_tones = (cents / 50.).round();
+        Self {
+            pitch: Pitch::from_quarter_tones(quarter_tones as i32),
+            cents: cents - quarter_tones * 50.,
+        }
+    }
+
+    pub fn pitch(&self) -> Pitch {
+        self.pitch
+    }
+
+    pub fn cents(&self) -> f64 {
+        self.cents
+    }
+
+    /// The sounding height above middle C, in cents.
+    pub fn cents_above_middle_c(&self) -> f64 {
+        self.pitch.quarter_tones() as f64 * 50. + self.cents
+    }
+
+    /// The frequency in hertz, given the frequency of the A above middle C.
+    pub fn frequency(&self, a4: f64) -> f64 {
+        a4 * 2f64.powf((self.cents_above_middle_c() - 900.) / 1200.)
+    }
+
+    /// The nearest MIDI note number (middle C is 60) and the 14-bit pitch bend
+    /// that reaches the exact height, for a synthesiser whose bend wheel spans
+    /// `bend_range` semitones either way. Bends beyond the range are clamped.
+    pub fn to_midi(&self, bend_range: f64) -> (i32, u16) {
+        let semitones = self.cents_above_middle_c() / 100.;
+        let note = semitones.round();
+        let bend = BEND_CENTER + (semitones - note) / bend_range * BEND_CENTER;
+        (note as i32 + 60, bend.round().clamp(0., 16383.) as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TunedPitch;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+
+    #[test]
+    fn from_cents() {
+        let tuned = TunedPitch::from_cents(386.3);
+        assert_eq!(tuned.pitch(), Pitch::new(PitchClass::new(E, Natural), 4));
+        assert!((tuned.cents() + 13.7).abs() < 1e-9);
+
+        let tuned = TunedPitch::from_cents(-1160.);
+        assert_eq!(
+            tuned.pitch(),
+            Pitch::new(PitchClass::new(C, QuarterSharp), 3)
+        );
+        assert!((tuned.cents() + 10.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn frequency() {
+        let a4 = TunedPitch::new(Pitch::new(PitchClass::new(A, Natural), 4), 0.);
+        assert!((a4.frequency(440.) - 440.).abs() < 1e-9);
+
+        let c5 = TunedPitch::new(Pitch::new(PitchClass::new(C, QuarterSharp), 5), -50.);
+        assert!((c5.frequency(440.) - 523.2511).abs() < 1e-3);
+    }
+
+    #[test]
+    fn to_midi() {
+        let a4 = TunedPitch::new(Pitch::new(PitchClass::new(A, Natural), 4), 0.);
+        assert_eq!(a4.to_midi(2.), (69, 8192));
+
+        let quarter_sharp = TunedPitch::new(Pitch::new(PitchClass::new(C, QuarterSharp), 4), 0.);
+        assert_eq!(quarter_sharp.to_midi(2.), (61, 6144));
+
+        let low = TunedPitch::new(Pitch::new(PitchClass::new(C, Natural), 4), -40.);
+        assert_eq!(low.to_midi(2.), (60, 6554));
+        assert_eq!(low.to_midi(0.1), (60, 0));
+    }
+}
@@ -0,0 +1,379 @@
+use std::collections::HashSet;
+
+use crate::{
+    duration::Duration, instrument::Instrument, multiplier::Multiplier, pitch::Pitch,
+    score_object::ScoreObject, time_signature::TimeSignature,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ViolationKind {
+    NonPositiveMultiplier(Multiplier),
+    EmptyTuplet,
+    EmptyChord,
+    UnprintableDuration(Duration),
+    MeasureDurationMismatch(TimeSignature, Duration),
+    DuplicateContextName(String),
+    MismatchedTie,
+    IllegalNesting(&'static str),
+    OutOfRange(Pitch),
+}
+
+/// A problem found by `check_wellformedness`, located by the chain of content
+/// indices leading from the checked object to the offending component.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    pub path: Vec<usize>,
+    pub kind: ViolationKind,
+}
+
+/// Reports every structural problem in `component` that the constructors
+/// would have rejected or that later mutation may have introduced. Ties are
+/// checked within each voice: a tied note or chord must be followed by a leaf
+/// with the same pitches. Written pitches are checked against the sounding
+/// range of the nearest enclosing voice or staff instrument.
+pub fn check_wellformedness(component: &ScoreObject) -> Vec<Violation> {
+    let mut violations = vec![];
+    let mut names = HashSet::new();
+    let mut voices = vec![];
+    check_component(
+        component,
+        &mut vec![],
+        &mut violations,
+        &mut names,
+        &mut voices,
+        None,
+        false,
+    );
+
+    if voices.is_empty() {
+        voices.push(vec![]);
+    }
+    for voice_path in voices {
+        check_ties(component, voice_path, &mut violations);
+    }
+
+    violations
+}
+
+fn check_component(
+    component: &ScoreObject,
+    path: &mut Vec<usize>,
+    violations: &mut Vec<Violation>,
+    names: &mut HashSet<(&'static str, String)>,
+    voices: &mut Vec<Vec<usize>>,
+    instrument: Option<&Instrument>,
+    is_concert_pitch: bool,
+) {
+    let instrument = component.instrument().or(instrument);
+    let is_concert_pitch = is_concert_pitch || component.is_concert_pitch();
+    let mut report = |kind| {
+        violations.push(Violation {
+            path: path.clone(),
+            kind,
+        })
+    };
+
+    if let Some(written_duration) = component.written_duration() {
+        if !written_duration.is_printable() {
+            report(ViolationKind::UnprintableDuration(written_duration));
+        }
+    }
+
+    match component {
+        ScoreObject::Chord {
+            written_pitches, ..
+        } if written_pitches.is_empty() => report(ViolationKind::EmptyChord),
+        ScoreObject::Tuplet {
+            multiplier,
+            contents,
+            ..
+        } => {
+            let (numerator, denominator) = multiplier.to_pair();
+            if numerator <= 0 || denominator <= 0 {
+                report(ViolationKind::NonPositiveMultiplier(*multiplier));
+            }
+            if contents.is_empty() {
+                report(ViolationKind::EmptyTuplet);
+            }
+        }
+        ScoreObject::Measure { time_signature, .. } => {
+            let duration = component.duration();
+            if duration != time_signature.duration() {
+                report(ViolationKind::MeasureDurationMismatch(
+                    *time_signature,
+                    duration,
+                ));
+            }
+        }
+        ScoreObject::Voice { .. } => voices.push(path.clone()),
+        _ => (),
+    }
+
+    if let (Some(instrument), Some(pitches)) = (instrument, pitches(component)) {
+        for pitch in pitches {
+            let sounding = match is_concert_pitch {
+                true => pitch,
+                false => pitch.transpose(&instrument.transposition()),
+            };
+            if !instrument.contains(&sounding) {
+                report(ViolationKind::OutOfRange(pitch));
+            }
+        }
+    }
+
+    if let Some(name) = component.name() {
+        if !names.insert((component.kind_name(), name.clone())) {
+            report(ViolationKind::DuplicateContextName(name.clone()));
+        }
+    }
+
+    if let Some(contents) = component.contents() {
+        for (index, child) in contents.iter().enumerate() {
+            path.push(index);
+            if !component.can_contain(child) {
+                violations.push(Violation {
+                    path: path.clone(),
+                    kind: ViolationKind::IllegalNesting(component.kind_name()),
+                });
+            }
+            check_component(
+                child,
+                path,
+                violations,
+                names,
+                voices,
+                instrument,
+                is_concert_pitch,
+            );
+            path.pop();
+        }
+    }
+}
+
+fn check_ties(root: &ScoreObject, voice_path: Vec<usize>, violations: &mut Vec<Violation>) {
+    let mut voice = root;
+    for index in voice_path.iter() {
+        voice = &voice[*index];
+    }
+
+    let mut leaves = vec![];
+    collect_leaf_paths(voice, &mut voice_path.clone(), &mut leaves);
+
+    for (position, (path, leaf)) in leaves.iter().enumerate() {
+        if !leaf.is_tied() {
+            continue;
+        }
+        let continues = leaves
+            .get(position + 1)
+            .is_some_and(|(_, next)| pitches(leaf) == pitches(next));
+        if !continues {
+            violations.push(Violation {
+                path: path.clone(),
+                kind: ViolationKind::MismatchedTie,
+            });
+        }
+    }
+}
+
+fn collect_leaf_paths<'a>(
+    component: &'a ScoreObject,
+    path: &mut Vec<usize>,
+    leaves: &mut Vec<(Vec<usize>, &'a ScoreObject)>,
+) {
+    match component.contents() {
+        Some(contents) => {
+            for (index, child) in contents.iter().enumerate() {
+                if matches!(child, ScoreObject::Voice { .. }) {
+                    continue;
+                }
+                path.push(index);
+                collect_leaf_paths(child, path, leaves);
+                path.pop();
+            }
+        }
+        None => leaves.push((path.clone(), component)),
+    }
+}
+
+fn pitches(leaf: &ScoreObject) -> Option<Vec<Pitch>> {
+    match leaf {
+        ScoreObject::Note { written_pitch, .. } => Some(vec![*written_pitch]),
+        ScoreObject::Chord {
+            written_pitches, ..
+        } => Some(written_pitches.to_vec()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_wellformedness, Violation, ViolationKind};
+    use crate::duration::Duration;
+    use crate::instrument::Instrument;
+    use crate::multiplier::Multiplier;
+    use crate::pitch::{Accidental::*, DiatonicPitchClass::*, Pitch, PitchClass};
+    use crate::score_object::{
+        chord, measure, note, rest, score, staff, tuplet, voice, ScoreObject,
+    };
+    use crate::time_signature::TimeSignature;
+
+    fn pitch(dpc: crate::pitch::DiatonicPitchClass) -> Pitch {
+        Pitch::new(PitchClass::new(dpc, Natural), 4)
+    }
+
+    fn kinds(component: &ScoreObject) -> Vec<ViolationKind> {
+        check_wellformedness(component)
+            .into_iter()
+            .map(|violation| violation.kind)
+            .collect()
+    }
+
+    #[test]
+    fn wellformed() {
+        let mut tied = note(pitch(C), Duration::new(1, 4)).unwrap();
+        tied.set_is_tied(true);
+        let m = measure(
+            TimeSignature::new(2, 4),
+            vec![tied, note(pitch(C), Duration::new(1, 4)).unwrap()],
+        )
+        .unwrap();
+        let s = score(vec![staff(vec![voice(vec![m]).unwrap()]).unwrap()]).unwrap();
+
+        assert!(check_wellformedness(&s).is_empty());
+    }
+
+    #[test]
+    fn tuplet_violations() {
+        let mut t = tuplet(Multiplier::new(0, 1), vec![]).unwrap();
+        assert_eq!(
+            kinds(&t),
+            vec![
+                ViolationKind::NonPositiveMultiplier(Multiplier::new(0, 1)),
+                ViolationKind::EmptyTuplet
+            ]
+        );
+
+        let negative = ScoreObject::Tuplet {
+            multiplier: Multiplier::new(-2, 3),
+            display_ratio: None,
+            contents: Box::new(vec![ScoreObject::Rest {
+                written_duration: Duration::new(1, 4),
+            }]),
+        };
+        assert_eq!(
+            kinds(&negative),
+            vec![ViolationKind::NonPositiveMultiplier(Multiplier::new(-2, 3))]
+        );
+
+        t.contents_mut().unwrap().push(ScoreObject::Rest {
+            written_duration: Duration::new(1, 3),
+        });
+        assert_eq!(
+            check_wellformedness(&t)[1],
+            Violation {
+                path: vec![0],
+                kind: ViolationKind::UnprintableDuration(Duration::new(1, 3))
+            }
+        );
+    }
+
+    #[test]
+    fn measure_and_chord_violations() {
+        let mut m = measure(
+            TimeSignature::new(2, 4),
+            vec![chord(vec![], Duration::new(1, 2)).unwrap()],
+        )
+        .unwrap();
+        m.contents_mut()
+            .unwrap()
+            .push(rest(Duration::new(1, 4)).unwrap());
+
+        assert_eq!(
+            kinds(&m),
+            vec![
+                ViolationKind::MeasureDurationMismatch(
+                    TimeSignature::new(2, 4),
+                    Duration::new(3, 4)
+                ),
+                ViolationKind::EmptyChord
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_names_and_nesting() {
+        let mut v1 = voice(vec![]).unwrap();
+        v1.set_name(Some("Voice".to_string()));
+        let v2 = v1.clone();
+        let mut s = staff(vec![v1, v2]).unwrap();
+        s.contents_mut().unwrap().push(score(vec![]).unwrap());
+
+        assert_eq!(
+            check_wellformedness(&s),
+            vec![
+                Violation {
+                    path: vec![1],
+                    kind: ViolationKind::DuplicateContextName("Voice".to_string())
+                },
+                Violation {
+                    path: vec![2],
+                    kind: ViolationKind::IllegalNesting("Staff")
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        let low = Pitch::new(PitchClass::new(E, Natural), 3);
+        let mut v = voice(vec![
+            note(low, Duration::new(1, 4)).unwrap(),
+            chord(vec![low, pitch(C)], Duration::new(1, 4)).unwrap(),
+        ])
+        .unwrap();
+        let mut s = staff(vec![v.clone()]).unwrap();
+        s.set_instrument(Some(Instrument::violin()));
+        assert_eq!(
+            check_wellformedness(&s),
+            vec![
+                Violation {
+                    path: vec![0, 0],
+                    kind: ViolationKind::OutOfRange(low)
+                },
+                Violation {
+                    path: vec![0, 1],
+                    kind: ViolationKind::OutOfRange(low)
+                }
+            ]
+        );
+
+        // Written E3 on a B-flat clarinet sounds D3, its lowest note.
+        v.set_instrument(Some(Instrument::clarinet_in_b_flat()));
+        s.contents_mut().unwrap()[0] = v;
+        assert!(check_wellformedness(&s).is_empty());
+
+        // At concert pitch the D3 is checked as written.
+        s.to_concert_pitch();
+        assert!(check_wellformedness(&s).is_empty());
+    }
+
+    #[test]
+    fn mismatched_ties() {
+        let mut tied = note(pitch(C), Duration::new(1, 4)).unwrap();
+        tied.set_is_tied(true);
+        let v = voice(vec![
+            tied.clone(),
+            note(pitch(D), Duration::new(1, 4)).unwrap(),
+            tied,
+        ])
+        .unwrap();
+
+        let violations = check_wellformedness(&v);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].path, vec![0]);
+        assert_eq!(violations[1].path, vec![2]);
+        assert!(violations
+            .iter()
+            .all(|violation| violation.kind == ViolationKind::MismatchedTie));
+    }
+}